@@ -0,0 +1,53 @@
+#!/bin/sh
+# Vendor the unmodified SpaceTraders OpenAPI spec, its models and its example responses into this directory.
+#
+# Usage: fixtures/openapi/vendor.sh [git ref of SpaceTradersAPI/api-docs, main by default]
+#
+# The spec and models are copied as is, keeping the layout of the upstream repository so their `$ref`s resolve.
+# The example response of each operation is written to `responses/<operationId>.json`; operations without an
+# example keep their current file. Needs curl, tar and python3. Run `cargo test conformance` afterwards.
+set -eu
+
+ref="${1:-main}"
+dir="$(cd "$(dirname "$0")" && pwd)"
+tmp="$(mktemp -d)"
+trap 'rm -rf "$tmp"' EXIT
+
+curl -sSfL "https://github.com/SpaceTradersAPI/api-docs/archive/$ref.tar.gz" | tar -xz -C "$tmp" --strip-components=1
+
+rm -rf "$dir/reference" "$dir/models"
+cp -R "$tmp/reference" "$dir/reference"
+cp -R "$tmp/models" "$dir/models"
+
+python3 - "$dir" <<'EOF'
+import json, os, sys
+
+directory = sys.argv[1]
+with open(os.path.join(directory, "reference", "SpaceTraders.json")) as file:
+    spec = json.load(file)
+
+missing = []
+for path, operations in spec.get("paths", {}).items():
+    for method, operation in operations.items():
+        if not isinstance(operation, dict) or "operationId" not in operation:
+            continue
+        example = None
+        for status, response in sorted(operation.get("responses", {}).items()):
+            if not status.startswith("2"):
+                continue
+            content = response.get("content", {}).get("application/json", {})
+            example = content.get("example")
+            if example is None and content.get("examples"):
+                example = next(iter(content["examples"].values())).get("value")
+            if example is not None:
+                break
+        if example is None:
+            missing.append(operation["operationId"])
+            continue
+        with open(os.path.join(directory, "responses", operation["operationId"] + ".json"), "w") as file:
+            json.dump(example, file, indent=2)
+            file.write("\n")
+
+if missing:
+    print("no example response for: " + ", ".join(missing), file=sys.stderr)
+EOF
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 
 use crate::{
+    api::fleet::ShipUpdate,
     client::{
         Authenticated,
         SpaceTradersClient
@@ -15,7 +16,7 @@ use crate::{
     error::server_error::SpaceTraderError,
     schemas::{
         contract::Contract,
-        meta::Meta, agent::Agent, ship::ship_cargo::ShipCargo
+        meta::Meta, agent::Agent, ship::{Ship, ship_cargo::ShipCargo}
     }
 };
 
@@ -34,6 +35,12 @@ pub struct ContractAndCargo {
 }
 
 
+impl ShipUpdate for ContractAndCargo {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cargo = self.cargo.clone();
+    }
+}
+
 impl SpaceTradersClient<Authenticated> {
     /// Return a paginated list of all your contracts.
     pub async fn list_contracts(&self, page_limit: Option<u64>, page_index: Option<u64>) -> Result<(Vec<Contract>, Meta), crate::error::Error> {
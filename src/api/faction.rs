@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use std::fmt::Debug;
+
+use serde::{Deserialize, de::DeserializeOwned};
 
 use crate::{
     client::{
@@ -42,6 +44,11 @@ impl SpaceTradersClient<Authenticated> {
 
     /// View the details of a faction.
     pub async fn get_faction(&self, faction_symbol: &str) -> Result<Faction, crate::error::Error> {
+        self.get_faction_as(faction_symbol).await
+    }
+
+    /// [`Self::get_faction`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_faction_as<T: DeserializeOwned + Debug + Clone>(&self, faction_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("factions/{faction_symbol}"))
             .send()
             .await?;
@@ -50,7 +57,7 @@ impl SpaceTradersClient<Authenticated> {
                 let json = response
                     .json::<serde_json::Value>()
                     .await?;
-                Ok(<DataWrapper::<Faction>>::deserialize(json)?.inner())
+                Ok(<DataWrapper::<T>>::deserialize(json)?.inner())
             }
             status => {
                 let json = response
@@ -90,6 +97,11 @@ impl SpaceTradersClient<Anonymous> {
 
     /// View the details of a faction.
     pub async fn get_faction(&self, faction_symbol: &str) -> Result<Faction, crate::error::Error> {
+        self.get_faction_as(faction_symbol).await
+    }
+
+    /// [`Self::get_faction`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_faction_as<T: DeserializeOwned + Debug + Clone>(&self, faction_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("factions/{faction_symbol}"))
             .send()
             .await?;
@@ -98,7 +110,7 @@ impl SpaceTradersClient<Anonymous> {
                 let json = response
                     .json::<serde_json::Value>()
                     .await?;
-                Ok(<DataWrapper::<Faction>>::deserialize(json)?.inner())
+                Ok(<DataWrapper::<T>>::deserialize(json)?.inner())
             }
             status => {
                 let json = response
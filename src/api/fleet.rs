@@ -8,6 +8,8 @@ pub mod refining;
 pub mod refuel;
 pub mod resources;
 pub mod survey;
+pub mod trade;
+pub mod travel;
 pub mod scan;
 
 use serde::Deserialize;
@@ -21,6 +23,29 @@ use crate::{
     schemas::{ship::Ship, meta::Meta, contract::Contract}
 };
 
+/// Wrapper arround the contract scheme: { "contract": {...} }
+/// This is returned when negotiating a contract.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractWrapper {
+    pub contract: Contract,
+}
+
+/// The result of an action that changes the state of a ship.
+///
+/// Applying it to a local copy of the ship keeps it consistent with the server without fetching the ship again.
+pub trait ShipUpdate {
+    /// Update the ship with the state returned by the server.
+    fn apply_to(&self, ship: &mut Ship);
+}
+
+impl Ship {
+    /// Apply the result of an action on this ship, see [`ShipUpdate`].
+    pub fn apply<U: ShipUpdate + ?Sized>(&mut self, update: &U) {
+        update.apply_to(self);
+    }
+}
+
 impl SpaceTradersClient<Authenticated> {
     /// Return a paginated list of all of ships under your agent's ownership.
     pub async fn list_ships(&self, page_limit: Option<u64>, page_index: Option<u64>) -> Result<(Vec<Ship>, Meta), crate::error::Error> {
@@ -76,7 +101,8 @@ impl SpaceTradersClient<Authenticated> {
     /// 
     /// The ship must be present at any waypoint with a faction present to negotiate a contract with that faction.
     pub async fn negotiate_contract(&self, ship_symbol: &str) -> Result<Contract, crate::error::Error> {
-        let response = self.get(&format!("my/ships/{ship_symbol}/negotiate/contract"))
+        let response = self.post(&format!("my/ships/{ship_symbol}/negotiate/contract"))
+            .header("content-length", 0)
             .send()
             .await?;
         match response.status().as_u16() {
@@ -84,7 +110,7 @@ impl SpaceTradersClient<Authenticated> {
                 let json = response
                     .json::<serde_json::Value>()
                     .await?;
-                Ok(<DataWrapper::<Contract>>::deserialize(json)?.inner())
+                Ok(<DataWrapper::<ContractWrapper>>::deserialize(json)?.inner().contract)
             }
             status => {
                 let json = response
@@ -96,4 +122,51 @@ impl SpaceTradersClient<Authenticated> {
         }
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        conformance::fixture_data,
+        schemas::{cooldown::Cooldown, ship::{Ship, ship_mount::ShipMountType, ship_nav::ship_nav_status::ShipNavStatus}, trade_symbol::TradeSymbol},
+    };
+    use super::{
+        nav::NavResult,
+        refuel::RefuelShipResponse,
+        resources::ExtractResourcesResponse,
+        mounts::MountInstallationResult,
+        cargo::CagoTransactionResult,
+    };
+
+    #[test]
+    fn apply_results() {
+        // docked at X1-MH34-A1 with a full tank, off cooldown
+        let mut ship: Ship = fixture_data("get-my-ship");
+        assert_eq!(ship.nav.waypoint_symbol, "X1-MH34-A1");
+
+        ship.apply(&fixture_data::<NavResult>("navigate-ship"));
+        assert_eq!(ship.nav.waypoint_symbol, "X1-MH34-B13");
+        assert_eq!(ship.nav.status, ShipNavStatus::InTransit);
+        assert_eq!(ship.fuel.current, 362);
+
+        ship.apply(&fixture_data::<RefuelShipResponse>("refuel-ship"));
+        assert_eq!(ship.fuel.current, 400);
+
+        ship.apply(&fixture_data::<ExtractResourcesResponse>("extract-resources"));
+        assert_eq!(ship.cargo.units, 12);
+        assert_eq!(ship.cargo.quantity_of(TradeSymbol::AluminumOre), 8);
+        assert_eq!(ship.cooldown.total_seconds, 70);
+        assert_eq!(ship.cooldown.remaining_seconds, 70);
+
+        ship.apply(&fixture_data::<MountInstallationResult>("install-mount"));
+        assert_eq!(ship.mounts.len(), 4);
+        assert!(ship.mounts.iter().any(|mount| mount.symbol == ShipMountType::MountMiningLaserIi));
+
+        ship.apply(&fixture_data::<CagoTransactionResult>("purchase-cargo"));
+        assert_eq!(ship.cargo.quantity_of(TradeSymbol::IceWater), 4);
+
+        ship.apply(&None::<Cooldown>);
+        assert_eq!(ship.cooldown.remaining_seconds, 0);
+        assert_eq!(ship.cooldown.expiration, None);
+    }
+}
@@ -3,11 +3,12 @@ use std::num::{NonZeroU64, NonZeroU32};
 
 use serde::Deserialize;
 use crate::{
+    api::fleet::ShipUpdate,
     client::{Authenticated, SpaceTradersClient},
     utils::wrapper::{DataWrapper, ErrorWrapper},
     error::server_error::SpaceTraderError,
     schemas::{
-        ship::ship_cargo::ShipCargo,
+        ship::{Ship, ship_cargo::ShipCargo},
         trade_symbol::TradeSymbol,
         agent::Agent,
         market::market_transaction::MarketTransaction
@@ -25,6 +26,33 @@ pub struct CagoTransactionResult {
     pub transaction: MarketTransaction,
 }
 
+/// Wrapper arround the cargo scheme: { "cargo": {...} }
+/// This is returned when jettisoning or transfering cargo.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CargoWrapper {
+    pub cargo: ShipCargo,
+}
+
+impl ShipUpdate for CagoTransactionResult {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cargo = self.cargo.clone();
+    }
+}
+
+impl ShipUpdate for CargoWrapper {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cargo = self.cargo.clone();
+    }
+}
+
+/// The cargo of the ship the request was made for: for a transfer, this is the cargo of the sending ship.
+impl ShipUpdate for ShipCargo {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cargo = self.clone();
+    }
+}
+
 impl SpaceTradersClient<Authenticated> {
     /// Retrieve the cargo of a ship under your agent's ownership.
     pub async fn get_ship_cargo(&self, ship_symbol: &str) -> Result<ShipCargo, crate::error::Error> {
@@ -63,7 +91,7 @@ impl SpaceTradersClient<Authenticated> {
                 let json = response
                     .json::<serde_json::Value>()
                     .await?;
-                Ok(<DataWrapper::<ShipCargo>>::deserialize(json)?.inner())
+                Ok(<DataWrapper::<CargoWrapper>>::deserialize(json)?.inner().cargo)
             }
             status => {
                 let json = response
@@ -114,7 +142,7 @@ impl SpaceTradersClient<Authenticated> {
             "symbol": to_purchase_symbol,
             "units": purchase_amount,
         });
-        let response = self.get(&format!("my/ships/{ship_symbol}/purchase"))
+        let response = self.post(&format!("my/ships/{ship_symbol}/purchase"))
             .json(&body)
             .send()
             .await?;
@@ -146,7 +174,7 @@ impl SpaceTradersClient<Authenticated> {
             "units": transfer_amount,
             "shipSymbol": to_ship_symbol,
         });
-        let response = self.get(&format!("my/ships/{from_ship_symbol}/transfer"))
+        let response = self.post(&format!("my/ships/{from_ship_symbol}/transfer"))
             .json(&body)
             .send()
             .await?;
@@ -155,7 +183,7 @@ impl SpaceTradersClient<Authenticated> {
                 let json = response
                     .json::<serde_json::Value>()
                     .await?;
-                Ok(<DataWrapper::<ShipCargo>>::deserialize(json)?.inner())
+                Ok(<DataWrapper::<CargoWrapper>>::deserialize(json)?.inner().cargo)
             }
             status => {
                 let json = response
@@ -1,7 +1,9 @@
 use serde::Deserialize;
 use crate::{
+    api::fleet::ShipUpdate,
     schemas::{
         chart::Chart,
+        ship::Ship,
         waypoint::Waypoint
     },
     client::{
@@ -21,6 +23,11 @@ pub struct ChartAndWaypoint {
     pub waypoint: Waypoint,
 }
 
+/// Charting does not change the state of the ship.
+impl ShipUpdate for ChartAndWaypoint {
+    fn apply_to(&self, _ship: &mut Ship) {}
+}
+
 impl SpaceTradersClient<Authenticated> {
     /// Command a ship to chart the waypoint at its current location.
     /// 
@@ -1,12 +1,32 @@
 use serde::Deserialize;
 use crate::{
+    api::fleet::ShipUpdate,
     client::Authenticated,
     utils::wrapper::{DataWrapper, ErrorWrapper},
     error::server_error::SpaceTraderError,
-    schemas::cooldown::Cooldown
+    schemas::{cooldown::Cooldown, ship::Ship}
 };
 
 
+impl ShipUpdate for Cooldown {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cooldown = self.clone();
+    }
+}
+
+impl ShipUpdate for Option<Cooldown> {
+    fn apply_to(&self, ship: &mut Ship) {
+        match self {
+            Some(cooldown) => ship.cooldown = cooldown.clone(),
+            // no cooldown means the ship is not on cooldown anymore
+            None => {
+                ship.cooldown.remaining_seconds = 0;
+                ship.cooldown.expiration = None;
+            }
+        }
+    }
+}
+
 impl crate::client::SpaceTradersClient<Authenticated> {
     /// Retrieve the details of your ship's reactor cooldown. Some actions such as activating your jump drive, scanning, or extracting resources taxes your reactor and results in a cooldown.
     /// 
@@ -34,6 +54,19 @@ impl crate::client::SpaceTradersClient<Authenticated> {
             }
         }
     }
-}
-
 
+    /// Wait until the ship is neither on cooldown nor in transit, so that the next action can be issued.
+    ///
+    /// The ship's cooldown and navigation are fetched from the server before waiting.
+    pub async fn wait_until_ship_ready(&self, ship_symbol: &str) -> Result<(), crate::error::Error> {
+        let nav = self.get_ship_nav(ship_symbol).await?;
+        let cooldown = self.get_ship_cooldown(ship_symbol).await?;
+        let now = chrono::Utc::now();
+        let remaining = cooldown
+            .map(|cooldown| cooldown.remaining(now))
+            .unwrap_or_default()
+            .max(nav.remaining(now));
+        tokio::time::sleep(remaining).await;
+        Ok(())
+    }
+}
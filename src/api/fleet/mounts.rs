@@ -1,12 +1,14 @@
 use serde::Deserialize;
 use crate::{
+    api::fleet::ShipUpdate,
     schemas::{
         ship::{
+            Ship,
             ship_mount::{ShipMount, ShipMountType},
-            ship_cargo::ShipCargo
+            ship_cargo::ShipCargo,
+            ship_modification_transaction::ShipModificationTransaction
         },
-        agent::Agent,
-        market::market_transaction::MarketTransaction
+        agent::Agent
     },
     client::{
         SpaceTradersClient,
@@ -27,7 +29,20 @@ pub struct MountInstallationResult {
     /// Ship cargo details.
     pub cargo: ShipCargo,
     /// Result of a transaction for a ship modification, such as installing a mount or a module.
-    pub transaction: MarketTransaction,
+    pub transaction: ShipModificationTransaction,
+}
+
+impl ShipUpdate for MountInstallationResult {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.mounts = self.mounts.clone();
+        ship.cargo = self.cargo.clone();
+    }
+}
+
+impl ShipUpdate for Vec<ShipMount> {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.mounts = self.clone();
+    }
 }
 
 impl SpaceTradersClient<Authenticated> {
@@ -1,10 +1,11 @@
 use serde::Deserialize;
 use crate::{
+    api::fleet::ShipUpdate,
     client::{Authenticated, SpaceTradersClient},
     utils::wrapper::{DataWrapper, ErrorWrapper},
     error::server_error::SpaceTraderError,
     schemas::{
-        ship::{ship_nav::{ShipNav, ship_nav_flight_mode::ShipNavFlightMode}, ship_fuel::ShipFuel},
+        ship::{Ship, ship_nav::{ShipNav, ship_nav_flight_mode::ShipNavFlightMode}, ship_fuel::ShipFuel},
         cooldown::Cooldown,
         market::market_transaction::MarketTransaction
     },
@@ -40,6 +41,32 @@ pub struct NavWrapper {
     pub nav: ShipNav,
 }
 
+impl ShipUpdate for JumpResult {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.nav = self.nav.clone();
+        ship.cooldown = self.cooldown.clone();
+    }
+}
+
+impl ShipUpdate for NavResult {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.nav = self.nav.clone();
+        ship.fuel = self.fuel.clone();
+    }
+}
+
+impl ShipUpdate for NavWrapper {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.nav = self.nav.clone();
+    }
+}
+
+impl ShipUpdate for ShipNav {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.nav = self.clone();
+    }
+}
+
 impl SpaceTradersClient<Authenticated> {
     /// Attempt to move your ship into orbit at its current location. The request will only succeed if your ship is capable of moving into orbit at the time of the request.
     /// 
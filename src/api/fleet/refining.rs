@@ -1,28 +1,40 @@
 use serde::{Serialize, Deserialize};
 use crate::{
+    api::fleet::ShipUpdate,
     client::Authenticated,
     utils::wrapper::{DataWrapper, ErrorWrapper},
     error::server_error::SpaceTraderError,
     schemas::{
-        ship::ship_cargo::ShipCargo,
+        ship::{ship_cargo::ShipCargo, Ship},
         cooldown::Cooldown,
-        extraction::extraction_yield::ExtractionYield,
         trade_symbol::TradeSymbol
     },
 };
 
+/// A good produced or consumed by a refining operation.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct RefinedGood {
+    /// Symbol of the good.
+    pub trade_symbol: TradeSymbol,
+    /// The number of units produced or consumed.
+    pub units: i64,
+}
+
+/// Wrapper around a cargo, cooldown and the goods produced and consumed by the refining.
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RefiningResult {
+    /// Ship cargo details.
     pub cargo: ShipCargo,
+    /// A cooldown is a period of time in which a ship cannot perform certain actions.
     pub cooldown: Cooldown,
-    pub produced: Vec<ExtractionYield>,
-    pub consumed: Vec<ExtractionYield>,
+    /// Goods that were added to the cargo.
+    pub produced: Vec<RefinedGood>,
+    /// Goods that were removed from the cargo.
+    pub consumed: Vec<RefinedGood>,
 }
 
-// TODO: we use ExtractionYield because it's what we will receive, but better name ?
-// also ExtractionYield is the same as SiphonYield ?
-
 /// Subset of the trade symbol that can be produced from ship refinig.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -38,9 +50,9 @@ pub enum FromRefinedTradeSymbol {
     Fuel,
 }
 
-impl Into<TradeSymbol> for FromRefinedTradeSymbol {
-    fn into(self) -> TradeSymbol {
-        match self {
+impl From<FromRefinedTradeSymbol> for TradeSymbol {
+    fn from(value: FromRefinedTradeSymbol) -> Self {
+        match value {
             FromRefinedTradeSymbol::Iron => TradeSymbol::Iron,
             FromRefinedTradeSymbol::Copper => TradeSymbol::Copper,
             FromRefinedTradeSymbol::Silver => TradeSymbol::Silver,
@@ -73,6 +85,13 @@ impl TryFrom<TradeSymbol> for FromRefinedTradeSymbol {
     }
 }
 
+impl ShipUpdate for RefiningResult {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cooldown = self.cooldown.clone();
+        ship.cargo = self.cargo.clone();
+    }
+}
+
 impl crate::client::SpaceTradersClient<Authenticated> {
     /// Attempt to refine the raw materials on your ship. The request will only succeed if your ship is capable of refining at the time of the request. In order to be able to refine, a ship must have goods that can be refined and have installed a Refinery module that can refine it.
     ///
@@ -101,4 +120,11 @@ impl crate::client::SpaceTradersClient<Authenticated> {
             }
         }
     }
-}
\ No newline at end of file
+
+    /// Same as [`Self::ship_refine`], but first checks that the ship has a refinery able to produce the requested good.
+    /// Returns [`crate::error::Error::MissingCapability`] without sending the request otherwise.
+    pub async fn ship_refine_checked(&self, ship: &Ship, refine_into: FromRefinedTradeSymbol) -> Result<RefiningResult, crate::error::Error> {
+        ship.capabilities().check_refine_into(refine_into.into())?;
+        self.ship_refine(&ship.symbol, refine_into).await
+    }
+}
@@ -3,18 +3,19 @@ use std::num::NonZeroU64;
 
 use serde::{Serialize, Deserialize};
 use crate::{
+    api::fleet::ShipUpdate,
     client::{Authenticated, SpaceTradersClient},
     utils::wrapper::{DataWrapper, ErrorWrapper},
     error::server_error::SpaceTraderError,
     schemas::{
-        ship::ship_fuel::ShipFuel,
+        ship::{Ship, ship_fuel::ShipFuel},
         agent::Agent,
         market::market_transaction::MarketTransaction
     }
 };
 
 /// Struct about how to refuel the ship.
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct RefuelShipInfo {
     /// The amount of fuel to fill in the ship's tanks. When not specified, the ship will be refueled to its maximum fuel capacity. If the amount specified is greater than the ship's remaining capacity, the ship will only be refueled to its maximum fuel capacity. The amount specified is not in market units but in ship fuel units.
@@ -23,12 +24,6 @@ pub struct RefuelShipInfo {
     pub from_cargo: Option<bool>,
 }
 
-impl Default for RefuelShipInfo {
-    fn default() -> Self {
-        RefuelShipInfo { units: None, from_cargo: None }
-    }
-}
-
 /// Struct about how to refuel the ship.
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -41,6 +36,13 @@ pub struct RefuelShipResponse {
     pub transaction: MarketTransaction,
 }
 
+/// When refueling from the cargo, the fuel units taken from the cargo are not reported: fetch the cargo again in that case.
+impl ShipUpdate for RefuelShipResponse {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.fuel = self.fuel.clone();
+    }
+}
+
 impl SpaceTradersClient<Authenticated> {
     /// Refuel your ship by buying fuel from the local market.
     /// 
@@ -1,13 +1,15 @@
 use serde::Deserialize;
 use crate::{
+    api::fleet::ShipUpdate,
     client::{Authenticated, SpaceTradersClient},
     utils::wrapper::{DataWrapper, ErrorWrapper},
     error::server_error::SpaceTraderError,
     schemas::{
-        ship::ship_cargo::ShipCargo,
+        ship::{ship_cargo::ShipCargo, Ship},
         survey::Survey,
         cooldown::Cooldown,
-        extraction::extraction_yield::ExtractionYield, siphon::siphon_yield::SiphonYield
+        extraction::Extraction,
+        siphon::Siphon
     },
 };
 
@@ -19,7 +21,7 @@ pub struct ExtractResourcesResponse {
     /// A cooldown is a period of time in which a ship cannot perform certain actions.
     pub cooldown: Cooldown,
     /// Extraction details.
-    pub extraction: ExtractionYield,
+    pub extraction: Extraction,
     /// Ship cargo details.
     pub cargo: ShipCargo,
 }
@@ -32,11 +34,25 @@ pub struct SiphonResourcesResponse {
     /// A cooldown is a period of time in which a ship cannot perform certain actions.
     pub cooldown: Cooldown,
     /// Siphon details.
-    pub siphon: SiphonYield,
+    pub siphon: Siphon,
     /// Ship cargo details.
     pub cargo: ShipCargo,
 }
 
+impl ShipUpdate for ExtractResourcesResponse {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cooldown = self.cooldown.clone();
+        ship.cargo = self.cargo.clone();
+    }
+}
+
+impl ShipUpdate for SiphonResourcesResponse {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cooldown = self.cooldown.clone();
+        ship.cargo = self.cargo.clone();
+    }
+}
+
 impl SpaceTradersClient<Authenticated> {
     /// Extract resources from a waypoint that can be extracted, such as asteroid fields, into your ship. Send an optional survey as the payload to target specific yields.
     /// 
@@ -121,4 +137,17 @@ impl SpaceTradersClient<Authenticated> {
         }
     }
 
-}
\ No newline at end of file
+    /// Same as [`Self::exract_resources`], but first checks that the ship has a mining laser installed.
+    /// Returns [`crate::error::Error::MissingCapability`] without sending the request otherwise.
+    pub async fn exract_resources_checked(&self, ship: &Ship, survey: Option<&Survey>) -> Result<ExtractResourcesResponse, crate::error::Error> {
+        ship.capabilities().check_extract()?;
+        self.exract_resources(&ship.symbol, survey).await
+    }
+
+    /// Same as [`Self::siphon_resources`], but first checks that the ship has a gas siphon and a gas processor installed.
+    /// Returns [`crate::error::Error::MissingCapability`] without sending the request otherwise.
+    pub async fn siphon_resources_checked(&self, ship: &Ship) -> Result<SiphonResourcesResponse, crate::error::Error> {
+        ship.capabilities().check_siphon()?;
+        self.siphon_resources(&ship.symbol).await
+    }
+}
@@ -1,11 +1,13 @@
 
 use serde::Deserialize;
 use crate::{
+    api::fleet::ShipUpdate,
     client::{Authenticated, SpaceTradersClient},
     utils::wrapper::{DataWrapper, ErrorWrapper},
     error::server_error::SpaceTraderError,
     schemas::{
         cooldown::Cooldown,
+        ship::Ship,
         scanned::{
             scanned_system::ScannedSystem,
             scanned_waypoint::ScannedWaypoint,
@@ -47,12 +49,30 @@ pub struct ShipsScanResult {
 
 
 
+impl ShipUpdate for SystemsScanResult {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cooldown = self.cooldown.clone();
+    }
+}
+
+impl ShipUpdate for WaypointsScanResult {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cooldown = self.cooldown.clone();
+    }
+}
+
+impl ShipUpdate for ShipsScanResult {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cooldown = self.cooldown.clone();
+    }
+}
+
 impl SpaceTradersClient<Authenticated> {
     /// Scan for nearby systems, retrieving information on the systems' distance from the ship and their waypoints. Requires a ship to have the Sensor Array mount installed to use.
     /// 
     /// The ship will enter a cooldown after using this function, during which it cannot execute certain actions.
     pub async fn scan_systems(&self, ship_symbol: &str) -> Result<SystemsScanResult, crate::error::Error> {
-        let response = self.get(&format!("my/ships/{ship_symbol}/scan/systems"))
+        let response = self.post(&format!("my/ships/{ship_symbol}/scan/systems"))
             .send()
             .await?;
         match response.status().as_u16() {
@@ -78,7 +98,7 @@ impl SpaceTradersClient<Authenticated> {
     ///     
     /// The ship will enter a cooldown after using this function, during which it cannot execute certain actions.
     pub async fn scan_waypoint(&self, ship_symbol: &str) -> Result<WaypointsScanResult, crate::error::Error> {
-        let response = self.get(&format!("my/ships/{ship_symbol}/scan/waypoints"))
+        let response = self.post(&format!("my/ships/{ship_symbol}/scan/waypoints"))
             .send()
             .await?;
         match response.status().as_u16() {
@@ -104,7 +124,7 @@ impl SpaceTradersClient<Authenticated> {
     ///
     ///The ship will enter a cooldown after using this function, during which it cannot execute certain actions.
     pub async fn scan_ships(&self, ship_symbol: &str) -> Result<ShipsScanResult, crate::error::Error> {
-        let response = self.get(&format!("my/ships/{ship_symbol}/scan/ships"))
+        let response = self.post(&format!("my/ships/{ship_symbol}/scan/ships"))
             .send()
             .await?;
         match response.status().as_u16() {
@@ -1,9 +1,10 @@
 use serde::Deserialize;
 use crate::{
+    api::fleet::ShipUpdate,
     client::Authenticated,
     utils::wrapper::{DataWrapper, ErrorWrapper},
     error::server_error::SpaceTraderError,
-    schemas::{cooldown::Cooldown, survey::Survey},
+    schemas::{cooldown::Cooldown, survey::Survey, ship::Ship},
 };
 
 /// Wrapper around a cooldown and surveys.
@@ -15,6 +16,12 @@ pub struct CooldownAndSurveys {
     pub surveys: Vec<Survey>,
 }
 
+impl ShipUpdate for CooldownAndSurveys {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cooldown = self.cooldown.clone();
+    }
+}
+
 impl crate::client::SpaceTradersClient<Authenticated> {
     /// Create surveys on a waypoint that can be extracted such as asteroid fields. A survey focuses on specific types of deposits from the extracted location. When ships extract using this survey, they are guaranteed to procure a high amount of one of the goods in the survey.
     /// 
@@ -45,4 +52,11 @@ impl crate::client::SpaceTradersClient<Authenticated> {
             }
         }
     }
-}
\ No newline at end of file
+
+    /// Same as [`Self::create_survey`], but first checks that the ship has a surveyor installed.
+    /// Returns [`crate::error::Error::MissingCapability`] without sending the request otherwise.
+    pub async fn create_survey_checked(&self, ship: &Ship) -> Result<CooldownAndSurveys, crate::error::Error> {
+        ship.capabilities().check_survey()?;
+        self.create_survey(&ship.symbol).await
+    }
+}
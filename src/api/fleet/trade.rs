@@ -0,0 +1,460 @@
+use std::{
+    collections::HashMap,
+    num::{NonZeroU32, NonZeroU64},
+};
+
+use chrono::Utc;
+use crate::{
+    client::{Authenticated, Requester, SpaceTradersClient, rate_limit::RateLimitedClient},
+    error::Error,
+    navigation::system_symbol,
+    schemas::{
+        agent::Agent,
+        market::{Market, market_transaction::{MarketTransaction, MarketTransactionType}},
+        ship::{Ship, ship_cargo::ShipCargo, ship_nav::ship_nav_status::ShipNavStatus},
+        trade_symbol::TradeSymbol,
+    },
+};
+
+/// A purchase or a sale of a good, made in transactions of at most the trade volume of the market.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeOrder {
+    pub symbol: TradeSymbol,
+    pub units: u64,
+    /// Highest price per unit paid for a purchase, or lowest price per unit accepted for a sale.
+    pub limit_price: Option<u64>,
+}
+
+impl TradeOrder {
+    /// An order for units of a good, at any price.
+    pub fn new(symbol: TradeSymbol, units: u64) -> TradeOrder {
+        TradeOrder { symbol, units, limit_price: None }
+    }
+
+    /// Stop the order once the price crosses the limit.
+    pub fn with_limit(self, limit_price: u64) -> TradeOrder {
+        TradeOrder { limit_price: Some(limit_price), ..self }
+    }
+
+    /// Whether the price is worse than the limit for this type of transaction.
+    fn crosses_limit(&self, transaction_type: MarketTransactionType, price: u64) -> bool {
+        match (transaction_type, self.limit_price) {
+            (_, None) => false,
+            (MarketTransactionType::Purchase, Some(limit)) => price > limit,
+            (MarketTransactionType::Sell, Some(limit)) => price < limit,
+        }
+    }
+
+    /// Units of the next transaction of the order once `filled` units are traded, or why the order stops:
+    /// at most the trade volume of the market, and the free space or units of the cargo.
+    fn next_transaction(&self, transaction_type: MarketTransactionType, filled: u64, market: &Market, cargo: &ShipCargo) -> Result<NonZeroU64, FillStop> {
+        if filled >= self.units {
+            return Err(FillStop::Filled);
+        }
+        let symbol = self.symbol.to_string();
+        let good = market.trade_goods.iter().flatten().find(|good| good.symbol == symbol).ok_or(FillStop::NotTraded)?;
+        let (price, available) = match transaction_type {
+            MarketTransactionType::Purchase => (good.purchase_price, cargo.free_space()),
+            MarketTransactionType::Sell => (good.sell_price, cargo.quantity_of(self.symbol)),
+        };
+        if self.crosses_limit(transaction_type, price) {
+            return Err(FillStop::LimitReached { price });
+        }
+        NonZeroU64::new((self.units - filled).min(good.trade_volume.get()).min(available)).ok_or(FillStop::NoCargo)
+    }
+}
+
+/// Why an order stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillStop {
+    /// Every unit of the order was traded.
+    Filled,
+    /// The price of the market crossed the limit price of the order.
+    LimitReached {
+        price: u64,
+    },
+    /// The market doesn't trade the good.
+    NotTraded,
+    /// The cargo is full for a purchase, or has no units of the good left for a sale.
+    NoCargo,
+}
+
+/// Result of an order: every transaction made, and why it stopped.
+#[derive(Debug, Clone)]
+pub struct FillReport {
+    pub symbol: TradeSymbol,
+    pub transaction_type: MarketTransactionType,
+    /// Units of the order.
+    pub requested: u64,
+    /// Transactions made, in order.
+    pub transactions: Vec<MarketTransaction>,
+    pub stop: FillStop,
+    /// The agent after the last transaction, `None` if no transaction was made.
+    pub agent: Option<Agent>,
+}
+
+impl FillReport {
+    fn new(order: &TradeOrder, transaction_type: MarketTransactionType) -> FillReport {
+        FillReport {
+            symbol: order.symbol,
+            transaction_type,
+            requested: order.units,
+            transactions: Vec::new(),
+            stop: FillStop::Filled,
+            agent: None,
+        }
+    }
+
+    /// Units traded.
+    pub fn filled(&self) -> u64 {
+        self.transactions.iter().map(|transaction| transaction.units).sum()
+    }
+
+    /// Whether every unit of the order was traded.
+    pub fn is_filled(&self) -> bool {
+        self.filled() >= self.requested
+    }
+
+    /// Credits paid for a purchase, or received for a sale.
+    pub fn total_price(&self) -> u64 {
+        self.transactions.iter().map(|transaction| transaction.total_price).sum()
+    }
+
+    /// Average price per unit traded, `None` if nothing was traded.
+    pub fn average_price(&self) -> Option<f64> {
+        match self.filled() {
+            0 => None,
+            filled => Some(self.total_price() as f64 / filled as f64),
+        }
+    }
+}
+
+/// What to do with the cargo when liquidating it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiquidationOptions {
+    /// Units of each good kept in the cargo.
+    pub reserve: HashMap<TradeSymbol, u64>,
+    /// Lowest price per unit accepted for each good.
+    pub min_prices: HashMap<TradeSymbol, u64>,
+    /// Throw away the goods the market doesn't trade.
+    pub jettison_unsellable: bool,
+}
+
+/// A step of a liquidation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidationStep {
+    Sell(TradeOrder),
+    Jettison {
+        symbol: TradeSymbol,
+        units: u64,
+    },
+}
+
+impl LiquidationOptions {
+    /// Sell everything the market trades, and keep the rest.
+    pub fn new() -> LiquidationOptions {
+        LiquidationOptions::default()
+    }
+
+    /// Keep units of a good in the cargo.
+    pub fn keep(mut self, symbol: TradeSymbol, units: u64) -> LiquidationOptions {
+        self.reserve.insert(symbol, units);
+        self
+    }
+
+    /// Don't sell a good below the given price per unit.
+    pub fn min_price(mut self, symbol: TradeSymbol, price: u64) -> LiquidationOptions {
+        self.min_prices.insert(symbol, price);
+        self
+    }
+
+    /// Throw away the goods the market doesn't trade.
+    pub fn jettison_unsellable(self) -> LiquidationOptions {
+        LiquidationOptions { jettison_unsellable: true, ..self }
+    }
+
+    /// What to do with each good of the cargo above its reserve: sell it if the market trades it, or else jettison it if allowed.
+    pub fn plan(&self, cargo: &ShipCargo, market: &Market) -> Vec<LiquidationStep> {
+        let traded = market.trade_goods.iter().flatten().map(|good| good.symbol.as_str()).collect::<Vec<_>>();
+        cargo.iter()
+            .filter_map(|(symbol, units)| {
+                let units = units.get().saturating_sub(self.reserve.get(&symbol).copied().unwrap_or(0));
+                if units == 0 {
+                    return None;
+                }
+                if traded.contains(&symbol.to_string().as_str()) {
+                    let order = TradeOrder { symbol, units, limit_price: self.min_prices.get(&symbol).copied() };
+                    return Some(LiquidationStep::Sell(order));
+                }
+                self.jettison_unsellable.then_some(LiquidationStep::Jettison { symbol, units })
+            })
+            .collect()
+    }
+}
+
+/// Result of a liquidation.
+#[derive(Debug, Clone, Default)]
+pub struct LiquidationReport {
+    /// The sale of each good sold.
+    pub sales: Vec<FillReport>,
+    /// Goods thrown away, and their units.
+    pub jettisoned: Vec<(TradeSymbol, u64)>,
+    /// Goods left in the cargo, and their units: reserved, unsellable, or not sold at the price limit.
+    pub leftovers: Vec<(TradeSymbol, u64)>,
+}
+
+impl LiquidationReport {
+    /// Credits received for the sales.
+    pub fn revenue(&self) -> u64 {
+        self.sales.iter().map(FillReport::total_price).sum()
+    }
+
+    /// Units sold over every good.
+    pub fn units_sold(&self) -> u64 {
+        self.sales.iter().map(FillReport::filled).sum()
+    }
+}
+
+/// A liquidation that failed, with what was sold and jettisoned before the failure.
+#[derive(Debug)]
+pub struct LiquidationError {
+    /// The sales and jettisons made before the failure, and the cargo left.
+    pub report: LiquidationReport,
+    pub error: Error,
+}
+
+impl From<LiquidationError> for Error {
+    fn from(value: LiquidationError) -> Self {
+        value.error
+    }
+}
+
+impl SpaceTradersClient<Authenticated> {
+    /// Dock the ship and buy goods at its market, in transactions of at most the trade volume.
+    ///
+    /// The price is checked before each transaction, and the order stops once it is above the limit price,
+    /// the cargo is full, or every unit is bought. The ship is updated with the results of the transactions.
+    pub async fn buy(&self, ship: &mut Ship, order: &TradeOrder) -> Result<FillReport, Error> {
+        fill_order(self, ship, MarketTransactionType::Purchase, order).await
+    }
+
+    /// Dock the ship and sell goods of its cargo at its market, in transactions of at most the trade volume.
+    ///
+    /// The price is checked before each transaction, and the order stops once it is below the limit price,
+    /// the cargo has no units of the good left, or every unit is sold. The ship is updated with the results of the transactions.
+    pub async fn sell(&self, ship: &mut Ship, order: &TradeOrder) -> Result<FillReport, Error> {
+        fill_order(self, ship, MarketTransactionType::Sell, order).await
+    }
+
+    /// Sell the cargo of the ship at its market, keeping the reserve of the options,
+    /// and jettison the goods the market doesn't trade if allowed. Sales are made with [`SpaceTradersClient::sell`].
+    ///
+    /// If a request fails, the error comes with the report of what was done before, as the cargo may be partly sold.
+    pub async fn liquidate(&self, ship: &mut Ship, options: &LiquidationOptions) -> Result<LiquidationReport, LiquidationError> {
+        liquidate(self, ship, options).await
+    }
+}
+
+impl RateLimitedClient<Authenticated> {
+    /// Buy goods at the market of the ship, see [`SpaceTradersClient::buy`]. Every request waits for the rate limiter.
+    pub async fn buy(&self, ship: &mut Ship, order: &TradeOrder) -> Result<FillReport, Error> {
+        fill_order(self, ship, MarketTransactionType::Purchase, order).await
+    }
+
+    /// Sell goods at the market of the ship, see [`SpaceTradersClient::sell`]. Every request waits for the rate limiter.
+    pub async fn sell(&self, ship: &mut Ship, order: &TradeOrder) -> Result<FillReport, Error> {
+        fill_order(self, ship, MarketTransactionType::Sell, order).await
+    }
+
+    /// Liquidate the cargo of the ship, see [`SpaceTradersClient::liquidate`]. Every request waits for the rate limiter.
+    pub async fn liquidate(&self, ship: &mut Ship, options: &LiquidationOptions) -> Result<LiquidationReport, LiquidationError> {
+        liquidate(self, ship, options).await
+    }
+}
+
+async fn fill_order<R: Requester>(requester: &R, ship: &mut Ship, transaction_type: MarketTransactionType, order: &TradeOrder) -> Result<FillReport, Error> {
+    ship.wait_until_ready().await;
+    ship.nav.update_status(Utc::now());
+    if ship.nav.status != ShipNavStatus::Docked {
+        requester.acquire().await;
+        let nav = requester.client().dock_ship(&ship.symbol).await?;
+        ship.apply(&nav);
+    }
+
+    let mut report = FillReport::new(order, transaction_type);
+    while report.filled() < order.units {
+        requester.acquire().await;
+        let market = requester.client().get_market(system_symbol(&ship.nav.waypoint_symbol), &ship.nav.waypoint_symbol).await?;
+        let units = match order.next_transaction(transaction_type, report.filled(), &market, &ship.cargo) {
+            Ok(units) => units,
+            Err(stop) => {
+                report.stop = stop;
+                break;
+            },
+        };
+        requester.acquire().await;
+        let result = match transaction_type {
+            MarketTransactionType::Purchase => requester.client().purchase_cargo(&ship.symbol, order.symbol, units).await?,
+            MarketTransactionType::Sell => requester.client().sell_cargo(&ship.symbol, order.symbol, units).await?,
+        };
+        ship.apply(&result);
+        report.transactions.push(result.transaction);
+        report.agent = Some(result.agent);
+    }
+    Ok(report)
+}
+
+async fn liquidate<R: Requester>(requester: &R, ship: &mut Ship, options: &LiquidationOptions) -> Result<LiquidationReport, LiquidationError> {
+    let mut report = LiquidationReport::default();
+    let result = liquidate_into(requester, ship, options, &mut report).await;
+    finish_liquidation(report, &ship.cargo, result)
+}
+
+/// Complete the report of a liquidation with the cargo left once it stopped, and attach it to the error if it failed.
+#[allow(clippy::result_large_err)]
+fn finish_liquidation(mut report: LiquidationReport, cargo: &ShipCargo, result: Result<(), Error>) -> Result<LiquidationReport, LiquidationError> {
+    report.leftovers = cargo.iter().map(|(symbol, units)| (symbol, units.get())).collect();
+    match result {
+        Ok(()) => Ok(report),
+        Err(error) => Err(LiquidationError { report, error }),
+    }
+}
+
+/// Liquidate the cargo, recording the sales and jettisons in the report as they are made.
+async fn liquidate_into<R: Requester>(requester: &R, ship: &mut Ship, options: &LiquidationOptions, report: &mut LiquidationReport) -> Result<(), Error> {
+    ship.wait_until_ready().await;
+    ship.nav.update_status(Utc::now());
+    requester.acquire().await;
+    let market = requester.client().get_market(system_symbol(&ship.nav.waypoint_symbol), &ship.nav.waypoint_symbol).await?;
+    for step in options.plan(&ship.cargo, &market) {
+        match step {
+            LiquidationStep::Sell(order) => {
+                report.sales.push(fill_order(requester, ship, MarketTransactionType::Sell, &order).await?);
+            },
+            LiquidationStep::Jettison { symbol, units } => {
+                requester.acquire().await;
+                let amount = NonZeroU32::new(units.min(u32::MAX as u64) as u32).unwrap_or(NonZeroU32::MIN);
+                let cargo = requester.client().jettison_cargo(&ship.symbol, symbol, amount).await?;
+                ship.apply(&cargo);
+                report.jettisoned.push((symbol, amount.get() as u64));
+            },
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::fleet::cargo::CagoTransactionResult, conformance::fixture_data};
+
+    fn transaction(operation: &str) -> MarketTransaction {
+        fixture_data::<CagoTransactionResult>(operation).transaction
+    }
+
+    #[test]
+    fn limits() {
+        let order = TradeOrder::new(TradeSymbol::Iron, 100);
+        assert!(!order.crosses_limit(MarketTransactionType::Purchase, u64::MAX));
+        let order = order.with_limit(50);
+        assert!(!order.crosses_limit(MarketTransactionType::Purchase, 50));
+        assert!(order.crosses_limit(MarketTransactionType::Purchase, 51));
+        assert!(!order.crosses_limit(MarketTransactionType::Sell, 50));
+        assert!(order.crosses_limit(MarketTransactionType::Sell, 49));
+    }
+
+    #[test]
+    fn report() {
+        let order = TradeOrder::new(TradeSymbol::Iron, 100);
+        let mut report = FillReport::new(&order, MarketTransactionType::Sell);
+        assert_eq!(report.average_price(), None);
+        let mut first = transaction("sell-cargo");
+        first.units = 20;
+        first.price_per_unit = 10;
+        first.total_price = 200;
+        let mut second = first.clone();
+        second.units = 30;
+        second.price_per_unit = 8;
+        second.total_price = 240;
+        report.transactions = vec![first, second];
+        assert_eq!(report.filled(), 50);
+        assert!(!report.is_filled());
+        assert_eq!(report.total_price(), 440);
+        assert_eq!(report.average_price(), Some(8.8));
+    }
+
+    #[test]
+    fn transactions() {
+        // aluminum ore is traded 60 units at a time, bought at 90 and sold at 44
+        let market: Market = fixture_data("get-market");
+        // 8 units of aluminum ore and 4 of ice water, with 28 units of free space
+        let ship: Ship = fixture_data("get-my-ship");
+        let next = |order: TradeOrder, transaction_type, filled, cargo: &ShipCargo| {
+            order.next_transaction(transaction_type, filled, &market, cargo).map(NonZeroU64::get)
+        };
+        let (buy, sell) = (MarketTransactionType::Purchase, MarketTransactionType::Sell);
+
+        let order = TradeOrder::new(TradeSymbol::AluminumOre, 100);
+        assert_eq!(next(order, sell, 0, &ship.cargo), Ok(8));
+        assert_eq!(next(order, buy, 0, &ship.cargo), Ok(28));
+        assert_eq!(next(order, buy, 95, &ship.cargo), Ok(5));
+        assert_eq!(next(order, buy, 100, &ship.cargo), Err(FillStop::Filled));
+        assert_eq!(next(order.with_limit(89), buy, 0, &ship.cargo), Err(FillStop::LimitReached { price: 90 }));
+        assert_eq!(next(order.with_limit(45), sell, 0, &ship.cargo), Err(FillStop::LimitReached { price: 44 }));
+        assert_eq!(next(TradeOrder::new(TradeSymbol::Gold, 10), sell, 0, &ship.cargo), Err(FillStop::NotTraded));
+        // nothing to sell
+        assert_eq!(next(TradeOrder::new(TradeSymbol::Fuel, 10), sell, 0, &ship.cargo), Err(FillStop::NoCargo));
+
+        // at most the trade volume
+        let mut cargo = ShipCargo { capacity: 200, ..ship.cargo.clone() };
+        assert_eq!(next(order, buy, 0, &cargo), Ok(60));
+        cargo.add(TradeSymbol::Iron, cargo.free_space()).unwrap();
+        assert_eq!(next(order, buy, 0, &cargo), Err(FillStop::NoCargo));
+    }
+
+    #[test]
+    fn failed_liquidation() {
+        let ship: Ship = fixture_data("get-my-ship");
+        let mut cargo = ship.cargo.clone();
+        // the aluminum ore was sold, then selling the ice water failed
+        let mut sale = transaction("sell-cargo");
+        sale.trade_symbol = TradeSymbol::AluminumOre.to_string();
+        sale.units = 8;
+        cargo.remove(TradeSymbol::AluminumOre, 8).unwrap();
+        let mut report = LiquidationReport::default();
+        let mut fill = FillReport::new(&TradeOrder::new(TradeSymbol::AluminumOre, 8), MarketTransactionType::Sell);
+        fill.transactions.push(sale);
+        report.sales.push(fill);
+        let error = Error::ShipActorStopped { ship_symbol: ship.symbol.clone() };
+
+        let failed = finish_liquidation(report.clone(), &cargo, Err(error)).unwrap_err();
+        assert_eq!(failed.report.units_sold(), 8);
+        assert_eq!(failed.report.leftovers, [(TradeSymbol::IceWater, 4)]);
+        assert!(matches!(Error::from(failed), Error::ShipActorStopped { .. }));
+
+        let done = finish_liquidation(report, &cargo, Ok(())).unwrap();
+        assert_eq!(done.units_sold(), 8);
+        assert_eq!(done.leftovers, [(TradeSymbol::IceWater, 4)]);
+    }
+
+    #[test]
+    fn liquidation_plan() {
+        let ship: Ship = fixture_data("get-my-ship");
+        let market: Market = fixture_data("get-market");
+        let mut cargo = ship.cargo.clone();
+        // not traded at the market
+        cargo.add(TradeSymbol::Iron, 5).unwrap();
+
+        let options = LiquidationOptions::new().keep(TradeSymbol::IceWater, 3).min_price(TradeSymbol::AluminumOre, 40);
+        assert_eq!(options.plan(&cargo, &market), [
+            LiquidationStep::Sell(TradeOrder::new(TradeSymbol::AluminumOre, 8).with_limit(40)),
+            LiquidationStep::Sell(TradeOrder::new(TradeSymbol::IceWater, 1)),
+        ]);
+
+        let options = options.keep(TradeSymbol::IceWater, 10).jettison_unsellable();
+        assert_eq!(options.plan(&cargo, &market), [
+            LiquidationStep::Sell(TradeOrder::new(TradeSymbol::AluminumOre, 8).with_limit(40)),
+            LiquidationStep::Jettison { symbol: TradeSymbol::Iron, units: 5 },
+        ]);
+    }
+}
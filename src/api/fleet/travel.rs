@@ -0,0 +1,417 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::Arc,
+};
+
+use crate::{
+    client::{Authenticated, Requester, SpaceTradersClient, rate_limit::RateLimitedClient},
+    error::{Error, code::ErrorCode},
+    navigation::{
+        system_symbol,
+        jump_network::JumpNetwork,
+        route::{Route, RouteOptions, SystemMap},
+        warp::WarpMap,
+    },
+    schemas::{
+        ship::{Ship, ship_nav::ship_nav_status::ShipNavStatus},
+        waypoint::{Waypoint, waypoint_trait::WaypointTraitType, waypoint_type::WaypointType},
+    },
+};
+
+/// Options of [`SpaceTradersClient::travel_to`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TravelOptions {
+    /// Objective and flight modes used to plan the navigations.
+    pub route: RouteOptions,
+    /// Refuel at the destination if fuel is sold there.
+    pub refuel_on_arrival: bool,
+    /// Dock at the destination.
+    pub dock_on_arrival: bool,
+    /// Maximum number of jump gates fetched when looking for a path to another system.
+    pub max_jump_gate_requests: usize,
+    /// Systems and fuel stations used to plan warps to another system.
+    /// If `None`, only the origin and destination systems are known, and the ship warps directly.
+    pub warp_map: Option<Arc<WarpMap>>,
+    /// Maps of systems by system symbol, with where fuel is sold, used instead of fetching their waypoints and markets.
+    pub system_maps: BTreeMap<String, Arc<SystemMap>>,
+}
+
+impl Default for TravelOptions {
+    fn default() -> Self {
+        TravelOptions {
+            route: RouteOptions::default(),
+            refuel_on_arrival: true,
+            dock_on_arrival: true,
+            max_jump_gate_requests: 20,
+            warp_map: None,
+            system_maps: BTreeMap::new(),
+        }
+    }
+}
+
+/// Whether the error means the ship was still busy, and the action can be retried once it is ready.
+fn is_busy(error: &Error) -> bool {
+    matches!(
+        error.code(),
+        Some(ErrorCode::CooldownConflictError | ErrorCode::ShipInTransitError | ErrorCode::NavigateInTransitError)
+    )
+}
+
+/// Fetch the ship, and wait until it is off cooldown and arrived.
+async fn wait_until_ship_ready<R: Requester>(requester: &R, ship_symbol: &str) -> Result<(), Error> {
+    requester.acquire().await;
+    let ship = requester.client().get_ship(ship_symbol).await?;
+    ship.wait_until_ready().await;
+    Ok(())
+}
+
+/// Send a request for a ship, and send it again once the ship is ready if the server answered it was busy.
+macro_rules! retry_when_busy {
+    ($requester:expr, $ship:expr, $request:expr) => {{
+        $requester.acquire().await;
+        match $request.await {
+            Err(error) if is_busy(&error) => {
+                wait_until_ship_ready($requester, &$ship.symbol).await?;
+                $requester.acquire().await;
+                $request.await?
+            },
+            result => result?,
+        }
+    }};
+}
+
+impl SpaceTradersClient<Authenticated> {
+    /// Fetch every waypoint of a system.
+    pub async fn list_all_waypoints_in_system(&self, system_symbol: &str) -> Result<Vec<Waypoint>, Error> {
+        list_all_waypoints_in_system(self, system_symbol).await
+    }
+
+    /// Fetch the waypoints of a system, and the markets of the system to know where fuel is sold.
+    pub async fn system_map(&self, system_symbol: &str) -> Result<SystemMap, Error> {
+        system_map(self, system_symbol).await
+    }
+
+    /// Move a ship to the destination waypoint, and return the ship once it arrived.
+    ///
+    /// This waits for the ship to be ready, then plans and follows a route to the destination:
+    /// navigations with refuel stops inside a system, and jumps through jump gates (or warps with refuel stops
+    /// if no gate path is known) to reach another system. Flight modes are set as needed, and actions rejected because the ship is
+    /// on cooldown or in transit are retried once the ship is ready.
+    pub async fn travel_to(&self, ship_symbol: &str, destination: &str, options: &TravelOptions) -> Result<Ship, Error> {
+        travel_to(self, ship_symbol, destination, options).await
+    }
+
+    /// Move the given ship to the destination waypoint, see [`SpaceTradersClient::travel_to`].
+    ///
+    /// The ship is updated with every action of the travel, so it is kept up to date even if the travel fails midway.
+    pub async fn travel(&self, ship: &mut Ship, destination: &str, options: &TravelOptions) -> Result<(), Error> {
+        travel(self, ship, destination, options).await
+    }
+}
+
+impl RateLimitedClient<Authenticated> {
+    /// Fetch the map of a system, see [`SpaceTradersClient::system_map`]. Every request waits for the rate limiter.
+    pub async fn system_map(&self, system_symbol: &str) -> Result<SystemMap, Error> {
+        system_map(self, system_symbol).await
+    }
+
+    /// Move a ship to the destination waypoint, see [`SpaceTradersClient::travel_to`].
+    /// Every request of the travel waits for the rate limiter.
+    pub async fn travel_to(&self, ship_symbol: &str, destination: &str, options: &TravelOptions) -> Result<Ship, Error> {
+        travel_to(self, ship_symbol, destination, options).await
+    }
+
+    /// Move the given ship to the destination waypoint, see [`SpaceTradersClient::travel`].
+    /// Every request of the travel waits for the rate limiter.
+    pub async fn travel(&self, ship: &mut Ship, destination: &str, options: &TravelOptions) -> Result<(), Error> {
+        travel(self, ship, destination, options).await
+    }
+}
+
+async fn list_all_waypoints_in_system<R: Requester>(requester: &R, system_symbol: &str) -> Result<Vec<Waypoint>, Error> {
+    let mut waypoints = Vec::new();
+    let mut page = 1;
+    loop {
+        requester.acquire().await;
+        let (mut batch, meta) = requester.client().list_waypoints_in_system(system_symbol, Some(20), Some(page), None, None).await?;
+        waypoints.append(&mut batch);
+        if !meta.has_next_page() {
+            return Ok(waypoints);
+        }
+        page += 1;
+    }
+}
+
+async fn system_map<R: Requester>(requester: &R, system_symbol: &str) -> Result<SystemMap, Error> {
+    let waypoints = list_all_waypoints_in_system(requester, system_symbol).await?;
+    let mut map = LazySystemMap::from_waypoints(waypoints);
+    map.fetch_markets(requester).await?;
+    Ok(Arc::unwrap_or_clone(map.map))
+}
+
+/// Map of a system used to plan routes, where markets are only fetched once they are needed.
+struct LazySystemMap {
+    map: Arc<SystemMap>,
+    /// Waypoints of the system, to find its markets. Empty once every market is known.
+    waypoints: Vec<Waypoint>,
+}
+
+impl LazySystemMap {
+    fn from_waypoints(waypoints: Vec<Waypoint>) -> LazySystemMap {
+        LazySystemMap { map: Arc::new(SystemMap::from_waypoints(&waypoints)), waypoints }
+    }
+
+    /// The cached map of the system if given in the options, otherwise its waypoints, listed unless already fetched.
+    async fn load<R: Requester>(requester: &R, system_symbol: &str, waypoints: Option<Vec<Waypoint>>, options: &TravelOptions) -> Result<LazySystemMap, Error> {
+        if let Some(map) = options.system_maps.get(system_symbol) {
+            return Ok(LazySystemMap { map: map.clone(), waypoints: Vec::new() });
+        }
+        let waypoints = match waypoints {
+            Some(waypoints) => waypoints,
+            None => list_all_waypoints_in_system(requester, system_symbol).await?,
+        };
+        Ok(LazySystemMap::from_waypoints(waypoints))
+    }
+
+    /// Fetch the markets not fetched yet, to know where fuel is sold.
+    async fn fetch_markets<R: Requester>(&mut self, requester: &R) -> Result<(), Error> {
+        for waypoint in std::mem::take(&mut self.waypoints) {
+            if waypoint.traits.iter().any(|t| t.symbol == WaypointTraitType::Marketplace) {
+                requester.acquire().await;
+                let market = requester.client().get_market(&waypoint.system_symbol, &waypoint.symbol).await?;
+                Arc::make_mut(&mut self.map).add_market(&market);
+            }
+        }
+        Ok(())
+    }
+
+    /// Plan a route for the ship to the destination.
+    /// Markets are only fetched if the destination can't be reached without refueling.
+    async fn plan_route<R: Requester>(&mut self, requester: &R, ship: &Ship, destination: &str, options: &RouteOptions) -> Result<Route, Error> {
+        if let Some(route) = self.map.plan_route(ship, destination, options) {
+            return Ok(route);
+        }
+        if !self.waypoints.is_empty() {
+            self.fetch_markets(requester).await?;
+            if let Some(route) = self.map.plan_route(ship, destination, options) {
+                return Ok(route);
+            }
+        }
+        Err(Error::NoRouteFound { origin: ship.nav.waypoint_symbol.clone(), destination: destination.to_string() })
+    }
+
+    /// Whether fuel is sold at the waypoint, fetching its market if it wasn't yet.
+    async fn sells_fuel<R: Requester>(&mut self, requester: &R, symbol: &str) -> Result<bool, Error> {
+        let market = self.waypoints.iter()
+            .position(|waypoint| waypoint.symbol == symbol && waypoint.traits.iter().any(|t| t.symbol == WaypointTraitType::Marketplace));
+        if let Some(index) = market {
+            let waypoint = self.waypoints.remove(index);
+            requester.acquire().await;
+            let market = requester.client().get_market(&waypoint.system_symbol, &waypoint.symbol).await?;
+            Arc::make_mut(&mut self.map).add_market(&market);
+        }
+        Ok(self.map.get(symbol).is_some_and(|waypoint| waypoint.sells_fuel))
+    }
+}
+
+async fn travel_to<R: Requester>(requester: &R, ship_symbol: &str, destination: &str, options: &TravelOptions) -> Result<Ship, Error> {
+    requester.acquire().await;
+    let mut ship = requester.client().get_ship(ship_symbol).await?;
+    travel(requester, &mut ship, destination, options).await?;
+    Ok(ship)
+}
+
+async fn travel<R: Requester>(requester: &R, ship: &mut Ship, destination: &str, options: &TravelOptions) -> Result<(), Error> {
+    ship.wait_until_ready().await;
+    ship.nav.update_status(chrono::Utc::now());
+
+    let destination_system = system_symbol(destination);
+    if ship.nav.system_symbol != destination_system {
+        let waypoints = list_all_waypoints_in_system(requester, &ship.nav.system_symbol).await?;
+        let gate = waypoints.iter().find(|waypoint| waypoint.waypoint_type == WaypointType::JumpGate).cloned();
+        let path = match &gate {
+            Some(gate) => find_jump_path(requester, gate, destination_system, options.max_jump_gate_requests).await?,
+            None => None,
+        };
+        match (gate, path) {
+            (Some(gate), Some(path)) => {
+                let mut map = LazySystemMap::load(requester, &ship.nav.system_symbol, Some(waypoints), options).await?;
+                let route = map.plan_route(requester, ship, &gate.symbol, &options.route).await?;
+                fly_route(requester, ship, route).await?;
+                for next_gate in path.into_iter().skip(1) {
+                    ensure_in_orbit(requester, ship).await?;
+                    let jump = retry_when_busy!(requester, ship, requester.client().jump_ship(&ship.symbol, &next_gate));
+                    ship.apply(&jump);
+                }
+            },
+            _ if ship.capabilities().can_warp() => {
+                let warp_map = match &options.warp_map {
+                    Some(warp_map) => warp_map.clone(),
+                    None => Arc::new(direct_warp_map(requester, &waypoints, destination_system).await?),
+                };
+                let mut origin = LazySystemMap::load(requester, &ship.nav.system_symbol, Some(waypoints), options).await?;
+                follow_warp_route(requester, ship, &warp_map, &mut origin, destination, &options.route).await?;
+            },
+            _ => return Err(Error::NoRouteFound { origin: ship.nav.waypoint_symbol.clone(), destination: destination.to_string() }),
+        }
+    }
+
+    let mut map = LazySystemMap::load(requester, destination_system, None, options).await?;
+    let route = map.plan_route(requester, ship, destination, &options.route).await?;
+    fly_route(requester, ship, route).await?;
+
+    if options.refuel_on_arrival && ship.fuel.current < ship.fuel.capacity && map.sells_fuel(requester, destination).await? {
+        refuel(requester, ship).await?;
+    }
+    if options.dock_on_arrival && ship.nav.status != ShipNavStatus::Docked {
+        let nav = retry_when_busy!(requester, ship, requester.client().dock_ship(&ship.symbol));
+        ship.apply(&nav);
+    }
+    Ok(())
+}
+
+/// Path of gates from the given gate to the gate of the destination system.
+/// Gates are fetched breadth first, closest gates first, until a path is found.
+/// Gates under construction can't be jumped through: the waypoint of every gate of a path is checked before the path is used.
+async fn find_jump_path<R: Requester>(requester: &R, start_gate: &Waypoint, destination_system: &str, max_requests: usize) -> Result<Option<Vec<String>>, Error> {
+    let mut network = JumpNetwork::new();
+    network.add_waypoint(start_gate);
+    let mut checked = BTreeSet::from([start_gate.symbol.clone()]);
+    let mut to_fetch = VecDeque::from([start_gate.symbol.clone()]);
+    let mut fetched = BTreeSet::new();
+    while let Some(gate_symbol) = to_fetch.pop_front() {
+        if fetched.len() >= max_requests {
+            break;
+        }
+        if !network.is_usable(&gate_symbol) || !fetched.insert(gate_symbol.clone()) {
+            continue;
+        }
+        requester.acquire().await;
+        let gate = requester.client().get_jumpgate(system_symbol(&gate_symbol), &gate_symbol).await?;
+        network.add_gate(&gate);
+        while let Some(path) = network.shortest_path(system_symbol(&start_gate.symbol), destination_system) {
+            let unchecked = path.gates.iter().find(|gate| !checked.contains(*gate));
+            let Some(unchecked) = unchecked.cloned() else {
+                return Ok(Some(path.gates));
+            };
+            requester.acquire().await;
+            let waypoint = requester.client().get_waypoint(system_symbol(&unchecked), &unchecked).await?;
+            network.add_waypoint(&waypoint);
+            checked.insert(unchecked);
+        }
+        to_fetch.extend(gate.connections);
+    }
+    Ok(None)
+}
+
+/// Map of the origin and destination systems of a warp, knowing where fuel is sold in the origin system.
+async fn direct_warp_map<R: Requester>(requester: &R, origin_waypoints: &[Waypoint], destination_system: &str) -> Result<WarpMap, Error> {
+    let mut map = WarpMap::new();
+    if let Some(waypoint) = origin_waypoints.first() {
+        requester.acquire().await;
+        map.insert(&requester.client().get_systems(&waypoint.system_symbol).await?);
+    }
+    requester.acquire().await;
+    map.insert(&requester.client().get_systems(destination_system).await?);
+    for waypoint in origin_waypoints.iter() {
+        if waypoint.traits.iter().any(|t| t.symbol == WaypointTraitType::Marketplace) {
+            requester.acquire().await;
+            map.add_market(&requester.client().get_market(&waypoint.system_symbol, &waypoint.symbol).await?);
+            if map.fuel_station(&waypoint.system_symbol).is_some() {
+                break;
+            }
+        }
+    }
+    Ok(map)
+}
+
+/// Warp the ship system by system to the destination waypoint, refueling at the fuel stations on the way as planned.
+///
+/// Each warp goes to the fuel station of the next system, and the last one to the destination waypoint.
+/// If the ship refuels before the first warp, the route to the fuel station of its system is planned along with the warps,
+/// before the ship leaves.
+async fn follow_warp_route<R: Requester>(
+    requester: &R,
+    ship: &mut Ship,
+    map: &WarpMap,
+    origin: &mut LazySystemMap,
+    destination: &str,
+    options: &RouteOptions,
+) -> Result<(), Error> {
+    let destination_system = system_symbol(destination);
+    let no_route = Error::NoRouteFound { origin: ship.nav.waypoint_symbol.clone(), destination: destination.to_string() };
+    let route = match map.plan_route(ship, destination_system, options) {
+        Some(route) => route,
+        None => return Err(no_route),
+    };
+    let to_station = match route.hops.first() {
+        Some(hop) if hop.refuel => match map.fuel_station(&hop.origin) {
+            Some(station) => origin.plan_route(requester, ship, station, options).await?,
+            None => return Err(no_route),
+        },
+        _ => Route::default(),
+    };
+    fly_route(requester, ship, to_station).await?;
+    for hop in route.hops {
+        if hop.refuel {
+            refuel(requester, ship).await?;
+        }
+        let target = match map.fuel_station(&hop.destination) {
+            _ if hop.destination == destination_system => destination,
+            Some(station) => station,
+            None => return Err(no_route),
+        };
+        ensure_in_orbit(requester, ship).await?;
+        if ship.nav.flight_mode != hop.leg.flight_mode {
+            requester.acquire().await;
+            let nav = requester.client().patch_ship_nav(&ship.symbol, hop.leg.flight_mode).await?;
+            ship.apply(&nav);
+        }
+        let warp = retry_when_busy!(requester, ship, requester.client().warp_ship(&ship.symbol, target));
+        ship.apply(&warp);
+        ship.wait_for_arrival().await;
+        ship.nav.update_status(chrono::Utc::now());
+    }
+    Ok(())
+}
+
+/// Navigate the ship inside its system along the route, refueling on the way as planned.
+async fn fly_route<R: Requester>(requester: &R, ship: &mut Ship, route: Route) -> Result<(), Error> {
+    for hop in route.hops {
+        if hop.refuel {
+            refuel(requester, ship).await?;
+        }
+        ensure_in_orbit(requester, ship).await?;
+        if ship.nav.flight_mode != hop.leg.flight_mode {
+            requester.acquire().await;
+            let nav = requester.client().patch_ship_nav(&ship.symbol, hop.leg.flight_mode).await?;
+            ship.apply(&nav);
+        }
+        let navigation = retry_when_busy!(requester, ship, requester.client().navigate_ship(&ship.symbol, &hop.destination));
+        ship.apply(&navigation);
+        ship.wait_for_arrival().await;
+        ship.nav.update_status(chrono::Utc::now());
+    }
+    Ok(())
+}
+
+/// Dock the ship if needed, and refuel it.
+async fn refuel<R: Requester>(requester: &R, ship: &mut Ship) -> Result<(), Error> {
+    if ship.nav.status != ShipNavStatus::Docked {
+        let nav = retry_when_busy!(requester, ship, requester.client().dock_ship(&ship.symbol));
+        ship.apply(&nav);
+    }
+    requester.acquire().await;
+    let refuel = requester.client().refuel_ship(&ship.symbol, None).await?;
+    ship.apply(&refuel);
+    Ok(())
+}
+
+/// Put the ship in orbit if it is docked.
+async fn ensure_in_orbit<R: Requester>(requester: &R, ship: &mut Ship) -> Result<(), Error> {
+    if ship.nav.status == ShipNavStatus::Docked {
+        let nav = retry_when_busy!(requester, ship, requester.client().orbit_ship(&ship.symbol));
+        ship.apply(&nav);
+    }
+    Ok(())
+}
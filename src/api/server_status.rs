@@ -5,7 +5,7 @@ use crate::{
         Authenticated,
         Anonymous
     },
-    utils::wrapper::{DataWrapper, ErrorWrapper},
+    utils::wrapper::ErrorWrapper,
     error::server_error::SpaceTraderError
 };
 
@@ -93,7 +93,7 @@ impl crate::client::SpaceTradersClient<Authenticated> {
                 let json = response
                     .json::<serde_json::Value>()
                     .await?;
-                Ok(<DataWrapper::<ServerStatus>>::deserialize(json)?.inner())
+                Ok(ServerStatus::deserialize(json)?)
             }
             status => {
                 let json = response
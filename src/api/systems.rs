@@ -1,6 +1,8 @@
 pub mod waypoint;
 
-use serde::Deserialize;
+use std::fmt::Debug;
+
+use serde::{Deserialize, de::DeserializeOwned};
 use crate::{
     client::{
         SpaceTradersClient,
@@ -15,6 +17,11 @@ use crate::{
 impl SpaceTradersClient<Anonymous> {
     /// Return a paginated list of all systems.
     pub async fn list_systems(&self, page_limit: Option<u64>, page_index: Option<u64>) -> Result<(Vec<System>, Meta), crate::error::Error> {
+        self.list_systems_as(page_limit, page_index).await
+    }
+
+    /// [`Self::list_systems`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn list_systems_as<T: DeserializeOwned + Debug + Clone>(&self, page_limit: Option<u64>, page_index: Option<u64>) -> Result<(Vec<T>, Meta), crate::error::Error> {
         let (limit, page) = page_limit_and_index(page_limit, page_index);
         let response = self.get("systems")
             .query(&[("limit", limit), ("page", page)])
@@ -22,7 +29,7 @@ impl SpaceTradersClient<Anonymous> {
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(PaginationWrapper::<System>::deserialize(json)?.inner())
+                Ok(PaginationWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -34,11 +41,16 @@ impl SpaceTradersClient<Anonymous> {
 
     /// Get the details of a system.
     pub async fn get_systems(&self, system_symbol: &str) -> Result<System, crate::error::Error> {
+        self.get_systems_as(system_symbol).await
+    }
+
+    /// [`Self::get_systems`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_systems_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<System>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -53,6 +65,11 @@ impl SpaceTradersClient<Anonymous> {
 impl SpaceTradersClient<Authenticated> {
     /// Return a paginated list of all systems.
     pub async fn list_systems(&self, page_limit: Option<u64>, page_index: Option<u64>) -> Result<(Vec<System>, Meta), crate::error::Error> {
+        self.list_systems_as(page_limit, page_index).await
+    }
+
+    /// [`Self::list_systems`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn list_systems_as<T: DeserializeOwned + Debug + Clone>(&self, page_limit: Option<u64>, page_index: Option<u64>) -> Result<(Vec<T>, Meta), crate::error::Error> {
         let (limit, page) = page_limit_and_index(page_limit, page_index);
         let response = self.get("systems")
             .query(&[("limit", limit), ("page", page)])
@@ -60,7 +77,7 @@ impl SpaceTradersClient<Authenticated> {
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(PaginationWrapper::<System>::deserialize(json)?.inner())
+                Ok(PaginationWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -72,11 +89,16 @@ impl SpaceTradersClient<Authenticated> {
 
     /// Get the details of a system.
     pub async fn get_systems(&self, system_symbol: &str) -> Result<System, crate::error::Error> {
+        self.get_systems_as(system_symbol).await
+    }
+
+    /// [`Self::get_systems`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_systems_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<System>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
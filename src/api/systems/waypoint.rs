@@ -1,5 +1,8 @@
-use serde::Deserialize;
+use std::fmt::Debug;
+
+use serde::{Deserialize, de::DeserializeOwned};
 use crate::{
+    api::fleet::ShipUpdate,
     client::{
         SpaceTradersClient,
         Anonymous,
@@ -13,7 +16,7 @@ use crate::{
         jump_gate::JumpGate,
         construction::Construction,
         trade_symbol::TradeSymbol,
-        ship::ship_cargo::ShipCargo
+        ship::{Ship, ship_cargo::ShipCargo}
     },
     utils::{wrapper::{PaginationWrapper, DataWrapper, ErrorWrapper}, pagination::page_limit_and_index},
     error::server_error::SpaceTraderError
@@ -24,6 +27,11 @@ impl SpaceTradersClient<Anonymous> {
     /// 
     /// If a waypoint is uncharted, it will return the Uncharted trait instead of its actual traits.
     pub async fn list_waypoints_in_system(&self, system_symbol: &str, page_limit: Option<u64>, page_index: Option<u64>, traits: Option<&[WaypointTraitType]>, waypoint_type: Option<WaypointType>) -> Result<(Vec<Waypoint>, Meta), crate::error::Error> {
+        self.list_waypoints_in_system_as(system_symbol, page_limit, page_index, traits, waypoint_type).await
+    }
+
+    /// [`Self::list_waypoints_in_system`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn list_waypoints_in_system_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, page_limit: Option<u64>, page_index: Option<u64>, traits: Option<&[WaypointTraitType]>, waypoint_type: Option<WaypointType>) -> Result<(Vec<T>, Meta), crate::error::Error> {
         let (limit, page) = page_limit_and_index(page_limit, page_index);
         let traits_query: Vec<(&str, WaypointTraitType)> = match traits {
             Some(traits) => traits.iter().map(|t| ("traits", *t)).collect::<Vec<_>>(),
@@ -41,7 +49,7 @@ impl SpaceTradersClient<Anonymous> {
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(PaginationWrapper::<Waypoint>::deserialize(json)?.inner())
+                Ok(PaginationWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -53,11 +61,16 @@ impl SpaceTradersClient<Anonymous> {
 
     /// Get the details of a system.
     pub async fn get_waypoint(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<Waypoint, crate::error::Error> {
+        self.get_waypoint_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_waypoint`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_waypoint_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<Waypoint>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -71,11 +84,16 @@ impl SpaceTradersClient<Anonymous> {
     /// 
     /// Send a ship to the waypoint to access trade good prices and recent transactions. Refer to the Market Overview page to gain better a understanding of the market in the game.
     pub async fn get_market(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<Market, crate::error::Error> {
+        self.get_market_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_market`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_market_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}/market")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<Market>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -87,11 +105,16 @@ impl SpaceTradersClient<Anonymous> {
 
     /// Get the shipyard for a waypoint. Requires a waypoint that has the Shipyard trait to use. Send a ship to the waypoint to access data on ships that are currently available for purchase and recent transactions.
     pub async fn get_shipyard(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<Shipyard, crate::error::Error> {
+        self.get_shipyard_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_shipyard`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_shipyard_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}/shipyard")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<Shipyard>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -105,11 +128,16 @@ impl SpaceTradersClient<Anonymous> {
     /// 
     /// Waypoints connected to this jump gate can be
     pub async fn get_jumpgate(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<JumpGate, crate::error::Error> {
+        self.get_jumpgate_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_jumpgate`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_jumpgate_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}/jump-gate")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<JumpGate>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -121,11 +149,16 @@ impl SpaceTradersClient<Anonymous> {
 
     /// Get construction details for a waypoint. Requires a waypoint with a property of isUnderConstruction to be true.
     pub async fn get_construction_site(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<Construction, crate::error::Error> {
+        self.get_construction_site_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_construction_site`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_construction_site_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}/construction")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<Construction>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -148,11 +181,22 @@ pub struct ConstructionAndCargo {
 }
 
 
+impl ShipUpdate for ConstructionAndCargo {
+    fn apply_to(&self, ship: &mut Ship) {
+        ship.cargo = self.cargo.clone();
+    }
+}
+
 impl SpaceTradersClient<Authenticated> {
     /// Return a paginated list of all of the waypoints for a given system.
     /// 
     /// If a waypoint is uncharted, it will return the Uncharted trait instead of its actual traits.
     pub async fn list_waypoints_in_system(&self, system_symbol: &str, page_limit: Option<u64>, page_index: Option<u64>, traits: Option<&[WaypointTraitType]>, waypoint_type: Option<WaypointType>) -> Result<(Vec<Waypoint>, Meta), crate::error::Error> {
+        self.list_waypoints_in_system_as(system_symbol, page_limit, page_index, traits, waypoint_type).await
+    }
+
+    /// [`Self::list_waypoints_in_system`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn list_waypoints_in_system_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, page_limit: Option<u64>, page_index: Option<u64>, traits: Option<&[WaypointTraitType]>, waypoint_type: Option<WaypointType>) -> Result<(Vec<T>, Meta), crate::error::Error> {
         let (limit, page) = page_limit_and_index(page_limit, page_index);
         let traits_query: Vec<(&str, WaypointTraitType)> = match traits {
             Some(traits) => traits.iter().map(|t| ("traits", *t)).collect::<Vec<_>>(),
@@ -170,7 +214,7 @@ impl SpaceTradersClient<Authenticated> {
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(PaginationWrapper::<Waypoint>::deserialize(json)?.inner())
+                Ok(PaginationWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -182,11 +226,16 @@ impl SpaceTradersClient<Authenticated> {
 
     /// Get the details of a system.
     pub async fn get_waypoint(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<Waypoint, crate::error::Error> {
+        self.get_waypoint_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_waypoint`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_waypoint_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<Waypoint>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -200,11 +249,16 @@ impl SpaceTradersClient<Authenticated> {
     /// 
     /// Send a ship to the waypoint to access trade good prices and recent transactions. Refer to the Market Overview page to gain better a understanding of the market in the game.
     pub async fn get_market(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<Market, crate::error::Error> {
+        self.get_market_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_market`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_market_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}/market")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<Market>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -217,11 +271,16 @@ impl SpaceTradersClient<Authenticated> {
 
     /// Get the shipyard for a waypoint. Requires a waypoint that has the Shipyard trait to use. Send a ship to the waypoint to access data on ships that are currently available for purchase and recent transactions.
     pub async fn get_shipyard(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<Shipyard, crate::error::Error> {
+        self.get_shipyard_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_shipyard`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_shipyard_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}/shipyard")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<Shipyard>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -235,11 +294,16 @@ impl SpaceTradersClient<Authenticated> {
     /// 
     /// Waypoints connected to this jump gate can be
     pub async fn get_jumpgate(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<JumpGate, crate::error::Error> {
+        self.get_jumpgate_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_jumpgate`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_jumpgate_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}/jump-gate")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<JumpGate>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
@@ -251,11 +315,16 @@ impl SpaceTradersClient<Authenticated> {
 
     /// Get construction details for a waypoint. Requires a waypoint with a property of isUnderConstruction to be true.
     pub async fn get_construction_site(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<Construction, crate::error::Error> {
+        self.get_construction_site_as(system_symbol, waypoint_symbol).await
+    }
+
+    /// [`Self::get_construction_site`], with the data deserialized into any type, such as raw json.
+    pub(crate) async fn get_construction_site_as<T: DeserializeOwned + Debug + Clone>(&self, system_symbol: &str, waypoint_symbol: &str) -> Result<T, crate::error::Error> {
         let response = self.get(&format!("systems/{system_symbol}/waypoints/{waypoint_symbol}/construction")).send().await?;
         match response.status().as_u16() {
             200 => {
                 let json = response.json::<serde_json::Value>().await?;
-                Ok(DataWrapper::<Construction>::deserialize(json)?.inner())
+                Ok(DataWrapper::<T>::deserialize(json)?.inner())
             },
             status => {
                 let json = response.json::<serde_json::Value>().await?;
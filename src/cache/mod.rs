@@ -0,0 +1,613 @@
+//* Cache of the universe data, in front of the systems endpoints.
+//* Responses are stored as raw json, so the store does not depend on the schemas being serializable.
+
+pub mod snapshot;
+pub mod source;
+
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use crate::{
+    error::Error,
+    navigation::system_symbol,
+    schemas::{
+        construction::Construction,
+        faction::Faction,
+        jump_gate::JumpGate,
+        market::Market,
+        shipyard::Shipyard,
+        system::System,
+        waypoint::Waypoint,
+    },
+};
+use self::source::{UniverseRequest, UniverseSource};
+
+/// Kind of data stored in the cache.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CacheKind {
+    /// A system, keyed by its symbol.
+    System,
+    /// Every waypoint of a system, keyed by the system symbol.
+    SystemWaypoints,
+    /// A waypoint, keyed by its symbol.
+    Waypoint,
+    /// A market, keyed by its waypoint symbol.
+    Market,
+    /// A shipyard, keyed by its waypoint symbol.
+    Shipyard,
+    /// A jump gate, keyed by its waypoint symbol.
+    JumpGate,
+    /// A construction site, keyed by its waypoint symbol.
+    Construction,
+    /// A faction, keyed by its symbol.
+    Faction,
+}
+
+/// How long cached data stays fresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ttl {
+    /// The data never changes, and is kept until invalidated.
+    Forever,
+    /// The data is fetched again once older than the duration.
+    Expires(Duration),
+}
+
+impl Ttl {
+    /// Whether data fetched at the given time is still fresh.
+    pub fn is_fresh(&self, fetched_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        match self {
+            Ttl::Forever => true,
+            Ttl::Expires(ttl) => (now - fetched_at).to_std().map_or(true, |age| age < *ttl),
+        }
+    }
+}
+
+/// Time to live of each kind of cached data.
+///
+/// Construction sites are kept forever once complete, whatever their time to live.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// Systems.
+    pub system: Ttl,
+    /// Lists of the waypoints of a system, which change as waypoints are charted or built.
+    pub system_waypoints: Ttl,
+    /// Waypoints, whose traits and modifiers change as they are charted or built.
+    pub waypoint: Ttl,
+    /// Markets, whose prices change often.
+    pub market: Ttl,
+    /// Shipyards.
+    pub shipyard: Ttl,
+    /// Jump gates.
+    pub jump_gate: Ttl,
+    /// Construction sites that are not complete yet.
+    pub construction: Ttl,
+    /// Factions.
+    pub faction: Ttl,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        CachePolicy {
+            system: Ttl::Forever,
+            system_waypoints: Ttl::Expires(Duration::from_secs(24 * 60 * 60)),
+            waypoint: Ttl::Expires(Duration::from_secs(24 * 60 * 60)),
+            market: Ttl::Expires(Duration::from_secs(5 * 60)),
+            shipyard: Ttl::Expires(Duration::from_secs(15 * 60)),
+            jump_gate: Ttl::Forever,
+            construction: Ttl::Expires(Duration::from_secs(5 * 60)),
+            faction: Ttl::Expires(Duration::from_secs(60 * 60)),
+        }
+    }
+}
+
+impl CachePolicy {
+    /// Time to live of a kind of data.
+    pub fn ttl(&self, kind: CacheKind) -> Ttl {
+        match kind {
+            CacheKind::System => self.system,
+            CacheKind::SystemWaypoints => self.system_waypoints,
+            CacheKind::Waypoint => self.waypoint,
+            CacheKind::Market => self.market,
+            CacheKind::Shipyard => self.shipyard,
+            CacheKind::JumpGate => self.jump_gate,
+            CacheKind::Construction => self.construction,
+            CacheKind::Faction => self.faction,
+        }
+    }
+}
+
+/// Data stored in the cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheEntry {
+    /// When the data was fetched.
+    pub fetched_at: DateTime<Utc>,
+    /// The data, as sent by the server.
+    pub data: serde_json::Value,
+}
+
+/// When the on-disk store is compacted automatically, as replaced and invalidated entries pile up in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompactionThreshold {
+    /// Never compact a store with fewer records than this.
+    pub min_records: usize,
+    /// Compact once the store has this many records for each current entry.
+    pub ratio: f64,
+}
+
+impl Default for CompactionThreshold {
+    fn default() -> Self {
+        CompactionThreshold {
+            min_records: 1000,
+            ratio: 2.,
+        }
+    }
+}
+
+impl CompactionThreshold {
+    /// Whether a store with the given number of records and entries should be compacted.
+    pub fn is_reached(&self, records: usize, entries: usize) -> bool {
+        records >= self.min_records && records as f64 >= self.ratio * entries as f64
+    }
+}
+
+/// A line of the on-disk store. Entries without data are invalidations.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Record {
+    kind: CacheKind,
+    key: String,
+    fetched_at: DateTime<Utc>,
+    data: Option<serde_json::Value>,
+}
+
+/// Cache of systems, waypoints, markets, shipyards, jump gates, construction sites and factions.
+///
+/// The cache can be backed by a JSON Lines file: every change is appended to it,
+/// and the file is read back when the cache is opened again, so a restarted bot doesn't fetch everything again.
+/// The file is compacted once it reaches the [`CompactionThreshold`].
+#[derive(Debug)]
+pub struct UniverseCache {
+    policy: CachePolicy,
+    entries: BTreeMap<(CacheKind, String), CacheEntry>,
+    path: Option<PathBuf>,
+    bypass: bool,
+    compaction: Option<CompactionThreshold>,
+    /// Number of lines of the on-disk store.
+    records_on_disk: usize,
+}
+
+impl UniverseCache {
+    /// Create a cache that is only kept in memory.
+    pub fn in_memory(policy: CachePolicy) -> UniverseCache {
+        UniverseCache {
+            policy,
+            entries: BTreeMap::new(),
+            path: None,
+            bypass: false,
+            compaction: Some(CompactionThreshold::default()),
+            records_on_disk: 0,
+        }
+    }
+
+    /// Open the cache stored in the file, or create it if the file doesn't exist.
+    ///
+    /// Lines that can't be parsed, such as a line partially written before a crash, are ignored.
+    pub fn open(path: impl AsRef<Path>, policy: CachePolicy) -> Result<UniverseCache, Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut cache = UniverseCache::in_memory(policy);
+        if path.exists() {
+            for line in BufReader::new(File::open(&path)?).lines() {
+                cache.records_on_disk += 1;
+                if let Ok(record) = serde_json::from_str::<Record>(&line?) {
+                    cache.apply(record);
+                }
+            }
+        }
+        cache.path = Some(path);
+        cache.compact_if_needed()?;
+        Ok(cache)
+    }
+
+    fn apply(&mut self, record: Record) {
+        let key = (record.kind, record.key);
+        match record.data {
+            Some(data) => {
+                self.entries.insert(key, CacheEntry { fetched_at: record.fetched_at, data });
+            },
+            None => {
+                self.entries.remove(&key);
+            },
+        }
+    }
+
+    fn persist(&mut self, records: &[Record]) -> Result<(), Error> {
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            let mut buffer = Vec::new();
+            for record in records {
+                serde_json::to_writer(&mut buffer, record)?;
+                buffer.push(b'\n');
+            }
+            file.write_all(&buffer)?;
+            self.records_on_disk += records.len();
+        }
+        Ok(())
+    }
+
+    /// The time to live of the cached data.
+    pub fn policy(&self) -> &CachePolicy {
+        &self.policy
+    }
+
+    /// When bypassed, the cache is never read: data is always fetched again, and the cache is updated with it.
+    pub fn set_bypass(&mut self, bypass: bool) {
+        self.bypass = bypass;
+    }
+
+    /// Whether the cache is bypassed.
+    pub fn is_bypassed(&self) -> bool {
+        self.bypass
+    }
+
+    /// Set when the on-disk store is compacted automatically, never if `None`.
+    pub fn set_compaction(&mut self, compaction: Option<CompactionThreshold>) {
+        self.compaction = compaction;
+    }
+
+    /// Number of cached entries, fresh or not.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over every cached entry, fresh or not.
+    pub fn entries(&self) -> impl Iterator<Item = (CacheKind, &str, &CacheEntry)> + '_ {
+        self.entries.iter().map(|((kind, key), entry)| (*kind, key.as_str(), entry))
+    }
+
+    /// Whether the entry is still fresh at the given time.
+    pub fn is_fresh(&self, kind: CacheKind, entry: &CacheEntry, now: DateTime<Utc>) -> bool {
+        if kind == CacheKind::Construction && entry.data["isComplete"] == serde_json::Value::Bool(true) {
+            return true;
+        }
+        self.policy.ttl(kind).is_fresh(entry.fetched_at, now)
+    }
+
+    /// The cached entry, if it is still fresh at the given time.
+    pub fn get_entry(&self, kind: CacheKind, key: &str, now: DateTime<Utc>) -> Option<&CacheEntry> {
+        self.entries.get(&(kind, key.to_string()))
+            .filter(|entry| self.is_fresh(kind, entry, now))
+    }
+
+    /// The cached data, if it is still fresh at the given time.
+    pub fn get<T: DeserializeOwned>(&self, kind: CacheKind, key: &str, now: DateTime<Utc>) -> Result<Option<T>, Error> {
+        match self.get_entry(kind, key, now) {
+            Some(entry) => Ok(Some(T::deserialize(&entry.data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Store data fetched at the given time.
+    pub fn insert(&mut self, kind: CacheKind, key: &str, data: serde_json::Value, fetched_at: DateTime<Utc>) -> Result<(), Error> {
+        self.insert_all([(kind, key.to_string(), data)], fetched_at)
+    }
+
+    /// Store several entries fetched at the given time, in a single write to the on-disk store.
+    pub fn insert_all(&mut self, entries: impl IntoIterator<Item = (CacheKind, String, serde_json::Value)>, fetched_at: DateTime<Utc>) -> Result<(), Error> {
+        let records = entries.into_iter()
+            .map(|(kind, key, data)| Record { kind, key, fetched_at, data: Some(data) })
+            .collect::<Vec<_>>();
+        self.persist(&records)?;
+        for record in records {
+            self.apply(record);
+        }
+        self.compact_if_needed()
+    }
+
+    /// Forget an entry, so it is fetched again the next time it is requested.
+    pub fn invalidate(&mut self, kind: CacheKind, key: &str) -> Result<(), Error> {
+        self.invalidate_where(|entry_kind, entry_key| entry_kind == kind && entry_key == key)
+    }
+
+    /// Forget every entry of a system: the system itself, and everything at its waypoints.
+    pub fn invalidate_system(&mut self, system: &str) -> Result<(), Error> {
+        self.invalidate_where(|_, key| key == system || system_symbol(key) == system)
+    }
+
+    /// Forget every entry of a kind.
+    pub fn invalidate_kind(&mut self, kind: CacheKind) -> Result<(), Error> {
+        self.invalidate_where(|entry_kind, _| entry_kind == kind)
+    }
+
+    fn invalidate_where(&mut self, predicate: impl Fn(CacheKind, &str) -> bool) -> Result<(), Error> {
+        let now = Utc::now();
+        let records = self.entries.keys()
+            .filter(|(kind, key)| predicate(*kind, key))
+            .map(|(kind, key)| Record { kind: *kind, key: key.clone(), fetched_at: now, data: None })
+            .collect::<Vec<_>>();
+        self.persist(&records)?;
+        for record in records {
+            self.apply(record);
+        }
+        self.compact_if_needed()
+    }
+
+    /// Rewrite the on-disk store with only the current entries, dropping replaced and invalidated ones.
+    pub fn compact(&mut self) -> Result<(), Error> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let temporary = path.with_extension("compact");
+        let mut buffer = Vec::new();
+        for ((kind, key), entry) in self.entries.iter() {
+            let record = Record { kind: *kind, key: key.clone(), fetched_at: entry.fetched_at, data: Some(entry.data.clone()) };
+            serde_json::to_writer(&mut buffer, &record)?;
+            buffer.push(b'\n');
+        }
+        std::fs::write(&temporary, buffer)?;
+        std::fs::rename(&temporary, path)?;
+        self.records_on_disk = self.entries.len();
+        Ok(())
+    }
+
+    fn compact_if_needed(&mut self) -> Result<(), Error> {
+        match self.compaction {
+            Some(compaction) if self.path.is_some() && compaction.is_reached(self.records_on_disk, self.entries.len()) => self.compact(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Return the cached data if fresh, or fetch it and cache it.
+    async fn fetch<T: DeserializeOwned, S: UniverseSource>(&mut self, source: &S, kind: CacheKind, key: &str, request: UniverseRequest) -> Result<T, Error> {
+        if !self.bypass {
+            if let Some(cached) = self.get(kind, key, Utc::now())? {
+                return Ok(cached);
+            }
+        }
+        let data = source.fetch(&request).await?.data;
+        let value = T::deserialize(&data)?;
+        self.insert(kind, key, data, Utc::now())?;
+        Ok(value)
+    }
+
+    /// Get the details of a system.
+    pub async fn system<S: UniverseSource>(&mut self, source: &S, system_symbol: &str) -> Result<System, Error> {
+        self.fetch(source, CacheKind::System, system_symbol, UniverseRequest::System(system_symbol.to_string())).await
+    }
+
+    /// Get every waypoint of a system. Each waypoint is also cached on its own, written with the list at once.
+    pub async fn waypoints_in_system<S: UniverseSource>(&mut self, source: &S, system_symbol: &str) -> Result<Vec<Waypoint>, Error> {
+        if !self.bypass {
+            if let Some(cached) = self.get(CacheKind::SystemWaypoints, system_symbol, Utc::now())? {
+                return Ok(cached);
+            }
+        }
+        let mut data = Vec::new();
+        let mut page = 1;
+        loop {
+            let response = source.fetch(&UniverseRequest::Waypoints { system: system_symbol.to_string(), page }).await?;
+            if let serde_json::Value::Array(batch) = response.data {
+                data.extend(batch);
+            }
+            if !response.meta.is_some_and(|meta| meta.has_next_page()) {
+                break;
+            }
+            page += 1;
+        }
+        let waypoints = Vec::<Waypoint>::deserialize(&serde_json::Value::Array(data.clone()))?;
+        let entries = waypoints.iter()
+            .zip(data.iter())
+            .map(|(waypoint, json)| (CacheKind::Waypoint, waypoint.symbol.clone(), json.clone()))
+            .chain([(CacheKind::SystemWaypoints, system_symbol.to_string(), serde_json::Value::Array(data.clone()))])
+            .collect::<Vec<_>>();
+        self.insert_all(entries, Utc::now())?;
+        Ok(waypoints)
+    }
+
+    /// Get the details of a waypoint.
+    pub async fn waypoint<S: UniverseSource>(&mut self, source: &S, waypoint_symbol: &str) -> Result<Waypoint, Error> {
+        self.fetch(source, CacheKind::Waypoint, waypoint_symbol, UniverseRequest::Waypoint(waypoint_symbol.to_string())).await
+    }
+
+    /// Get the market at a waypoint.
+    pub async fn market<S: UniverseSource>(&mut self, source: &S, waypoint_symbol: &str) -> Result<Market, Error> {
+        self.fetch(source, CacheKind::Market, waypoint_symbol, UniverseRequest::Market(waypoint_symbol.to_string())).await
+    }
+
+    /// Get the shipyard at a waypoint.
+    pub async fn shipyard<S: UniverseSource>(&mut self, source: &S, waypoint_symbol: &str) -> Result<Shipyard, Error> {
+        self.fetch(source, CacheKind::Shipyard, waypoint_symbol, UniverseRequest::Shipyard(waypoint_symbol.to_string())).await
+    }
+
+    /// Get the jump gate at a waypoint.
+    pub async fn jump_gate<S: UniverseSource>(&mut self, source: &S, waypoint_symbol: &str) -> Result<JumpGate, Error> {
+        self.fetch(source, CacheKind::JumpGate, waypoint_symbol, UniverseRequest::JumpGate(waypoint_symbol.to_string())).await
+    }
+
+    /// Get the construction site at a waypoint.
+    pub async fn construction<S: UniverseSource>(&mut self, source: &S, waypoint_symbol: &str) -> Result<Construction, Error> {
+        self.fetch(source, CacheKind::Construction, waypoint_symbol, UniverseRequest::Construction(waypoint_symbol.to_string())).await
+    }
+
+    /// Get the details of a faction.
+    pub async fn faction<S: UniverseSource>(&mut self, source: &S, faction_symbol: &str) -> Result<Faction, Error> {
+        self.fetch(source, CacheKind::Faction, faction_symbol, UniverseRequest::Faction(faction_symbol.to_string())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{cache::source::UniverseResponse, conformance::load_fixture, schemas::meta::Meta};
+
+    /// Answers every request with a fixture, and counts the requests.
+    struct FixtureSource {
+        requests: AtomicUsize,
+    }
+
+    impl FixtureSource {
+        fn new() -> FixtureSource {
+            FixtureSource { requests: AtomicUsize::new(0) }
+        }
+
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+    }
+
+    impl UniverseSource for FixtureSource {
+        async fn fetch(&self, request: &UniverseRequest) -> Result<UniverseResponse, Error> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            let fixture = match request {
+                UniverseRequest::Systems { .. } => "get-systems",
+                UniverseRequest::System(_) => "get-system",
+                UniverseRequest::Waypoints { .. } => "get-system-waypoints",
+                UniverseRequest::Waypoint(_) => "get-waypoint",
+                UniverseRequest::Market(_) => "get-market",
+                UniverseRequest::Shipyard(_) => "get-shipyard",
+                UniverseRequest::JumpGate(_) => "get-jump-gate",
+                UniverseRequest::Construction(_) => "get-construction",
+                UniverseRequest::Faction(_) => "get-faction",
+            };
+            let mut json = load_fixture(&format!("responses/{fixture}.json"));
+            Ok(UniverseResponse {
+                data: json["data"].take(),
+                meta: Option::<Meta>::deserialize(&json["meta"])?,
+            })
+        }
+    }
+
+    fn temporary_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("space-traders-cache-{name}-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[tokio::test]
+    async fn cached_requests() {
+        let source = FixtureSource::new();
+        let mut cache = UniverseCache::in_memory(CachePolicy::default());
+        cache.market(&source, "X1-A-M1").await.unwrap();
+        cache.market(&source, "X1-A-M1").await.unwrap();
+        cache.system(&source, "X1-A").await.unwrap();
+        assert_eq!(source.requests(), 2);
+
+        cache.set_bypass(true);
+        cache.market(&source, "X1-A-M1").await.unwrap();
+        assert_eq!(source.requests(), 3);
+        cache.set_bypass(false);
+
+        cache.invalidate(CacheKind::Market, "X1-A-M1").unwrap();
+        cache.market(&source, "X1-A-M1").await.unwrap();
+        assert_eq!(source.requests(), 4);
+
+        cache.invalidate_system("X1-A").unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn system_waypoints() {
+        let source = FixtureSource::new();
+        let mut cache = UniverseCache::in_memory(CachePolicy::default());
+        let waypoints = cache.waypoints_in_system(&source, "X1-A").await.unwrap();
+        assert_eq!(cache.len(), waypoints.len() + 1);
+        cache.waypoint(&source, &waypoints[0].symbol).await.unwrap();
+        cache.waypoints_in_system(&source, "X1-A").await.unwrap();
+        assert_eq!(source.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn system_waypoints_on_disk() {
+        let path = temporary_file("waypoints");
+        let source = FixtureSource::new();
+        let mut cache = UniverseCache::open(&path, CachePolicy::default()).unwrap();
+        let waypoints = cache.waypoints_in_system(&source, "X1-A").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), waypoints.len() + 1);
+        let reopened = UniverseCache::open(&path, CachePolicy::default()).unwrap();
+        assert!(reopened.get_entry(CacheKind::Waypoint, &waypoints[0].symbol, Utc::now()).is_some());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn time_to_live() {
+        let mut cache = UniverseCache::in_memory(CachePolicy::default());
+        let fetched_at = Utc::now();
+        let later = fetched_at + chrono::Duration::minutes(10);
+        cache.insert(CacheKind::Market, "X1-A-M1", serde_json::json!({}), fetched_at).unwrap();
+        cache.insert(CacheKind::System, "X1-A", serde_json::json!({}), fetched_at).unwrap();
+        cache.insert(CacheKind::Waypoint, "X1-A-B1", serde_json::json!({}), fetched_at).unwrap();
+        cache.insert(CacheKind::Construction, "X1-A-I1", serde_json::json!({ "isComplete": false }), fetched_at).unwrap();
+        cache.insert(CacheKind::Construction, "X1-A-I2", serde_json::json!({ "isComplete": true }), fetched_at).unwrap();
+        assert!(cache.get_entry(CacheKind::Market, "X1-A-M1", fetched_at).is_some());
+        assert!(cache.get_entry(CacheKind::Market, "X1-A-M1", later).is_none());
+        assert!(cache.get_entry(CacheKind::System, "X1-A", later).is_some());
+        assert!(cache.get_entry(CacheKind::Waypoint, "X1-A-B1", later).is_some());
+        assert!(cache.get_entry(CacheKind::Waypoint, "X1-A-B1", fetched_at + chrono::Duration::days(1)).is_none());
+        assert!(cache.get_entry(CacheKind::Construction, "X1-A-I1", later).is_none());
+        assert!(cache.get_entry(CacheKind::Construction, "X1-A-I2", later).is_some());
+    }
+
+    #[test]
+    fn persistence() {
+        let path = temporary_file("persistence");
+        let now = Utc::now();
+        {
+            let mut cache = UniverseCache::open(&path, CachePolicy::default()).unwrap();
+            cache.insert(CacheKind::System, "X1-A", serde_json::json!({ "symbol": "X1-A" }), now).unwrap();
+            cache.insert(CacheKind::System, "X1-B", serde_json::json!({ "symbol": "X1-B" }), now).unwrap();
+            cache.insert(CacheKind::System, "X1-A", serde_json::json!({ "symbol": "X1-A", "x": 1 }), now).unwrap();
+            cache.invalidate(CacheKind::System, "X1-B").unwrap();
+        }
+        // a line cut short by a crash is ignored
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"kind\":\"SYS").unwrap();
+
+        let mut cache = UniverseCache::open(&path, CachePolicy::default()).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get_entry(CacheKind::System, "X1-A", now).unwrap().data["x"], 1);
+        cache.compact().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        assert_eq!(UniverseCache::open(&path, CachePolicy::default()).unwrap().len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn automatic_compaction() {
+        let path = temporary_file("compaction");
+        let now = Utc::now();
+        let mut cache = UniverseCache::open(&path, CachePolicy::default()).unwrap();
+        cache.set_compaction(Some(CompactionThreshold { min_records: 4, ratio: 2. }));
+        for x in 0..10 {
+            cache.insert(CacheKind::System, "X1-A", serde_json::json!({ "x": x }), now).unwrap();
+        }
+        // compacted to a single line every time the store reached 4 lines
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        cache.insert(CacheKind::System, "X1-B", serde_json::json!({}), now).unwrap();
+        cache.insert(CacheKind::System, "X1-C", serde_json::json!({}), now).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        cache.set_compaction(None);
+        for _ in 0..10 {
+            cache.insert(CacheKind::System, "X1-A", serde_json::json!({}), now).unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 13);
+        // the default threshold leaves small stores alone
+        assert!(!CompactionThreshold::default().is_reached(13, 3));
+        assert!(CompactionThreshold::default().is_reached(1000, 3));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use crate::{
+    error::Error,
+    schemas::{
+        construction::Construction,
+        faction::Faction,
+        jump_gate::JumpGate,
+        market::Market,
+        shipyard::Shipyard,
+        system::System,
+        waypoint::Waypoint,
+    },
+};
+use super::{CacheKind, UniverseCache};
+
+/// Name of the format written in the header of a snapshot.
+pub const SNAPSHOT_FORMAT: &str = "space-traders-universe";
+/// Version of the snapshot format written by this crate.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// First line of a snapshot.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotHeader {
+    /// Always [`SNAPSHOT_FORMAT`].
+    pub format: String,
+    /// Version of the format.
+    pub version: u32,
+    /// When the snapshot was taken.
+    pub created_at: DateTime<Utc>,
+}
+
+/// A line of a snapshot, after the header.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRecord {
+    /// Kind of the data.
+    pub kind: CacheKind,
+    /// Symbol the data is keyed by in the cache.
+    pub key: String,
+    /// When the data was fetched.
+    pub fetched_at: DateTime<Utc>,
+    /// The data, as sent by the server.
+    pub data: serde_json::Value,
+}
+
+/// Everything known about the universe at some point, as a JSON Lines archive:
+/// a [`SnapshotHeader`] line followed by one [`SnapshotRecord`] line per item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniverseSnapshot {
+    /// Format, version and date of the snapshot.
+    pub header: SnapshotHeader,
+    /// Items of the snapshot.
+    pub records: Vec<SnapshotRecord>,
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+}
+
+impl UniverseSnapshot {
+    /// Take a snapshot of every entry of the cache, fresh or not.
+    pub fn from_cache(cache: &UniverseCache) -> UniverseSnapshot {
+        UniverseSnapshot {
+            header: SnapshotHeader {
+                format: SNAPSHOT_FORMAT.to_string(),
+                version: SNAPSHOT_VERSION,
+                created_at: Utc::now(),
+            },
+            records: cache.entries()
+                .map(|(kind, key, entry)| SnapshotRecord {
+                    kind,
+                    key: key.to_string(),
+                    fetched_at: entry.fetched_at,
+                    data: entry.data.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Write the snapshot as JSON Lines.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = BufWriter::new(writer);
+        serde_json::to_writer(&mut writer, &self.header)?;
+        writer.write_all(b"\n")?;
+        for record in self.records.iter() {
+            serde_json::to_writer(&mut writer, record)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`Self::write`].
+    ///
+    /// Fails if the header is missing, or if the snapshot was written with a newer version of the format.
+    /// Records of kinds unknown to this version are skipped.
+    pub fn read<R: BufRead>(reader: R) -> Result<UniverseSnapshot, Error> {
+        let mut lines = reader.lines();
+        let header: SnapshotHeader = match lines.next() {
+            Some(line) => serde_json::from_str(&line?)?,
+            None => return Err(invalid_data("empty snapshot".to_string())),
+        };
+        if header.format != SNAPSHOT_FORMAT {
+            return Err(invalid_data(format!("unknown snapshot format {}", header.format)));
+        }
+        if header.version > SNAPSHOT_VERSION {
+            return Err(invalid_data(format!("unsupported snapshot version {}", header.version)));
+        }
+        let mut records = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let json: serde_json::Value = serde_json::from_str(&line)?;
+            if CacheKind::deserialize(&json["kind"]).is_err() {
+                continue;
+            }
+            records.push(SnapshotRecord::deserialize(json)?);
+        }
+        Ok(UniverseSnapshot { header, records })
+    }
+
+    /// Write the snapshot to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.write(File::create(path)?)
+    }
+
+    /// Read a snapshot from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<UniverseSnapshot, Error> {
+        UniverseSnapshot::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Load the records into the cache. Entries of the cache fetched more recently are kept.
+    /// Returns the number of records loaded.
+    pub fn import_into(&self, cache: &mut UniverseCache) -> Result<usize, Error> {
+        let mut imported = 0;
+        for record in self.records.iter() {
+            let newer_in_cache = cache.entries.get(&(record.kind, record.key.clone()))
+                .is_some_and(|entry| entry.fetched_at >= record.fetched_at);
+            if !newer_in_cache {
+                cache.insert(record.kind, &record.key, record.data.clone(), record.fetched_at)?;
+                imported += 1;
+            }
+        }
+        Ok(imported)
+    }
+
+    /// Every item of a kind, parsed into the crate's type.
+    pub fn items<T: DeserializeOwned>(&self, kind: CacheKind) -> Result<Vec<T>, Error> {
+        self.records.iter()
+            .filter(|record| record.kind == kind)
+            .map(|record| Ok(T::deserialize(&record.data)?))
+            .collect()
+    }
+
+    /// Every system of the snapshot.
+    pub fn systems(&self) -> Result<Vec<System>, Error> {
+        self.items(CacheKind::System)
+    }
+
+    /// Every waypoint of the snapshot.
+    pub fn waypoints(&self) -> Result<Vec<Waypoint>, Error> {
+        self.items(CacheKind::Waypoint)
+    }
+
+    /// Every market of the snapshot.
+    pub fn markets(&self) -> Result<Vec<Market>, Error> {
+        self.items(CacheKind::Market)
+    }
+
+    /// Every shipyard of the snapshot.
+    pub fn shipyards(&self) -> Result<Vec<Shipyard>, Error> {
+        self.items(CacheKind::Shipyard)
+    }
+
+    /// Every jump gate of the snapshot.
+    pub fn jump_gates(&self) -> Result<Vec<JumpGate>, Error> {
+        self.items(CacheKind::JumpGate)
+    }
+
+    /// Every construction site of the snapshot.
+    pub fn constructions(&self) -> Result<Vec<Construction>, Error> {
+        self.items(CacheKind::Construction)
+    }
+
+    /// Every faction of the snapshot.
+    pub fn factions(&self) -> Result<Vec<Faction>, Error> {
+        self.items(CacheKind::Faction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cache::CachePolicy, conformance::load_fixture};
+
+    fn cache() -> UniverseCache {
+        let mut cache = UniverseCache::in_memory(CachePolicy::default());
+        let now = Utc::now();
+        for (kind, fixture) in [
+            (CacheKind::System, "get-system"),
+            (CacheKind::Waypoint, "get-waypoint"),
+            (CacheKind::Market, "get-market"),
+            (CacheKind::Shipyard, "get-shipyard"),
+            (CacheKind::JumpGate, "get-jump-gate"),
+            (CacheKind::Construction, "get-construction"),
+            (CacheKind::Faction, "get-faction"),
+        ] {
+            let data = load_fixture(&format!("responses/{fixture}.json"))["data"].clone();
+            let key = data["symbol"].as_str().unwrap().to_string();
+            cache.insert(kind, &key, data, now).unwrap();
+        }
+        cache
+    }
+
+    #[test]
+    fn round_trip() {
+        let snapshot = UniverseSnapshot::from_cache(&cache());
+        let mut buffer = Vec::new();
+        snapshot.write(&mut buffer).unwrap();
+        assert_eq!(buffer.iter().filter(|b| **b == b'\n').count(), 8);
+
+        let read = UniverseSnapshot::read(buffer.as_slice()).unwrap();
+        assert_eq!(read, snapshot);
+        assert_eq!(read.systems().unwrap().len(), 1);
+        assert_eq!(read.waypoints().unwrap().len(), 1);
+        assert_eq!(read.markets().unwrap().len(), 1);
+        assert_eq!(read.shipyards().unwrap().len(), 1);
+        assert_eq!(read.jump_gates().unwrap().len(), 1);
+        assert_eq!(read.constructions().unwrap().len(), 1);
+        assert_eq!(read.factions().unwrap().len(), 1);
+
+        let mut other = UniverseCache::in_memory(CachePolicy::default());
+        assert_eq!(read.import_into(&mut other).unwrap(), 7);
+        // entries are not replaced by older ones
+        assert_eq!(read.import_into(&mut other).unwrap(), 0);
+    }
+
+    #[test]
+    fn versions() {
+        let header = |version: u32| format!(r#"{{"format":"{SNAPSHOT_FORMAT}","version":{version},"createdAt":"2023-11-20T14:51:22.716Z"}}"#);
+        let record = r#"{"kind":"SOMETHING_NEW","key":"X1-A","fetchedAt":"2023-11-20T14:51:22.716Z","data":{}}"#;
+        let snapshot = UniverseSnapshot::read(format!("{}\n{record}\n", header(1)).as_bytes()).unwrap();
+        assert!(snapshot.records.is_empty());
+        assert!(UniverseSnapshot::read(header(2).as_bytes()).is_err());
+        assert!(UniverseSnapshot::read("".as_bytes()).is_err());
+    }
+}
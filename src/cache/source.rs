@@ -0,0 +1,105 @@
+use std::{future::Future, sync::Arc};
+
+use crate::{
+    client::{SpaceTradersClient, Anonymous, Authenticated, rate_limit::RateLimiter},
+    error::Error,
+    navigation::system_symbol,
+    schemas::meta::Meta,
+};
+
+/// Number of items requested per page, the most the server allows.
+pub const PAGE_LIMIT: u64 = 20;
+
+/// Universe data that can be requested from a [`UniverseSource`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UniverseRequest {
+    /// A page of the list of systems.
+    Systems { page: u64 },
+    /// A system, by symbol.
+    System(String),
+    /// A page of the waypoints of a system.
+    Waypoints { system: String, page: u64 },
+    /// A waypoint, by symbol.
+    Waypoint(String),
+    /// The market at a waypoint.
+    Market(String),
+    /// The shipyard at a waypoint.
+    Shipyard(String),
+    /// The jump gate at a waypoint.
+    JumpGate(String),
+    /// The construction site at a waypoint.
+    Construction(String),
+    /// A faction, by symbol.
+    Faction(String),
+}
+
+/// Answer to a [`UniverseRequest`].
+#[derive(Debug, Clone)]
+pub struct UniverseResponse {
+    /// The data of the response as sent by the server: an item, or an array of items for a page.
+    pub data: serde_json::Value,
+    /// Pagination of a page, `None` for a single item.
+    pub meta: Option<Meta>,
+}
+
+/// Something the universe data can be fetched from, usually a client.
+///
+/// The data is returned as raw json, so it can be stored as is.
+pub trait UniverseSource {
+    /// Fetch the requested data.
+    fn fetch(&self, request: &UniverseRequest) -> impl Future<Output = Result<UniverseResponse, Error>> + Send;
+}
+
+macro_rules! impl_universe_source {
+    ($($auth:ty),*) => {
+        $(
+            impl UniverseSource for SpaceTradersClient<$auth> {
+                async fn fetch(&self, request: &UniverseRequest) -> Result<UniverseResponse, Error> {
+                    let page = |(data, meta): (Vec<serde_json::Value>, Meta)| UniverseResponse { data: data.into(), meta: Some(meta) };
+                    let item = |data| UniverseResponse { data, meta: None };
+                    Ok(match request {
+                        UniverseRequest::Systems { page: index } => page(self.list_systems_as(Some(PAGE_LIMIT), Some(*index)).await?),
+                        UniverseRequest::System(symbol) => item(self.get_systems_as(symbol).await?),
+                        UniverseRequest::Waypoints { system, page: index } => {
+                            page(self.list_waypoints_in_system_as(system, Some(PAGE_LIMIT), Some(*index), None, None).await?)
+                        },
+                        UniverseRequest::Waypoint(symbol) => item(self.get_waypoint_as(system_symbol(symbol), symbol).await?),
+                        UniverseRequest::Market(symbol) => item(self.get_market_as(system_symbol(symbol), symbol).await?),
+                        UniverseRequest::Shipyard(symbol) => item(self.get_shipyard_as(system_symbol(symbol), symbol).await?),
+                        UniverseRequest::JumpGate(symbol) => item(self.get_jumpgate_as(system_symbol(symbol), symbol).await?),
+                        UniverseRequest::Construction(symbol) => item(self.get_construction_site_as(system_symbol(symbol), symbol).await?),
+                        UniverseRequest::Faction(symbol) => item(self.get_faction_as(symbol).await?),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_universe_source!(Anonymous, Authenticated);
+
+/// A source whose requests wait for a rate limiter, which can be shared with other sources.
+#[derive(Debug)]
+pub struct RateLimited<S> {
+    source: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> RateLimited<S> {
+    /// Limit the requests of the source with the limiter.
+    pub fn new(source: S, limiter: Arc<RateLimiter>) -> RateLimited<S> {
+        RateLimited { source, limiter }
+    }
+
+    /// The limited source.
+    pub fn inner(&self) -> &S {
+        &self.source
+    }
+}
+
+impl<S: UniverseSource + Sync> UniverseSource for RateLimited<S> {
+    async fn fetch(&self, request: &UniverseRequest) -> Result<UniverseResponse, Error> {
+        self.limiter.acquire().await;
+        self.source.fetch(request).await
+    }
+}
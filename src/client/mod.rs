@@ -1,9 +1,12 @@
+pub mod rate_limit;
+
+use std::future::Future;
 
 // the idea was cool and all, but the test url is spitting out nonsense
 // #[cfg(any(test, debug_assertions))]
-// const SPACE_TRADERS_API: &'static str  = "https://stoplight.io/mocks/spacetraders/spacetraders/96627693";
+// const SPACE_TRADERS_API: &str  = "https://stoplight.io/mocks/spacetraders/spacetraders/96627693";
 // #[cfg(all(not(test), not(debug_assertions)))]
-const SPACE_TRADERS_API: &'static str  = "https://api.spacetraders.io/v2";
+const SPACE_TRADERS_API: &str  = "https://api.spacetraders.io/v2";
 
 pub trait AuthState {}
 
@@ -82,4 +85,26 @@ impl SpaceTradersClient<Authenticated> {
             .header("Content-Type", "application/json")
             .header("Authorization", format!("Bearer {}", &self.auth_token.0))
     }
-}
\ No newline at end of file
+}
+
+/// Sends the requests of operations made of several requests, such as [`SpaceTradersClient::travel_to`]:
+/// directly with the client, or through the rate limiter of a [`rate_limit::RateLimitedClient`].
+///
+/// Operations call [`Requester::acquire`] before each request they send with [`Requester::client`].
+pub trait Requester: Sync {
+    /// Wait until a request can be sent.
+    fn acquire(&self) -> impl Future<Output = ()> + Send;
+
+    /// The client to send the requests with.
+    fn client(&self) -> &SpaceTradersClient<Authenticated>;
+}
+
+impl Requester for SpaceTradersClient<Authenticated> {
+    fn acquire(&self) -> impl Future<Output = ()> + Send {
+        std::future::ready(())
+    }
+
+    fn client(&self) -> &SpaceTradersClient<Authenticated> {
+        self
+    }
+}
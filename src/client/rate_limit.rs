@@ -0,0 +1,121 @@
+use std::{future::Future, sync::{Arc, Mutex}, time::Duration};
+
+use tokio::time::Instant;
+use super::{AuthState, Authenticated, Requester, SpaceTradersClient};
+
+/// Requests per second allowed by the SpaceTraders API.
+pub const SPACE_TRADERS_REQUESTS_PER_SECOND: f64 = 2.;
+
+/// Token bucket shared by everything sending requests with the same token or address.
+///
+/// Each request reserves a token. When the bucket is empty, requests wait in the order they were made.
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_second: f64,
+    burst: f64,
+    /// Tokens left, negative when requests are waiting, and when they were counted.
+    state: Mutex<(f64, Instant)>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new(SPACE_TRADERS_REQUESTS_PER_SECOND, 2)
+    }
+}
+
+impl RateLimiter {
+    /// A limiter allowing the given number of requests per second on average, and bursts of `burst` requests.
+    pub fn new(per_second: f64, burst: u32) -> RateLimiter {
+        let burst = burst.max(1) as f64;
+        RateLimiter {
+            per_second,
+            burst,
+            state: Mutex::new((burst, Instant::now())),
+        }
+    }
+
+    /// Reserve a token at the given time, and return how long to wait before using it.
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (tokens, last) = *state;
+        let elapsed = now.saturating_duration_since(last).as_secs_f64();
+        let tokens = (tokens + elapsed * self.per_second).min(self.burst) - 1.;
+        *state = (tokens, now.max(last));
+        match tokens < 0. {
+            true => Duration::from_secs_f64(-tokens / self.per_second),
+            false => Duration::ZERO,
+        }
+    }
+
+    /// Wait until a request can be sent.
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// A client shared by several tasks, whose requests wait for a shared rate limiter.
+pub struct RateLimitedClient<A: AuthState> {
+    client: SpaceTradersClient<A>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<A: AuthState> RateLimitedClient<A> {
+    /// Limit the requests of the client with the limiter.
+    pub fn new(client: SpaceTradersClient<A>, limiter: Arc<RateLimiter>) -> RateLimitedClient<A> {
+        RateLimitedClient { client, limiter }
+    }
+
+    /// The client, to send requests without waiting for the limiter.
+    /// Operations made of several requests take a [`Requester`] instead, which the limited client is.
+    pub fn client(&self) -> &SpaceTradersClient<A> {
+        &self.client
+    }
+
+    /// The limiter of the client.
+    pub fn limiter(&self) -> &Arc<RateLimiter> {
+        &self.limiter
+    }
+
+    /// Wait for the limiter, then send a request with the client.
+    ///
+    /// The closure is expected to send a single request, such as `|client| client.dock_ship(symbol)`.
+    pub async fn request<'a, T, F: Future<Output = T> + 'a>(&'a self, request: impl FnOnce(&'a SpaceTradersClient<A>) -> F) -> T {
+        self.limiter.acquire().await;
+        request(&self.client).await
+    }
+}
+
+impl Requester for RateLimitedClient<Authenticated> {
+    async fn acquire(&self) {
+        self.limiter.acquire().await
+    }
+
+    fn client(&self) -> &SpaceTradersClient<Authenticated> {
+        &self.client
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reservations() {
+        let limiter = RateLimiter::new(2., 2);
+        let start = Instant::now();
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::from_millis(500));
+        assert_eq!(limiter.reserve(start), Duration::from_secs(1));
+        // waiting requests are served before new ones
+        assert_eq!(limiter.reserve(start + Duration::from_secs(1)), Duration::from_millis(500));
+        // the bucket doesn't fill past the burst
+        let later = start + Duration::from_secs(60);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeSet;
+
+use serde::{de::Visitor, Deserialize, Deserializer};
+
+use crate::{
+    error::code::ErrorCode,
+    schemas::{
+        activity_level::ActivityLevel,
+        contract::ContractType,
+        faction::{faction_symbol::FactionSymbol, faction_trait::FactionTraitIdentifier},
+        market::{
+            market_trade_good::{MarketTradeGoodActivity, MarketTradeGoodType},
+            market_transaction::MarketTransactionType,
+        },
+        ship::{
+            ship_crew::ShipCrewRotation,
+            ship_engine::ShipEngineType,
+            ship_frame::ShipFrameType,
+            ship_module::ShipModuleType,
+            ship_mount::{ShipMountDeposits, ShipMountType},
+            ship_nav::{ship_nav_flight_mode::ShipNavFlightMode, ship_nav_status::ShipNavStatus},
+            ship_reactor::ShipReactorType,
+            ship_role::ShipRole,
+            ship_type::ShipType,
+        },
+        supply_level::SupplyLevel,
+        survey::SurveySize,
+        system::system_type::SystemType,
+        trade_symbol::TradeSymbol,
+        waypoint::{
+            waypoint_modifier::WaypointModifierType,
+            waypoint_trait::WaypointTraitType,
+            waypoint_type::WaypointType,
+        },
+    },
+};
+
+use super::{load_fixture, spec_value};
+
+/// Deserializer that only records the variant names serde expects for an enum.
+/// Derived enum deserializers hand the list of (renamed) variants to `deserialize_enum`,
+/// which is exactly what we want to compare against the spec.
+struct VariantNames<'a>(&'a mut Option<&'static [&'static str]>);
+
+impl<'de, 'a> Deserializer<'de> for VariantNames<'a> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(serde::de::Error::custom("only enums can be introspected"))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, variants: &'static [&'static str], _visitor: V) -> Result<V::Value, Self::Error> {
+        *self.0 = Some(variants);
+        Err(serde::de::Error::custom("variants recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Names of the variants of a serde enum, as they appear on the wire.
+fn variants_of<'de, T: Deserialize<'de>>() -> BTreeSet<String> {
+    let mut variants = None;
+    let _ = T::deserialize(VariantNames(&mut variants));
+    variants
+        .expect("type is not deserialized as an enum")
+        .iter()
+        .map(|v| v.to_string())
+        .collect()
+}
+
+/// Enum values of the spec at the given json pointer.
+fn spec_enum(pointer: &str) -> BTreeSet<String> {
+    spec_value(pointer)
+        .unwrap_or_else(|| panic!("no schema at {pointer} in the spec"))
+        .as_array()
+        .unwrap_or_else(|| panic!("schema at {pointer} is not an enum"))
+        .iter()
+        .map(|v| v.as_str().expect("enum values are strings").to_string())
+        .collect()
+}
+
+/// Assert the crate enum `T` has exactly the variants listed in the spec at `pointer`.
+fn assert_matches_spec<'de, T: Deserialize<'de>>(pointer: &str) {
+    let expected = spec_enum(pointer);
+    let actual = variants_of::<T>();
+    let missing = expected.difference(&actual).collect::<Vec<_>>();
+    let unknown = actual.difference(&expected).collect::<Vec<_>>();
+    assert!(
+        missing.is_empty() && unknown.is_empty(),
+        "{} does not match the spec at {pointer}\n\tmissing: {missing:?}\n\tnot in spec: {unknown:?}",
+        std::any::type_name::<T>(),
+    );
+}
+
+macro_rules! spec_enum_tests {
+    ($($test:ident: $ty:ty => $pointer:literal,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                assert_matches_spec::<$ty>($pointer);
+            }
+        )*
+    };
+}
+
+spec_enum_tests! {
+    activity_level: ActivityLevel => "/components/schemas/ActivityLevel/enum",
+    contract_type: ContractType => "/components/schemas/Contract/properties/type/enum",
+    faction_symbol: FactionSymbol => "/components/schemas/FactionSymbol/enum",
+    faction_trait: FactionTraitIdentifier => "/components/schemas/FactionTraitSymbol/enum",
+    market_trade_good_activity: MarketTradeGoodActivity => "/components/schemas/MarketTradeGood/properties/activity/enum",
+    market_trade_good_type: MarketTradeGoodType => "/components/schemas/MarketTradeGood/properties/type/enum",
+    market_transaction_type: MarketTransactionType => "/components/schemas/MarketTransaction/properties/type/enum",
+    ship_crew_rotation: ShipCrewRotation => "/components/schemas/ShipCrew/properties/rotation/enum",
+    ship_engine_type: ShipEngineType => "/components/schemas/ShipEngine/properties/symbol/enum",
+    ship_frame_type: ShipFrameType => "/components/schemas/ShipFrame/properties/symbol/enum",
+    ship_module_type: ShipModuleType => "/components/schemas/ShipModule/properties/symbol/enum",
+    ship_mount_type: ShipMountType => "/components/schemas/ShipMount/properties/symbol/enum",
+    ship_mount_deposits: ShipMountDeposits => "/components/schemas/ShipMount/properties/deposits/items/enum",
+    ship_nav_flight_mode: ShipNavFlightMode => "/components/schemas/ShipNavFlightMode/enum",
+    ship_nav_status: ShipNavStatus => "/components/schemas/ShipNavStatus/enum",
+    ship_reactor_type: ShipReactorType => "/components/schemas/ShipReactor/properties/symbol/enum",
+    ship_role: ShipRole => "/components/schemas/ShipRole/enum",
+    ship_type: ShipType => "/components/schemas/ShipType/enum",
+    supply_level: SupplyLevel => "/components/schemas/SupplyLevel/enum",
+    survey_size: SurveySize => "/components/schemas/SurveySize/enum",
+    system_type: SystemType => "/components/schemas/SystemType/enum",
+    trade_symbol: TradeSymbol => "/components/schemas/TradeSymbol/enum",
+    waypoint_modifier_type: WaypointModifierType => "/components/schemas/WaypointModifierSymbol/enum",
+    waypoint_trait_type: WaypointTraitType => "/components/schemas/WaypointTraitSymbol/enum",
+    waypoint_type: WaypointType => "/components/schemas/WaypointType/enum",
+}
+
+#[test]
+fn error_codes() {
+    // error codes are deserialized from their numeric value, so there is no variant list to introspect:
+    // instead, try every possible code and keep the ones that are accepted.
+    let actual = (0..=u16::MAX)
+        .filter_map(|code| ErrorCode::deserialize(serde_json::json!(code)).ok().map(|c| (code, format!("{c}"))))
+        .collect::<BTreeSet<_>>();
+    let expected = load_fixture("reference/ErrorCodes.json")
+        .as_object()
+        .expect("error codes are a map of name to code")
+        .iter()
+        .map(|(name, code)| {
+            let code = code.as_u64().expect("error codes are integers") as u16;
+            // the spec uses camelCase names, our variants are PascalCase
+            let mut chars = name.chars();
+            let name = chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars).collect::<String>();
+            (code, name)
+        })
+        // not listed by the spec, but sent by the server for missing resources
+        .chain(std::iter::once((404, "ResourceDoesNotExist".to_string())))
+        .collect::<BTreeSet<_>>();
+    let missing = expected.difference(&actual).collect::<Vec<_>>();
+    let unknown = actual.difference(&expected).collect::<Vec<_>>();
+    assert!(
+        missing.is_empty() && unknown.is_empty(),
+        "ErrorCode does not match the spec\n\tmissing: {missing:?}\n\tnot in spec: {unknown:?}",
+    );
+}
+
+#[test]
+fn trade_symbol_list() {
+    let all = TradeSymbol::ALL
+        .iter()
+        .map(|symbol| serde_json::to_value(symbol).unwrap().as_str().unwrap().to_string())
+        .collect::<BTreeSet<_>>();
+    assert_eq!(all.len(), TradeSymbol::ALL.len(), "TradeSymbol::ALL contains duplicates");
+    assert_eq!(all, variants_of::<TradeSymbol>(), "TradeSymbol::ALL does not list every variant");
+}
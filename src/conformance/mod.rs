@@ -0,0 +1,115 @@
+//* Offline conformance tests against the vendored SpaceTraders OpenAPI spec.
+//* The spec and example payloads live in `fixtures/openapi` at the root of the crate, laid out as in the
+//* SpaceTradersAPI/api-docs repository: `reference/SpaceTraders.json` refers to the schemas of `models/*.json`.
+//*
+//* `fixtures/openapi/vendor.sh` vendors the unmodified upstream files. Until it is run, `reference/SpaceTraders.json`
+//* is a hand-trimmed copy of the component enums, written along with the fixes it checks, so the enum tests
+//* only guard against regressions, not against the API.
+
+pub mod enums;
+pub mod responses;
+
+use std::path::PathBuf;
+
+/// Path of a file in the vendored OpenAPI fixtures.
+pub(crate) fn fixture_path(relative: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join("openapi")
+        .join(relative)
+}
+
+/// Load and parse a json file from the vendored OpenAPI fixtures.
+pub(crate) fn load_fixture(relative: &str) -> serde_json::Value {
+    let path = fixture_path(relative);
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("unable to read fixture {}: {e}", path.display()));
+    serde_json::from_str(&content)
+        .unwrap_or_else(|e| panic!("invalid json in fixture {}: {e}", path.display()))
+}
+
+/// Path of the spec in the vendored OpenAPI fixtures.
+pub(crate) const SPEC: &str = "reference/SpaceTraders.json";
+
+/// Resolve a path relative to the directory of a fixture file, such as the `../models/Ship.json` of a `$ref`.
+fn relative_to(file: &str, path: &str) -> String {
+    let mut segments = file.split('/').collect::<Vec<_>>();
+    segments.pop();
+    for segment in path.split('/') {
+        match segment {
+            "." | "" => {},
+            ".." => {
+                segments.pop();
+            },
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Follow a json pointer from a file, resolving the `$ref`s met on the way, to this file or to other files.
+/// `load` reads a file by its path relative to the fixtures.
+fn resolve_pointer(load: &impl Fn(&str) -> serde_json::Value, file: &str, pointer: &str) -> Option<serde_json::Value> {
+    let mut file = file.to_string();
+    let mut root = load(&file);
+    let mut current = root.clone();
+    let tokens = pointer.split('/').skip(1).map(|token| token.replace("~1", "/").replace("~0", "~"));
+    for token in tokens.map(Some).chain(std::iter::once(None)) {
+        while let Some(reference) = current.get("$ref").and_then(serde_json::Value::as_str).map(str::to_string) {
+            let (path, fragment) = reference.split_once('#').unwrap_or((&reference, ""));
+            if !path.is_empty() {
+                file = relative_to(&file, path);
+                root = load(&file);
+            }
+            current = root.pointer(fragment)?.clone();
+        }
+        let Some(token) = token else {
+            break;
+        };
+        current = match &current {
+            serde_json::Value::Array(items) => items.get(token.parse::<usize>().ok()?)?.clone(),
+            value => value.get(&token)?.clone(),
+        };
+    }
+    Some(current)
+}
+
+/// The value of the spec at a json pointer, following the `$ref`s to the vendored models.
+pub(crate) fn spec_value(pointer: &str) -> Option<serde_json::Value> {
+    resolve_pointer(&load_fixture, SPEC, pointer)
+}
+
+/// Deserialize the `data` of the example response of an operation, as the client unwraps it.
+pub(crate) fn fixture_data<T: serde::de::DeserializeOwned>(operation: &str) -> T {
+    let json = load_fixture(&format!("responses/{operation}.json"));
+    serde_json::from_value(json["data"].clone())
+        .unwrap_or_else(|e| panic!("data of {operation} does not deserialize into {}: {e}", std::any::type_name::<T>()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn references() {
+        assert_eq!(relative_to("reference/SpaceTraders.json", "../models/Ship.json"), "models/Ship.json");
+        assert_eq!(relative_to("models/Ship.json", "./ShipNav.json"), "models/ShipNav.json");
+
+        let load = |file: &str| match file {
+            "reference/Spec.json" => json!({ "components": { "schemas": { "Ship": { "$ref": "../models/Ship.json" } } } }),
+            "models/Ship.json" => json!({ "properties": { "nav": { "$ref": "./ShipNav.json" } } }),
+            "models/ShipNav.json" => json!({
+                "properties": { "status": { "$ref": "#/definitions/Status" } },
+                "definitions": { "Status": { "enum": ["DOCKED", "IN_ORBIT"] } },
+            }),
+            file => panic!("no fixture {file}"),
+        };
+        assert_eq!(
+            resolve_pointer(&load, "reference/Spec.json", "/components/schemas/Ship/properties/nav/properties/status/enum/1"),
+            Some(json!("IN_ORBIT")),
+        );
+        assert_eq!(resolve_pointer(&load, "reference/Spec.json", "/components/schemas/Ship/properties/fuel"), None);
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+
+use crate::{
+    api::{
+        contract::{AgentAndContract, ContractAndCargo},
+        fleet::{
+            ContractWrapper,
+            cargo::{CagoTransactionResult, CargoWrapper},
+            chart::ChartAndWaypoint,
+            mounts::MountInstallationResult,
+            nav::{JumpResult, NavResult, NavWrapper},
+            purchase::ShipPurchaseResult,
+            refining::RefiningResult,
+            refuel::RefuelShipResponse,
+            resources::{ExtractResourcesResponse, SiphonResourcesResponse},
+            scan::{ShipsScanResult, SystemsScanResult, WaypointsScanResult},
+            survey::CooldownAndSurveys,
+        },
+        registered_agent::RegisteredAgent,
+        server_status::ServerStatus,
+        systems::waypoint::ConstructionAndCargo,
+    },
+    schemas::{
+        agent::Agent,
+        construction::Construction,
+        contract::Contract,
+        cooldown::Cooldown,
+        faction::Faction,
+        jump_gate::JumpGate,
+        market::Market,
+        ship::{Ship, ship_cargo::ShipCargo, ship_mount::ShipMount, ship_nav::ShipNav},
+        shipyard::Shipyard,
+        system::System,
+        waypoint::Waypoint,
+    },
+    utils::wrapper::{ArrayWrapper, DataWrapper, PaginationWrapper},
+};
+
+use super::{fixture_path, load_fixture};
+
+/// For each endpoint, deserialize the example response of the spec
+/// with the same wrapper and type the client uses for that endpoint.
+macro_rules! response_tests {
+    ($($test:ident: $ty:ty => $operation:literal,)*) => {
+        /// Operation ids of the spec that have an example response test.
+        const TESTED_OPERATIONS: &[&str] = &[$($operation),*];
+
+        $(
+            #[test]
+            fn $test() {
+                let json = load_fixture(&format!("responses/{}.json", $operation));
+                if let Err(e) = <$ty>::deserialize(json) {
+                    panic!("example response of {} does not deserialize into {}: {e}", $operation, stringify!($ty));
+                }
+            }
+        )*
+    };
+}
+
+response_tests! {
+    // status
+    get_status: ServerStatus => "get-status",
+    register: DataWrapper<RegisteredAgent> => "register",
+    // agents
+    get_my_agent: DataWrapper<Agent> => "get-my-agent",
+    get_agents: PaginationWrapper<Agent> => "get-agents",
+    get_agent: DataWrapper<Agent> => "get-agent",
+    // contracts
+    get_contracts: PaginationWrapper<Contract> => "get-contracts",
+    get_contract: DataWrapper<Contract> => "get-contract",
+    accept_contract: DataWrapper<AgentAndContract> => "accept-contract",
+    deliver_contract: DataWrapper<ContractAndCargo> => "deliver-contract",
+    fulfill_contract: DataWrapper<AgentAndContract> => "fulfill-contract",
+    // factions
+    get_factions: PaginationWrapper<Faction> => "get-factions",
+    get_faction: DataWrapper<Faction> => "get-faction",
+    // fleet
+    get_my_ships: PaginationWrapper<Ship> => "get-my-ships",
+    get_my_ship: DataWrapper<Ship> => "get-my-ship",
+    purchase_ship: DataWrapper<ShipPurchaseResult> => "purchase-ship",
+    negotiate_contract: DataWrapper<ContractWrapper> => "negotiateContract",
+    get_my_ship_cargo: DataWrapper<ShipCargo> => "get-my-ship-cargo",
+    jettison: DataWrapper<CargoWrapper> => "jettison",
+    sell_cargo: DataWrapper<CagoTransactionResult> => "sell-cargo",
+    purchase_cargo: DataWrapper<CagoTransactionResult> => "purchase-cargo",
+    transfer_cargo: DataWrapper<CargoWrapper> => "transfer-cargo",
+    create_chart: DataWrapper<ChartAndWaypoint> => "create-chart",
+    get_ship_cooldown: DataWrapper<Cooldown> => "get-ship-cooldown",
+    get_mounts: ArrayWrapper<ShipMount> => "get-mounts",
+    install_mount: DataWrapper<MountInstallationResult> => "install-mount",
+    remove_mount: DataWrapper<MountInstallationResult> => "remove-mount",
+    orbit_ship: DataWrapper<NavWrapper> => "orbit-ship",
+    dock_ship: DataWrapper<NavWrapper> => "dock-ship",
+    jump_ship: DataWrapper<JumpResult> => "jump-ship",
+    navigate_ship: DataWrapper<NavResult> => "navigate-ship",
+    warp_ship: DataWrapper<NavResult> => "warp-ship",
+    patch_ship_nav: DataWrapper<ShipNav> => "patch-ship-nav",
+    get_ship_nav: DataWrapper<ShipNav> => "get-ship-nav",
+    ship_refine: DataWrapper<RefiningResult> => "ship-refine",
+    refuel_ship: DataWrapper<RefuelShipResponse> => "refuel-ship",
+    extract_resources: DataWrapper<ExtractResourcesResponse> => "extract-resources",
+    extract_resources_with_survey: DataWrapper<ExtractResourcesResponse> => "extract-resources-with-survey",
+    siphon_resources: DataWrapper<SiphonResourcesResponse> => "siphon-resources",
+    create_survey: DataWrapper<CooldownAndSurveys> => "create-survey",
+    scan_systems: DataWrapper<SystemsScanResult> => "create-ship-system-scan",
+    scan_waypoints: DataWrapper<WaypointsScanResult> => "create-ship-waypoint-scan",
+    scan_ships: DataWrapper<ShipsScanResult> => "create-ship-ship-scan",
+    // systems
+    get_systems: PaginationWrapper<System> => "get-systems",
+    get_system: DataWrapper<System> => "get-system",
+    get_system_waypoints: PaginationWrapper<Waypoint> => "get-system-waypoints",
+    get_waypoint: DataWrapper<Waypoint> => "get-waypoint",
+    get_market: DataWrapper<Market> => "get-market",
+    get_shipyard: DataWrapper<Shipyard> => "get-shipyard",
+    get_jump_gate: DataWrapper<JumpGate> => "get-jump-gate",
+    get_construction: DataWrapper<Construction> => "get-construction",
+    supply_construction: DataWrapper<ConstructionAndCargo> => "supply-construction",
+}
+
+#[test]
+fn every_example_response_is_tested() {
+    let vendored = std::fs::read_dir(fixture_path("responses"))
+        .expect("unable to list the example responses")
+        .map(|entry| entry.expect("unable to read fixture entry").path())
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+        .collect::<BTreeSet<_>>();
+    let tested = TESTED_OPERATIONS.iter().map(|op| op.to_string()).collect::<BTreeSet<_>>();
+    let untested = vendored.difference(&tested).collect::<Vec<_>>();
+    assert!(untested.is_empty(), "example responses without a test: {untested:?}");
+}
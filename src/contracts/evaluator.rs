@@ -0,0 +1,331 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use crate::{
+    navigation::route::{RouteOptions, SystemMap},
+    schemas::{
+        contract::Contract,
+        ship::Ship,
+        trade_symbol::{TradeSymbol, fuel_units_for_ship_fuel},
+    },
+    trading::history::MarketHistory,
+};
+use super::cheapest_market;
+
+/// What the evaluator needs to know about the ship completing the contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShipStats {
+    pub engine_speed: u64,
+    pub fuel_capacity: u64,
+    pub cargo_capacity: u64,
+    /// Where the ship starts from.
+    pub waypoint: String,
+}
+
+impl ShipStats {
+    /// The stats of a ship, starting from its current waypoint.
+    pub fn from_ship(ship: &Ship) -> ShipStats {
+        ShipStats {
+            engine_speed: ship.engine.speed.get(),
+            fuel_capacity: ship.fuel.capacity,
+            cargo_capacity: ship.cargo.capacity,
+            waypoint: ship.nav.waypoint_symbol.clone(),
+        }
+    }
+}
+
+/// A waypoint where some goods can be extracted instead of bought.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractionSite {
+    pub waypoint: String,
+    /// Goods that can be extracted at the waypoint.
+    pub goods: Vec<TradeSymbol>,
+    /// Units of a wanted good extracted per hour, surveys and cooldowns included.
+    pub units_per_hour: f64,
+}
+
+/// Options of the evaluation.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EvaluationOptions {
+    /// How the ship travels between waypoints.
+    pub route: RouteOptions,
+    /// Where goods that no known market sells can be extracted.
+    pub extraction_sites: Vec<ExtractionSite>,
+    /// Price of a unit of market fuel, the lowest known price if `None`.
+    pub fuel_price: Option<u64>,
+}
+
+/// Why a contract couldn't be evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvaluationError {
+    /// A good of the contract is not a known trade symbol.
+    UnknownTradeSymbol(String),
+    /// No known market sells the good, and it can't be extracted at any site.
+    NoSource(TradeSymbol),
+    /// The ship can't travel between the waypoints, or they are not on the map.
+    NoRoute {
+        origin: String,
+        destination: String,
+    },
+    /// The ship has no cargo hold to carry goods.
+    NoCargoSpace,
+    /// The ship needs fuel, but no known market sells it.
+    UnknownFuelPrice,
+}
+
+/// Where a good is obtained.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GoodSource {
+    /// Bought at the market with the lowest last seen purchase price.
+    Purchase {
+        market: String,
+        unit_price: u64,
+    },
+    /// Extracted at a site.
+    Extraction {
+        waypoint: String,
+        units_per_hour: f64,
+    },
+}
+
+impl GoodSource {
+    /// The waypoint where the good is obtained.
+    pub fn waypoint(&self) -> &str {
+        match self {
+            GoodSource::Purchase { market, .. } => market,
+            GoodSource::Extraction { waypoint, .. } => waypoint,
+        }
+    }
+}
+
+/// Estimate for a good of the contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoodEvaluation {
+    pub symbol: TradeSymbol,
+    pub destination: String,
+    /// Units left to deliver.
+    pub units: u64,
+    pub source: GoodSource,
+    /// Number of round trips between the source and the destination, one cargo hold each.
+    pub trips: u64,
+    /// Credits spent buying the good, nothing if it is extracted.
+    pub purchase_cost: u64,
+    /// Ship fuel consumed to travel to the source and deliver the good.
+    pub fuel: u64,
+    /// Time spent travelling and extracting.
+    pub duration: Duration,
+}
+
+/// Estimate of the cost, reward and duration of a contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractEvaluation {
+    pub goods: Vec<GoodEvaluation>,
+    /// Credits received on acceptance and fulfillment.
+    pub reward: i64,
+    /// Credits spent buying goods.
+    pub purchase_cost: u64,
+    /// Ship fuel consumed.
+    pub fuel: u64,
+    /// Credits spent buying fuel.
+    pub fuel_cost: u64,
+    /// Time to complete the contract.
+    pub duration: Duration,
+    /// When the contract would be completed, if started at the time of the evaluation.
+    pub finishes_at: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+}
+
+impl ContractEvaluation {
+    /// Credits spent on goods and fuel.
+    pub fn cost(&self) -> u64 {
+        self.purchase_cost + self.fuel_cost
+    }
+
+    /// Reward minus cost.
+    pub fn profit(&self) -> i64 {
+        self.reward - self.cost() as i64
+    }
+
+    /// Profit per hour spent on the contract. A contract taking no time is counted as taking a second.
+    pub fn profit_per_hour(&self) -> f64 {
+        self.profit() as f64 * 3600. / self.duration.as_secs_f64().max(1.)
+    }
+
+    /// Whether the contract can be completed before its deadline.
+    pub fn is_deadline_feasible(&self) -> bool {
+        self.finishes_at <= self.deadline
+    }
+}
+
+/// Fuel and time to travel between two waypoints, refuelling on the way when needed.
+fn travel(map: &SystemMap, ship: &ShipStats, origin: &str, destination: &str, options: &RouteOptions) -> Result<(u64, Duration), EvaluationError> {
+    map.plan_route_with(ship.engine_speed, ship.fuel_capacity, ship.fuel_capacity, origin, destination, options)
+        .map(|route| (route.fuel(), route.duration()))
+        .ok_or_else(|| EvaluationError::NoRoute { origin: origin.to_string(), destination: destination.to_string() })
+}
+
+/// Where to obtain a good: bought at the cheapest known market, or else extracted at a site.
+fn source(symbol: TradeSymbol, history: &MarketHistory, options: &EvaluationOptions) -> Result<GoodSource, EvaluationError> {
+    let purchase = cheapest_market(symbol, history).map(|(market, unit_price)| GoodSource::Purchase { market, unit_price });
+    let extraction = || options.extraction_sites.iter()
+        .filter(|site| site.goods.contains(&symbol) && site.units_per_hour > 0.)
+        .max_by(|a, b| a.units_per_hour.total_cmp(&b.units_per_hour))
+        .map(|site| GoodSource::Extraction { waypoint: site.waypoint.clone(), units_per_hour: site.units_per_hour });
+    purchase.or_else(extraction).ok_or(EvaluationError::NoSource(symbol))
+}
+
+/// Estimate the cost, reward and duration of completing a contract with a ship, from the known markets and the map of the system.
+///
+/// The goods are handled one after the other: the ship goes to the source of a good, and makes round trips to the
+/// destination with a full cargo hold until every unit is delivered. Prices are the last seen ones, and don't account
+/// for their changes as units are bought.
+pub fn evaluate_contract(
+    contract: &Contract,
+    ship: &ShipStats,
+    history: &MarketHistory,
+    map: &SystemMap,
+    options: &EvaluationOptions,
+    now: DateTime<Utc>,
+) -> Result<ContractEvaluation, EvaluationError> {
+    let mut goods = Vec::new();
+    let mut position = ship.waypoint.as_str();
+    for deliver in contract.terms.deliver.iter().flatten() {
+        let units = (deliver.units_required - deliver.units_fulfilled).max(0) as u64;
+        if units == 0 {
+            continue;
+        }
+        if ship.cargo_capacity == 0 {
+            return Err(EvaluationError::NoCargoSpace);
+        }
+        let symbol = deliver.trade_symbol.parse::<TradeSymbol>()
+            .map_err(|_| EvaluationError::UnknownTradeSymbol(deliver.trade_symbol.clone()))?;
+        let source = source(symbol, history, options)?;
+        let trips = units.div_ceil(ship.cargo_capacity);
+
+        let (to_source_fuel, to_source_time) = travel(map, ship, position, source.waypoint(), &options.route)?;
+        let (there_fuel, there_time) = travel(map, ship, source.waypoint(), &deliver.destination_symbol, &options.route)?;
+        let (back_fuel, back_time) = travel(map, ship, &deliver.destination_symbol, source.waypoint(), &options.route)?;
+        let fuel = to_source_fuel + trips * there_fuel + (trips - 1) * back_fuel;
+        let mut duration = to_source_time + there_time * trips as u32 + back_time * (trips - 1) as u32;
+        let purchase_cost = match &source {
+            GoodSource::Purchase { unit_price, .. } => unit_price * units,
+            GoodSource::Extraction { units_per_hour, .. } => {
+                duration += Duration::from_secs_f64(units as f64 / units_per_hour * 3600.);
+                0
+            },
+        };
+        goods.push(GoodEvaluation {
+            symbol,
+            destination: deliver.destination_symbol.clone(),
+            units,
+            source,
+            trips,
+            purchase_cost,
+            fuel,
+            duration,
+        });
+        position = &deliver.destination_symbol;
+    }
+
+    let fuel = goods.iter().map(|good| good.fuel).sum();
+    let fuel_cost = match fuel {
+        0 => 0,
+        fuel => {
+            let price = options.fuel_price
+                .or_else(|| history.latest_prices(TradeSymbol::Fuel).map(|(_, observation)| observation.purchase_price).min())
+                .ok_or(EvaluationError::UnknownFuelPrice)?;
+            fuel_units_for_ship_fuel(fuel) * price
+        },
+    };
+    let duration = goods.iter().map(|good| good.duration).sum();
+    Ok(ContractEvaluation {
+        reward: contract.terms.payment.total(),
+        purchase_cost: goods.iter().map(|good| good.purchase_cost).sum(),
+        fuel,
+        fuel_cost,
+        duration,
+        finishes_at: chrono::Duration::from_std(duration).ok()
+            .and_then(|duration| now.checked_add_signed(duration))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        deadline: contract.terms.deadline,
+        goods,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        conformance::fixture_data,
+        schemas::{market::Market, ship::ship_nav::ship_nav_flight_mode::ShipNavFlightMode},
+    };
+
+    /// The market selling the aluminum ore and fuel, 50 units away from the destination of the contract.
+    fn map() -> SystemMap {
+        let mut map = SystemMap::new();
+        map.insert("X1-MH34-A1", &(0, 0));
+        map.insert("X1-MH34-H52", &(30, 40));
+        map.set_sells_fuel("X1-MH34-A1", true);
+        map
+    }
+
+    fn ship() -> ShipStats {
+        ShipStats { engine_speed: 30, fuel_capacity: 400, cargo_capacity: 40, waypoint: "X1-MH34-A1".to_string() }
+    }
+
+    fn options() -> EvaluationOptions {
+        EvaluationOptions {
+            route: RouteOptions { flight_modes: vec![ShipNavFlightMode::Cruise], ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn purchase() {
+        let contract: Contract = fixture_data("get-contract");
+        let mut history = MarketHistory::new();
+        history.record(&fixture_data::<Market>("get-market"), Utc::now());
+        let start = contract.terms.deadline - chrono::Duration::days(1);
+        let evaluation = evaluate_contract(&contract, &ship(), &history, &map(), &options(), start).unwrap();
+
+        // 62 units in two trips: there, back, and there again
+        let good = &evaluation.goods[0];
+        assert_eq!(good.trips, 2);
+        assert_eq!(good.source, GoodSource::Purchase { market: "X1-MH34-A1".to_string(), unit_price: 90 });
+        assert_eq!(evaluation.purchase_cost, 62 * 90);
+        assert_eq!(evaluation.fuel, 3 * 50);
+        assert_eq!(evaluation.fuel_cost, 2 * 72);
+        assert_eq!(evaluation.duration, Duration::from_secs(3 * 57));
+        assert_eq!(evaluation.reward, 2028 + 11592);
+        assert_eq!(evaluation.profit(), 2028 + 11592 - 62 * 90 - 2 * 72);
+        assert!(evaluation.profit_per_hour() > evaluation.profit() as f64);
+        assert!(evaluation.is_deadline_feasible());
+
+        let late = evaluate_contract(&contract, &ship(), &history, &map(), &options(), contract.terms.deadline).unwrap();
+        assert!(!late.is_deadline_feasible());
+    }
+
+    #[test]
+    fn extraction() {
+        let contract: Contract = fixture_data("get-contract");
+        let history = MarketHistory::new();
+        assert_eq!(
+            evaluate_contract(&contract, &ship(), &history, &map(), &options(), Utc::now()),
+            Err(EvaluationError::NoSource(TradeSymbol::AluminumOre)),
+        );
+
+        let site = ExtractionSite { waypoint: "X1-MH34-A1".to_string(), goods: vec![TradeSymbol::AluminumOre], units_per_hour: 62. };
+        let options = EvaluationOptions { extraction_sites: vec![site], fuel_price: Some(100), ..options() };
+        let evaluation = evaluate_contract(&contract, &ship(), &history, &map(), &options, Utc::now()).unwrap();
+        assert_eq!(evaluation.purchase_cost, 0);
+        assert_eq!(evaluation.fuel_cost, 200);
+        assert_eq!(evaluation.duration, Duration::from_secs(3600 + 3 * 57));
+
+        let mut far = map();
+        far.insert("X1-MH34-H52", &(3000, 4000));
+        assert!(matches!(
+            evaluate_contract(&contract, &ship(), &history, &far, &options, Utc::now()),
+            Err(EvaluationError::NoRoute { .. }),
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+//* Tools to negotiate, evaluate and complete contracts.
+
+pub mod evaluator;
+pub mod runner;
+
+use chrono::{DateTime, Utc};
+use crate::{
+    error::Error,
+    schemas::{contract::contract_terms::ContractTerms, trade_symbol::TradeSymbol},
+    trading::history::MarketHistory,
+};
+
+/// A good to deliver for a contract, and the cheapest known market selling it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourcedGood {
+    pub symbol: TradeSymbol,
+    /// Where the good is delivered.
+    pub destination: String,
+    /// Units left to deliver.
+    pub units: u64,
+    /// The market with the lowest last seen purchase price for the good, and that price.
+    /// `None` if no known market sells the good.
+    pub source: Option<(String, u64)>,
+}
+
+impl SourcedGood {
+    /// Cost of buying the units left at the source, ignoring price changes as units are bought.
+    pub fn cost(&self) -> Option<u64> {
+        self.source.as_ref().map(|(_, price)| price * self.units)
+    }
+}
+
+/// The market with the lowest last seen purchase price for a good, and that price.
+pub fn cheapest_market(symbol: TradeSymbol, history: &MarketHistory) -> Option<(String, u64)> {
+    history.latest_prices(symbol)
+        .min_by_key(|(_, observation)| observation.purchase_price)
+        .map(|(market, observation)| (market.to_string(), observation.purchase_price))
+}
+
+/// Where to buy the goods left to deliver for a contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourcingPlan {
+    pub goods: Vec<SourcedGood>,
+}
+
+impl SourcingPlan {
+    /// Find the cheapest known market for each good of the terms that is not fully delivered yet.
+    pub fn new(terms: &ContractTerms, history: &MarketHistory) -> Result<SourcingPlan, Error> {
+        let mut goods = Vec::new();
+        for deliver in terms.deliver.iter().flatten() {
+            let units = (deliver.units_required - deliver.units_fulfilled).max(0) as u64;
+            if units == 0 {
+                continue;
+            }
+            let symbol = deliver.trade_symbol.parse::<TradeSymbol>()
+                .map_err(|_| Error::UnknownTradeSymbol(deliver.trade_symbol.clone()))?;
+            let source = cheapest_market(symbol, history);
+            goods.push(SourcedGood { symbol, destination: deliver.destination_symbol.clone(), units, source });
+        }
+        Ok(SourcingPlan { goods })
+    }
+
+    /// Whether every good can be bought at a known market.
+    pub fn is_sourced(&self) -> bool {
+        self.goods.iter().all(|good| good.source.is_some())
+    }
+
+    /// Cost of buying every good left, `None` if some good is not sold by any known market.
+    pub fn cost(&self) -> Option<u64> {
+        self.goods.iter().map(SourcedGood::cost).sum()
+    }
+}
+
+/// Whether a contract is likely to be fulfilled before its deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadlineRisk {
+    /// At the current pace, the contract is fulfilled before the deadline.
+    OnTrack,
+    /// At the current pace, the deadline passes before the contract is fulfilled.
+    AtRisk,
+    /// The deadline passed.
+    Missed,
+}
+
+/// Project when the units left are delivered from the pace of the deliveries since `started`.
+///
+/// Without any delivery yet there is no pace to project, and the contract is considered on track until its deadline.
+pub fn deadline_risk(started: DateTime<Utc>, now: DateTime<Utc>, deadline: DateTime<Utc>, delivered: u64, remaining: u64) -> DeadlineRisk {
+    if now >= deadline {
+        return DeadlineRisk::Missed;
+    }
+    if remaining == 0 || delivered == 0 {
+        return DeadlineRisk::OnTrack;
+    }
+    let elapsed = (now - started).num_milliseconds().max(0) as u64;
+    let projected = chrono::Duration::milliseconds(elapsed.saturating_mul(remaining).div_ceil(delivered).min(i64::MAX as u64) as i64);
+    match now.checked_add_signed(projected).is_none_or(|end| end > deadline) {
+        true => DeadlineRisk::AtRisk,
+        false => DeadlineRisk::OnTrack,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{conformance::fixture_data, schemas::{contract::Contract, market::Market}};
+
+    fn contract() -> Contract {
+        fixture_data("get-contract")
+    }
+
+    #[test]
+    fn sourcing() {
+        let contract = contract();
+        let mut history = MarketHistory::new();
+        let plan = SourcingPlan::new(&contract.terms, &history).unwrap();
+        assert_eq!(plan.goods.len(), 1);
+        assert!(!plan.is_sourced());
+        assert_eq!(plan.cost(), None);
+
+        let market: Market = fixture_data("get-market");
+        history.record(&market, Utc::now());
+        let plan = SourcingPlan::new(&contract.terms, &history).unwrap();
+        let good = &plan.goods[0];
+        assert_eq!(good.symbol, TradeSymbol::AluminumOre);
+        assert_eq!(good.units, 62);
+        assert_eq!(good.source, Some(("X1-MH34-A1".to_string(), 90)));
+        assert_eq!(plan.cost(), Some(62 * 90));
+        assert_eq!(contract.terms.payment.total(), 2028 + 11592);
+    }
+
+    #[test]
+    fn risk() {
+        let started = Utc::now();
+        let deadline = started + Duration::hours(10);
+        let now = started + Duration::hours(2);
+        assert_eq!(deadline_risk(started, now, deadline, 0, 100), DeadlineRisk::OnTrack);
+        // 2 more hours for the 50 units left
+        assert_eq!(deadline_risk(started, now, deadline, 50, 50), DeadlineRisk::OnTrack);
+        // 10 more hours for the 90 units left
+        assert_eq!(deadline_risk(started, now, deadline, 18, 90), DeadlineRisk::AtRisk);
+        assert_eq!(deadline_risk(started, deadline, deadline, 100, 0), DeadlineRisk::Missed);
+    }
+}
@@ -0,0 +1,370 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::watch;
+use crate::{
+    api::fleet::travel::TravelOptions,
+    client::{Authenticated, rate_limit::RateLimitedClient},
+    error::Error,
+    runtime::{actor::ShipHandle, behavior::builtin::ContractDeliver, supervisor::Fleet},
+    schemas::{contract::Contract, trade_symbol::TradeSymbol},
+    trading::history::MarketHistory,
+};
+use super::{
+    DeadlineRisk, SourcedGood, SourcingPlan, deadline_risk,
+    evaluator::{ContractEvaluation, EvaluationError, EvaluationOptions, ShipStats, evaluate_contract},
+};
+
+/// How the runner picks and completes contracts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContractOptions {
+    /// Where new contracts are negotiated, the headquarters of the agent if `None`.
+    pub negotiate_at: Option<String>,
+    /// Minimum profit of the evaluation, reward minus the cost of the goods and fuel, for a contract to be accepted.
+    pub min_profit: i64,
+    /// How offered contracts are evaluated, in the system of the ship.
+    pub evaluation: EvaluationOptions,
+    /// How the ship travels to the markets and destinations.
+    pub travel: TravelOptions,
+}
+
+/// Progress of the contract being completed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractProgress {
+    pub contract_id: String,
+    /// Units delivered over every good of the contract.
+    pub fulfilled: u64,
+    /// Units required over every good of the contract.
+    pub required: u64,
+    pub deadline: DateTime<Utc>,
+    pub risk: DeadlineRisk,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Units delivered and units required, over every good of the contract.
+fn units(contract: &Contract) -> (u64, u64) {
+    contract.terms.deliver.iter().flatten()
+        .fold((0, 0), |(fulfilled, required), good| {
+            (fulfilled + good.units_fulfilled.max(0) as u64, required + good.units_required.max(0) as u64)
+        })
+}
+
+impl ContractProgress {
+    /// Progress of a contract, at the pace of the units delivered since `started`.
+    pub fn new(contract: &Contract, started: DateTime<Utc>, fulfilled_at_start: u64, now: DateTime<Utc>) -> ContractProgress {
+        let (fulfilled, required) = units(contract);
+        let delivered = fulfilled.saturating_sub(fulfilled_at_start);
+        ContractProgress {
+            contract_id: contract.id.clone(),
+            fulfilled,
+            required,
+            deadline: contract.terms.deadline,
+            risk: deadline_risk(started, now, contract.terms.deadline, delivered, required.saturating_sub(fulfilled)),
+            updated_at: now,
+        }
+    }
+
+    /// Whether every unit is delivered.
+    pub fn is_complete(&self) -> bool {
+        self.fulfilled >= self.required
+    }
+}
+
+/// How a run ended.
+#[derive(Debug, Clone)]
+pub enum ContractOutcome {
+    /// The contract was fulfilled.
+    Fulfilled(Contract),
+    /// The contract was not accepted, as it is not profitable enough, can't be completed before its deadline,
+    /// or couldn't be evaluated.
+    Declined {
+        contract: Contract,
+        evaluation: Result<ContractEvaluation, EvaluationError>,
+    },
+    /// The ship couldn't deliver more units of a good, as it has none and no known market sells it.
+    Stalled {
+        contract: Contract,
+        symbol: TradeSymbol,
+    },
+}
+
+/// The contract to work on among the contracts of the agent: the first accepted one that is neither fulfilled
+/// nor past its deadline, or else the first offered one that can still be accepted.
+pub fn select_contract(contracts: impl IntoIterator<Item = Contract>, now: DateTime<Utc>) -> Option<Contract> {
+    let mut offered = None;
+    for contract in contracts {
+        if contract.accepted {
+            if !contract.fulfilled && contract.terms.deadline > now {
+                return Some(contract);
+            }
+        } else if offered.is_none() && contract.deadline_to_accept.is_none_or(|deadline| deadline > now) {
+            offered = Some(contract);
+        }
+    }
+    offered
+}
+
+/// What to do next to complete a contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionStep {
+    /// Deliver a batch of a good, at most a cargo hold.
+    Deliver(SourcedGood),
+    /// Every good is delivered.
+    Fulfill,
+    /// The last batch of the good delivered nothing.
+    Stall(TradeSymbol),
+}
+
+/// The next step to complete a contract, with a ship of the given cargo capacity.
+///
+/// `last_batch` is the number of units delivered before the last batch, and the good of the batch, if any.
+pub fn completion_step(contract: &Contract, last_batch: Option<(u64, TradeSymbol)>, history: &MarketHistory, cargo_capacity: u64) -> Result<CompletionStep, Error> {
+    if let Some((fulfilled_before, symbol)) = last_batch {
+        if units(contract).0 <= fulfilled_before {
+            return Ok(CompletionStep::Stall(symbol));
+        }
+    }
+    let plan = SourcingPlan::new(&contract.terms, history)?;
+    Ok(match plan.goods.into_iter().next() {
+        Some(good) => CompletionStep::Deliver(SourcedGood { units: good.units.min(cargo_capacity), ..good }),
+        None => CompletionStep::Fulfill,
+    })
+}
+
+/// Complete contracts with a ship: negotiate, evaluate, accept, source and deliver the goods, and fulfill.
+///
+/// The runner drives the ship through its handle, outside of the behaviors assigned with [`Fleet::assign`]:
+/// the ship must be idle, or its actions would interleave with the ones of its behavior, see [`ContractRunner::from_fleet`].
+pub struct ContractRunner {
+    client: Arc<RateLimitedClient<Authenticated>>,
+    ship: ShipHandle,
+    history: Arc<Mutex<MarketHistory>>,
+    options: ContractOptions,
+    progress: watch::Sender<Option<ContractProgress>>,
+}
+
+impl ContractRunner {
+    /// Complete contracts with the ship, buying the goods at the markets of the history.
+    pub fn new(client: Arc<RateLimitedClient<Authenticated>>, ship: ShipHandle, history: Arc<Mutex<MarketHistory>>, options: ContractOptions) -> ContractRunner {
+        ContractRunner {
+            client,
+            ship,
+            history,
+            options,
+            progress: watch::channel(None).0,
+        }
+    }
+
+    /// Complete contracts with a ship of the fleet, aborting the behavior assigned to it.
+    /// Returns `None` if the fleet has no such ship.
+    pub fn from_fleet(fleet: &mut Fleet, ship_symbol: &str, history: Arc<Mutex<MarketHistory>>, options: ContractOptions) -> Option<ContractRunner> {
+        let ship = fleet.ship(ship_symbol)?.clone();
+        if let Some(behavior) = fleet.unassign(ship_symbol) {
+            behavior.abort();
+        }
+        Some(ContractRunner::new(fleet.client().clone(), ship, history, options))
+    }
+
+    /// Follow the progress of the contract being completed.
+    pub fn subscribe(&self) -> watch::Receiver<Option<ContractProgress>> {
+        self.progress.subscribe()
+    }
+
+    /// The contract to work on, see [`select_contract`], or else a new one.
+    pub async fn find_contract(&self) -> Result<Contract, Error> {
+        let mut contracts = Vec::new();
+        let mut page = 1;
+        loop {
+            let (batch, meta) = self.client.request(|client| client.list_contracts(Some(20), Some(page))).await?;
+            contracts.extend(batch);
+            if !meta.has_next_page() {
+                break;
+            }
+            page += 1;
+        }
+        match select_contract(contracts, Utc::now()) {
+            Some(contract) => Ok(contract),
+            None => self.negotiate().await,
+        }
+    }
+
+    /// Travel to a waypoint with a faction, and negotiate a new contract there.
+    pub async fn negotiate(&self) -> Result<Contract, Error> {
+        let waypoint = match &self.options.negotiate_at {
+            Some(waypoint) => waypoint.clone(),
+            None => self.client.request(|client| client.get_agent()).await?.headquarters,
+        };
+        self.ship.travel_to(&waypoint, self.options.travel.clone()).await?;
+        self.ship.run(|context| Box::pin(async move {
+            context.dock().await?;
+            let symbol = context.symbol().to_string();
+            context.client().request(|client| client.negotiate_contract(&symbol)).await
+        })).await?
+    }
+
+    /// Find a contract, and complete it if it is worth it: offered contracts are accepted if their evaluation,
+    /// from the map of the system of the ship, is profitable enough and meets the deadline.
+    pub async fn run(&self) -> Result<ContractOutcome, Error> {
+        let mut contract = self.find_contract().await?;
+        if !contract.accepted {
+            let ship = self.ship.ship();
+            let map = self.client.system_map(&ship.nav.system_symbol).await?;
+            let evaluation = {
+                let history = self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                evaluate_contract(&contract, &ShipStats::from_ship(&ship), &history, &map, &self.options.evaluation, Utc::now())
+            };
+            let worth_it = evaluation.as_ref()
+                .is_ok_and(|evaluation| evaluation.profit() >= self.options.min_profit && evaluation.is_deadline_feasible());
+            if !worth_it {
+                return Ok(ContractOutcome::Declined { contract, evaluation });
+            }
+            contract = self.client.request(|client| client.accept_contract(&contract.id)).await?.contract;
+        }
+        self.complete(contract).await
+    }
+
+    /// Deliver every good of an accepted contract, one cargo hold at a time, and fulfill it.
+    pub async fn complete(&self, mut contract: Contract) -> Result<ContractOutcome, Error> {
+        let started = Utc::now();
+        let (fulfilled_at_start, _) = units(&contract);
+        self.progress.send_replace(Some(ContractProgress::new(&contract, started, fulfilled_at_start, started)));
+        let mut last_batch = None;
+        loop {
+            let step = {
+                let history = self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                completion_step(&contract, last_batch, &history, self.ship.ship().cargo.capacity)?
+            };
+            let good = match step {
+                CompletionStep::Deliver(good) => good,
+                CompletionStep::Fulfill => break,
+                CompletionStep::Stall(symbol) => return Ok(ContractOutcome::Stalled { contract, symbol }),
+            };
+            let source = good.source.as_ref().map(|(market, _)| market.as_str());
+            let mut batch = ContractDeliver::new(&contract.id, good.symbol, good.units, source, &good.destination);
+            batch.travel = self.options.travel.clone();
+            self.ship.assign(batch).join().await?;
+
+            last_batch = Some((units(&contract).0, good.symbol));
+            contract = self.client.request(|client| client.get_contract(&contract.id)).await?;
+            self.progress.send_replace(Some(ContractProgress::new(&contract, started, fulfilled_at_start, Utc::now())));
+        }
+        let contract = self.client.request(|client| client.fulfill_contract(&contract.id)).await?.contract;
+        Ok(ContractOutcome::Fulfilled(contract))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{
+        conformance::fixture_data,
+        runtime::{
+            behavior::tests::Counter,
+            supervisor::SupervisorOptions,
+            tests::{client, ship},
+        },
+        schemas::market::Market,
+    };
+
+    /// An offered contract for 62 units of aluminum ore, sold at the market of the history.
+    fn contract() -> Contract {
+        fixture_data("get-contract")
+    }
+
+    fn history() -> MarketHistory {
+        let mut history = MarketHistory::new();
+        history.record(&fixture_data::<Market>("get-market"), Utc::now());
+        history
+    }
+
+    fn deliver(contract: &mut Contract, units: i64) {
+        contract.terms.deliver.as_mut().unwrap()[0].units_fulfilled += units;
+    }
+
+    #[test]
+    fn selection() {
+        let offered = contract();
+        let now = offered.deadline_to_accept.unwrap() - Duration::hours(1);
+        let mut accepted = Contract { id: "accepted".to_string(), accepted: true, ..contract() };
+        let fulfilled = Contract { id: "fulfilled".to_string(), fulfilled: true, ..accepted.clone() };
+        assert_eq!(select_contract([offered.clone(), fulfilled.clone(), accepted.clone()], now).unwrap().id, "accepted");
+        assert_eq!(select_contract([offered.clone(), fulfilled.clone()], now).unwrap().id, offered.id);
+
+        // past their deadlines
+        accepted.terms.deadline = now - Duration::hours(1);
+        assert_eq!(select_contract([accepted.clone(), offered.clone()], now).unwrap().id, offered.id);
+        assert!(select_contract([accepted, offered.clone()], offered.deadline_to_accept.unwrap()).is_none());
+        assert!(select_contract([], now).is_none());
+    }
+
+    #[test]
+    fn completion() {
+        let mut contract = Contract { accepted: true, ..contract() };
+        let history = history();
+        let step = completion_step(&contract, None, &history, 40).unwrap();
+        let CompletionStep::Deliver(good) = step else {
+            panic!("expected a delivery, got {step:?}");
+        };
+        assert_eq!(good.units, 40);
+        assert_eq!(good.source, Some(("X1-MH34-A1".to_string(), 90)));
+
+        // the batch delivered 40 units, 22 are left
+        deliver(&mut contract, 40);
+        let step = completion_step(&contract, Some((0, TradeSymbol::AluminumOre)), &history, 40).unwrap();
+        assert!(matches!(step, CompletionStep::Deliver(SourcedGood { units: 22, .. })));
+        assert_eq!(completion_step(&contract, Some((40, TradeSymbol::AluminumOre)), &history, 40).unwrap(), CompletionStep::Stall(TradeSymbol::AluminumOre));
+
+        deliver(&mut contract, 22);
+        assert_eq!(completion_step(&contract, Some((40, TradeSymbol::AluminumOre)), &history, 40).unwrap(), CompletionStep::Fulfill);
+
+        contract.terms.deliver.as_mut().unwrap()[0].trade_symbol = "NOT_A_GOOD".to_string();
+        deliver(&mut contract, -62);
+        assert!(matches!(completion_step(&contract, None, &history, 40), Err(Error::UnknownTradeSymbol(symbol)) if symbol == "NOT_A_GOOD"));
+    }
+
+    #[test]
+    fn progress() {
+        let mut contract = contract();
+        let started = contract.terms.deadline - Duration::hours(10);
+        let progress = ContractProgress::new(&contract, started, 0, started);
+        assert_eq!((progress.fulfilled, progress.required), (0, 62));
+        assert_eq!(progress.risk, DeadlineRisk::OnTrack);
+        assert!(!progress.is_complete());
+
+        // 10 units in 5 hours, 52 left to deliver in 5 hours
+        deliver(&mut contract, 10);
+        let progress = ContractProgress::new(&contract, started, 0, started + Duration::hours(5));
+        assert_eq!(progress.risk, DeadlineRisk::AtRisk);
+        // the 10 units were delivered before the start
+        let progress = ContractProgress::new(&contract, started, 10, started + Duration::hours(5));
+        assert_eq!(progress.risk, DeadlineRisk::OnTrack);
+
+        deliver(&mut contract, 52);
+        let progress = ContractProgress::new(&contract, started, 0, contract.terms.deadline);
+        assert!(progress.is_complete());
+        assert_eq!(progress.risk, DeadlineRisk::Missed);
+    }
+
+    #[tokio::test]
+    async fn from_fleet() {
+        let options = SupervisorOptions { refresh_on_restart: false, ..Default::default() };
+        let mut fleet = Fleet::new(client(), options);
+        let handle = fleet.spawn(ship());
+        let log = Arc::default();
+        fleet.assign(handle.symbol(), Counter::new("a", usize::MAX, &log)).unwrap();
+        let history = Arc::new(Mutex::new(history()));
+        assert!(ContractRunner::from_fleet(&mut fleet, "UNKNOWN", history.clone(), ContractOptions::default()).is_none());
+
+        let runner = ContractRunner::from_fleet(&mut fleet, handle.symbol(), history, ContractOptions::default()).unwrap();
+        assert_eq!(runner.ship.symbol(), handle.symbol());
+        // the behavior was aborted, and no longer drives the ship
+        assert!(fleet.behavior(handle.symbol()).is_none());
+        let fuel = || handle.run(|context| Box::pin(async move { context.ship.fuel.current }));
+        let stopped_at = fuel().await.unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(fuel().await.unwrap(), stopped_at);
+        fleet.shutdown().await;
+    }
+}
@@ -0,0 +1,548 @@
+//* Crawl of the public data of the universe, with the anonymous client.
+
+pub mod sink;
+
+use std::{
+    collections::BTreeSet,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+use crate::{
+    cache::{
+        CacheKind,
+        snapshot::SnapshotRecord,
+        source::{UniverseRequest, UniverseResponse, UniverseSource},
+    },
+    client::rate_limit::RateLimiter,
+    error::Error,
+    navigation::Position,
+    schemas::{
+        system::System,
+        waypoint::{Waypoint, waypoint_trait::WaypointTraitType, waypoint_type::WaypointType},
+    },
+};
+use self::sink::CrawlSink;
+
+/// A system listed by the crawler.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CrawlSystem {
+    /// The symbol of the system.
+    pub symbol: String,
+    /// Position of the system in the x axis.
+    pub x: i64,
+    /// Position of the system in the y axis.
+    pub y: i64,
+}
+
+impl Position for CrawlSystem {
+    fn x(&self) -> i64 {
+        self.x
+    }
+    fn y(&self) -> i64 {
+        self.y
+    }
+}
+
+/// Progress of a crawl, saved after every page of systems and every crawled system.
+///
+/// The checkpoint file is a JSON Lines log: each page and each crawled system appends a line to it,
+/// so saving the progress doesn't rewrite the systems listed so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrawlCheckpoint {
+    /// Next page of the list of systems to fetch.
+    pub next_systems_page: u64,
+    /// Whether every page of the list of systems was fetched.
+    pub systems_listed: bool,
+    /// Systems listed so far.
+    pub systems: Vec<CrawlSystem>,
+    /// Systems whose waypoints were crawled.
+    pub completed: BTreeSet<String>,
+}
+
+/// A line of the checkpoint file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+enum CheckpointRecord {
+    /// Systems listed on a page of the list of systems.
+    #[serde(rename_all = "camelCase")]
+    Systems { next_page: u64, listed: bool, systems: Vec<CrawlSystem> },
+    /// A system whose waypoints were crawled.
+    Completed { system: String },
+}
+
+impl CrawlCheckpoint {
+    /// Read a checkpoint, or start a new crawl if the file doesn't exist.
+    ///
+    /// Lines that can't be parsed, such as a line partially written before a crash, are ignored.
+    pub fn load(path: impl AsRef<Path>) -> Result<CrawlCheckpoint, Error> {
+        let mut checkpoint = CrawlCheckpoint::default();
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(checkpoint),
+            Err(error) => return Err(error.into()),
+        };
+        for line in BufReader::new(file).lines() {
+            if let Ok(record) = serde_json::from_str::<CheckpointRecord>(&line?) {
+                checkpoint.apply(record);
+            }
+        }
+        Ok(checkpoint)
+    }
+
+    /// Write the whole checkpoint. The previous checkpoint is only replaced once the new one is fully written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let temporary = path.with_extension("tmp");
+        let mut records = vec![CheckpointRecord::Systems {
+            next_page: self.next_systems_page,
+            listed: self.systems_listed,
+            systems: self.systems.clone(),
+        }];
+        records.extend(self.completed.iter().map(|system| CheckpointRecord::Completed { system: system.clone() }));
+        std::fs::write(&temporary, lines(&records)?)?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    fn apply(&mut self, record: CheckpointRecord) {
+        match record {
+            CheckpointRecord::Systems { next_page, listed, systems } => {
+                self.next_systems_page = next_page;
+                self.systems_listed = listed;
+                self.systems.extend(systems);
+            },
+            CheckpointRecord::Completed { system } => {
+                self.completed.insert(system);
+            },
+        }
+    }
+}
+
+/// The records as JSON Lines.
+fn lines(records: &[CheckpointRecord]) -> Result<Vec<u8>, Error> {
+    let mut buffer = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut buffer, record)?;
+        buffer.push(b'\n');
+    }
+    Ok(buffer)
+}
+
+/// Area of the universe to crawl.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrawlArea {
+    /// Center of the area in the x axis.
+    pub x: i64,
+    /// Center of the area in the y axis.
+    pub y: i64,
+    /// Distance from the center to the farthest crawled systems.
+    pub radius: f64,
+}
+
+impl CrawlArea {
+    /// Systems within the radius of the given system.
+    pub fn around<P: Position + ?Sized>(center: &P, radius: f64) -> CrawlArea {
+        CrawlArea { x: center.x(), y: center.y(), radius }
+    }
+
+    /// Whether the position is in the area.
+    pub fn contains<P: Position + ?Sized>(&self, position: &P) -> bool {
+        position.distance_to(&(self.x, self.y)) <= self.radius
+    }
+}
+
+/// What and how to crawl.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrawlOptions {
+    /// Number of systems crawled at the same time.
+    pub concurrency: usize,
+    /// Only crawl the waypoints of the systems in this area, closest first. Every system is crawled if `None`.
+    pub area: Option<CrawlArea>,
+    /// Fetch the markets.
+    pub markets: bool,
+    /// Fetch the shipyards.
+    pub shipyards: bool,
+    /// Fetch the jump gates.
+    pub jump_gates: bool,
+    /// File where the progress is saved, to resume the crawl if it is interrupted.
+    pub checkpoint_path: Option<PathBuf>,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        CrawlOptions {
+            concurrency: 4,
+            area: None,
+            markets: true,
+            shipyards: true,
+            jump_gates: true,
+            checkpoint_path: None,
+        }
+    }
+}
+
+/// A market, shipyard or jump gate that couldn't be fetched.
+#[derive(Debug)]
+pub struct CrawlFailure {
+    /// Kind of the data.
+    pub kind: CacheKind,
+    /// Symbol of the waypoint.
+    pub waypoint: String,
+    /// Why the request failed.
+    pub error: Error,
+}
+
+/// What a crawl did.
+#[derive(Debug, Default)]
+pub struct CrawlReport {
+    /// Number of systems whose waypoints were crawled.
+    pub systems_crawled: usize,
+    /// Number of items sent to the sink.
+    pub records: usize,
+    /// Markets, shipyards and jump gates that were skipped as their request failed.
+    pub failures: Vec<CrawlFailure>,
+}
+
+/// Walks the universe: lists every system, then the waypoints of each system with their markets, shipyards and jump gates.
+///
+/// Anything implementing [`UniverseSource`] can be crawled, usually the anonymous client.
+/// The requests of the concurrent systems wait for a rate limiter allowing the requests per second of the server,
+/// which can be shared with other clients with [`Crawler::with_rate_limiter`].
+pub struct Crawler<S> {
+    source: Arc<S>,
+    limiter: Option<Arc<RateLimiter>>,
+    options: CrawlOptions,
+    checkpoint: CrawlCheckpoint,
+}
+
+impl<S: UniverseSource + Send + Sync + 'static> Crawler<S> {
+    /// Create a crawler, resuming from the checkpoint file of the options if it exists.
+    pub fn new(source: S, options: CrawlOptions) -> Result<Crawler<S>, Error> {
+        let checkpoint = match &options.checkpoint_path {
+            Some(path) if path.exists() => {
+                // compact the log of the previous crawls
+                let checkpoint = CrawlCheckpoint::load(path)?;
+                checkpoint.save(path)?;
+                checkpoint
+            },
+            _ => CrawlCheckpoint::default(),
+        };
+        let limiter = Some(Arc::new(RateLimiter::default()));
+        Ok(Crawler { source: Arc::new(source), limiter, options, checkpoint })
+    }
+
+    /// Wait for the given limiter before each request, to share it with other clients.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Crawler<S> {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Send the requests without waiting for a rate limiter, for sources that are already limited.
+    pub fn without_rate_limit(mut self) -> Crawler<S> {
+        self.limiter = None;
+        self
+    }
+
+    /// Progress of the crawl.
+    pub fn checkpoint(&self) -> &CrawlCheckpoint {
+        &self.checkpoint
+    }
+
+    /// Record progress, appending it to the checkpoint file.
+    fn save_progress(&mut self, record: CheckpointRecord) -> Result<(), Error> {
+        if let Some(path) = &self.options.checkpoint_path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(&lines(std::slice::from_ref(&record))?)?;
+        }
+        self.checkpoint.apply(record);
+        Ok(())
+    }
+
+    /// Systems left to crawl, closest to the center of the area first.
+    fn pending_systems(&self) -> Vec<String> {
+        let mut systems = self.checkpoint.systems.iter()
+            .filter(|system| !self.checkpoint.completed.contains(&system.symbol))
+            .filter(|system| self.options.area.is_none_or(|area| area.contains(*system)))
+            .collect::<Vec<_>>();
+        if let Some(area) = self.options.area {
+            systems.sort_by(|a, b| a.distance_to(&(area.x, area.y)).total_cmp(&b.distance_to(&(area.x, area.y))));
+        }
+        systems.into_iter().map(|system| system.symbol.clone()).collect()
+    }
+
+    /// Crawl what is left to crawl, and send what is found to the sink.
+    ///
+    /// Every listed system is sent to the sink, the area only limits the systems whose waypoints are crawled.
+    /// Markets, shipyards and jump gates that can't be fetched are skipped and listed in the report.
+    /// If another request fails, the error is returned and the crawl can be resumed from the last checkpoint.
+    pub async fn run<K: CrawlSink + ?Sized>(&mut self, sink: &mut K) -> Result<CrawlReport, Error> {
+        let mut report = CrawlReport::default();
+        while !self.checkpoint.systems_listed {
+            let page = self.checkpoint.next_systems_page.max(1);
+            let response = fetch(&*self.source, self.limiter.as_deref(), &UniverseRequest::Systems { page }).await?;
+            let mut listed = Vec::new();
+            if let serde_json::Value::Array(systems) = response.data {
+                for data in systems {
+                    let system = System::deserialize(&data)?;
+                    sink.accept(record(CacheKind::System, &system.symbol, data))?;
+                    report.records += 1;
+                    listed.push(CrawlSystem { symbol: system.symbol, x: system.x, y: system.y });
+                }
+            }
+            self.save_progress(CheckpointRecord::Systems {
+                next_page: page + 1,
+                listed: !response.meta.is_some_and(|meta| meta.has_next_page()),
+                systems: listed,
+            })?;
+        }
+
+        let mut pending = self.pending_systems().into_iter();
+        let mut tasks = JoinSet::new();
+        loop {
+            while tasks.len() < self.options.concurrency.max(1) {
+                match pending.next() {
+                    Some(system) => {
+                        tasks.spawn(crawl_system(self.source.clone(), self.limiter.clone(), system, self.options.clone()));
+                    },
+                    None => break,
+                }
+            }
+            let result = match tasks.join_next().await {
+                Some(Ok(result)) => result,
+                Some(Err(error)) => std::panic::resume_unwind(error.into_panic()),
+                None => break,
+            };
+            let (system, records, mut failures) = result?;
+            report.records += records.len();
+            for record in records {
+                sink.accept(record)?;
+            }
+            report.failures.append(&mut failures);
+            self.save_progress(CheckpointRecord::Completed { system })?;
+            report.systems_crawled += 1;
+        }
+        Ok(report)
+    }
+}
+
+fn record(kind: CacheKind, key: &str, data: serde_json::Value) -> SnapshotRecord {
+    SnapshotRecord { kind, key: key.to_string(), fetched_at: Utc::now(), data }
+}
+
+/// Wait for the limiter if any, then fetch the data.
+async fn fetch<S: UniverseSource>(source: &S, limiter: Option<&RateLimiter>, request: &UniverseRequest) -> Result<UniverseResponse, Error> {
+    if let Some(limiter) = limiter {
+        limiter.acquire().await;
+    }
+    source.fetch(request).await
+}
+
+/// Fetch the waypoints of a system, and their markets, shipyards and jump gates.
+/// Returns the data found, and the markets, shipyards and jump gates that couldn't be fetched.
+async fn crawl_system<S: UniverseSource>(
+    source: Arc<S>,
+    limiter: Option<Arc<RateLimiter>>,
+    system: String,
+    options: CrawlOptions,
+) -> Result<(String, Vec<SnapshotRecord>, Vec<CrawlFailure>), Error> {
+    let limiter = limiter.as_deref();
+    let mut records = Vec::new();
+    let mut waypoints = Vec::new();
+    let mut page = 1;
+    loop {
+        let response = fetch(&*source, limiter, &UniverseRequest::Waypoints { system: system.clone(), page }).await?;
+        if let serde_json::Value::Array(batch) = response.data {
+            for data in batch {
+                let waypoint = Waypoint::deserialize(&data)?;
+                records.push(record(CacheKind::Waypoint, &waypoint.symbol, data));
+                waypoints.push(waypoint);
+            }
+        }
+        if !response.meta.is_some_and(|meta| meta.has_next_page()) {
+            break;
+        }
+        page += 1;
+    }
+
+    let mut failures = Vec::new();
+    for waypoint in waypoints {
+        let has_trait = |symbol: WaypointTraitType| waypoint.traits.iter().any(|t| t.symbol == symbol);
+        let symbol = waypoint.symbol.clone();
+        let mut details = Vec::new();
+        if options.markets && has_trait(WaypointTraitType::Marketplace) {
+            details.push((CacheKind::Market, UniverseRequest::Market(symbol.clone())));
+        }
+        if options.shipyards && has_trait(WaypointTraitType::Shipyard) {
+            details.push((CacheKind::Shipyard, UniverseRequest::Shipyard(symbol.clone())));
+        }
+        if options.jump_gates && waypoint.waypoint_type == WaypointType::JumpGate {
+            details.push((CacheKind::JumpGate, UniverseRequest::JumpGate(symbol.clone())));
+        }
+        for (kind, request) in details {
+            match fetch(&*source, limiter, &request).await {
+                Ok(response) => records.push(record(kind, &symbol, response.data)),
+                Err(error) => failures.push(CrawlFailure { kind, waypoint: symbol.clone(), error }),
+            }
+        }
+    }
+    Ok((system, records, failures))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Mutex, atomic::{AtomicBool, Ordering}};
+
+    use super::*;
+    use crate::{cache::source::UniverseResponse, conformance::load_fixture, navigation::system_symbol, schemas::meta::Meta};
+
+    /// Three systems on a line, listed on two pages, whose waypoints are the fixture waypoints.
+    struct FakeUniverse {
+        fail_markets: AtomicBool,
+        fail_waypoints: AtomicBool,
+        requests: Mutex<Vec<UniverseRequest>>,
+    }
+
+    impl FakeUniverse {
+        fn new() -> FakeUniverse {
+            FakeUniverse { fail_markets: AtomicBool::new(false), fail_waypoints: AtomicBool::new(false), requests: Mutex::new(Vec::new()) }
+        }
+    }
+
+    fn server_error() -> Error {
+        let error = serde_json::json!({ "message": "down", "code": 4000 });
+        Error::from((500, serde_json::from_value(error).unwrap()))
+    }
+
+    /// A crawler of the fake universe, without waiting for a rate limiter.
+    fn unlimited_crawler(source: FakeUniverse, options: CrawlOptions) -> Crawler<FakeUniverse> {
+        Crawler::new(source, options).unwrap().without_rate_limit()
+    }
+
+    /// A fixture whose symbols are moved to the given system.
+    fn fixture_in(name: &str, system: &str) -> serde_json::Value {
+        let json = load_fixture(&format!("responses/{name}.json")).to_string();
+        serde_json::from_str(&json.replace("X1-MH34", system)).unwrap()
+    }
+
+    impl UniverseSource for FakeUniverse {
+        async fn fetch(&self, request: &UniverseRequest) -> Result<UniverseResponse, Error> {
+            self.requests.lock().unwrap().push(request.clone());
+            let item = |name: &str, symbol: &str| UniverseResponse { data: fixture_in(name, system_symbol(symbol))["data"].take(), meta: None };
+            Ok(match request {
+                UniverseRequest::Systems { page } => {
+                    let systems = [("X1-A", 0), ("X1-B", 100), ("X1-C", 1000)].into_iter()
+                        .skip((*page as usize - 1) * 2)
+                        .take(2)
+                        .map(|(symbol, x)| {
+                            let mut system = fixture_in("get-system", symbol)["data"].take();
+                            system["x"] = x.into();
+                            system["y"] = 0.into();
+                            system
+                        })
+                        .collect::<Vec<_>>();
+                    let meta = serde_json::json!({ "total": 3, "page": page, "limit": 2 });
+                    UniverseResponse { data: systems.into(), meta: Some(Meta::deserialize(meta)?) }
+                },
+                UniverseRequest::Waypoints { .. } if self.fail_waypoints.load(Ordering::SeqCst) => return Err(server_error()),
+                UniverseRequest::Waypoints { system, .. } => {
+                    let mut json = fixture_in("get-system-waypoints", system);
+                    UniverseResponse { data: json["data"].take(), meta: Some(Meta::deserialize(&json["meta"])?) }
+                },
+                UniverseRequest::Market(_) if self.fail_markets.load(Ordering::SeqCst) => return Err(server_error()),
+                UniverseRequest::Market(symbol) => item("get-market", symbol),
+                UniverseRequest::Shipyard(symbol) => item("get-shipyard", symbol),
+                _ => panic!("unexpected request {request:?}"),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn crawl() {
+        let mut records = Vec::new();
+        let mut crawler = unlimited_crawler(FakeUniverse::new(), CrawlOptions::default());
+        let report = crawler.run(&mut records).await.unwrap();
+        // 3 systems, and for each system 2 waypoints, a market and a shipyard
+        assert_eq!(report.systems_crawled, 3);
+        assert_eq!(report.records, 3 + 3 * 4);
+        assert!(report.failures.is_empty());
+        assert_eq!(records.iter().filter(|r| r.kind == CacheKind::Market).count(), 3);
+        assert!(records.iter().any(|r| r.kind == CacheKind::Shipyard && r.key == "X1-C-A1"));
+        assert_eq!(crawler.checkpoint().completed.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn area() {
+        let mut records = Vec::new();
+        let options = CrawlOptions { area: Some(CrawlArea::around(&(0, 0), 150.)), ..Default::default() };
+        let mut crawler = unlimited_crawler(FakeUniverse::new(), options);
+        let report = crawler.run(&mut records).await.unwrap();
+        assert_eq!(report.systems_crawled, 2);
+        assert!(!crawler.checkpoint().completed.contains("X1-C"));
+    }
+
+    #[tokio::test]
+    async fn resume() {
+        let path = std::env::temp_dir().join(format!("space-traders-crawl-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let options = CrawlOptions { checkpoint_path: Some(path.clone()), ..Default::default() };
+
+        let mut records = Vec::new();
+        let source = FakeUniverse::new();
+        source.fail_waypoints.store(true, Ordering::SeqCst);
+        let mut crawler = unlimited_crawler(source, options.clone());
+        assert!(crawler.run(&mut records).await.is_err());
+        let checkpoint = CrawlCheckpoint::load(&path).unwrap();
+        assert!(checkpoint.systems_listed);
+        assert_eq!(checkpoint.systems.len(), 3);
+        // a line per page of systems
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+        // the systems are not listed again
+        let mut crawler = unlimited_crawler(FakeUniverse::new(), options.clone());
+        let report = crawler.run(&mut records).await.unwrap();
+        assert_eq!(report.systems_crawled, 3);
+        assert!(!crawler.source.requests.lock().unwrap().iter().any(|request| matches!(request, UniverseRequest::Systems { .. })));
+        // the pages were compacted into a line, and a line was appended per crawled system
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 4);
+
+        // nothing is left to crawl, even if the last line was cut short by a crash
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"completed\":").unwrap();
+        let mut crawler = unlimited_crawler(FakeUniverse::new(), options);
+        let report = crawler.run(&mut records).await.unwrap();
+        assert_eq!((report.systems_crawled, report.records), (0, 0));
+        assert_eq!(crawler.checkpoint().completed.len(), 3);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn failures() {
+        let source = FakeUniverse::new();
+        source.fail_markets.store(true, Ordering::SeqCst);
+        let mut records = Vec::new();
+        let report = unlimited_crawler(source, CrawlOptions::default()).run(&mut records).await.unwrap();
+        assert_eq!(report.systems_crawled, 3);
+        assert_eq!(report.records, 3 + 3 * 3);
+        assert_eq!(report.failures.len(), 3);
+        assert!(report.failures.iter().all(|failure| failure.kind == CacheKind::Market && failure.error.code().is_some()));
+        assert!(report.failures.iter().any(|failure| failure.waypoint == "X1-B-A1"));
+    }
+
+    #[tokio::test]
+    async fn rate_limit() {
+        assert!(Crawler::new(FakeUniverse::new(), CrawlOptions::default()).unwrap().limiter.is_some());
+
+        // 11 requests, 20 milliseconds apart
+        let limiter = Arc::new(RateLimiter::new(50., 1));
+        let mut crawler = Crawler::new(FakeUniverse::new(), CrawlOptions::default()).unwrap().with_rate_limiter(limiter);
+        let start = std::time::Instant::now();
+        crawler.run(&mut Vec::new()).await.unwrap();
+        assert_eq!(crawler.source.requests.lock().unwrap().len(), 11);
+        assert!(start.elapsed() >= std::time::Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,68 @@
+use std::io::Write;
+
+use crate::{
+    cache::{UniverseCache, snapshot::{SnapshotHeader, SnapshotRecord, SNAPSHOT_FORMAT, SNAPSHOT_VERSION}},
+    error::Error,
+};
+
+/// Where the data found by a crawl goes.
+pub trait CrawlSink {
+    /// Receive an item found by the crawler.
+    fn accept(&mut self, record: SnapshotRecord) -> Result<(), Error>;
+}
+
+/// Keep the items in memory.
+impl CrawlSink for Vec<SnapshotRecord> {
+    fn accept(&mut self, record: SnapshotRecord) -> Result<(), Error> {
+        self.push(record);
+        Ok(())
+    }
+}
+
+/// Store the items in the cache, so they are not fetched again.
+impl CrawlSink for UniverseCache {
+    fn accept(&mut self, record: SnapshotRecord) -> Result<(), Error> {
+        self.insert(record.kind, &record.key, record.data, record.fetched_at)
+    }
+}
+
+/// Write the items as the records of a snapshot, one json per line.
+///
+/// The snapshot header is not written: write it first with [`JsonLinesSink::with_header`] to get a readable snapshot.
+#[derive(Debug)]
+pub struct JsonLinesSink<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    /// Write the records to the writer.
+    pub fn new(writer: W) -> JsonLinesSink<W> {
+        JsonLinesSink { writer }
+    }
+
+    /// Write a snapshot header, then the records to the writer.
+    pub fn with_header(mut writer: W) -> Result<JsonLinesSink<W>, Error> {
+        let header = SnapshotHeader {
+            format: SNAPSHOT_FORMAT.to_string(),
+            version: SNAPSHOT_VERSION,
+            created_at: chrono::Utc::now(),
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+        Ok(JsonLinesSink { writer })
+    }
+
+    /// The underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> CrawlSink for JsonLinesSink<W> {
+    fn accept(&mut self, record: SnapshotRecord) -> Result<(), Error> {
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
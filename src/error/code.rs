@@ -3,11 +3,13 @@ use std::fmt::Display;
 use serde_repr::Deserialize_repr;
 
 
-#[derive(Deserialize_repr, Debug, Clone, Copy)]
+#[derive(Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum ErrorCode {
     ResourceDoesNotExist = 404,
+    ResponseSchemaError = 3000,
     CooldownConflictError = 4000,
+    WaypointNoAccessError = 4001,
     TokenEmptyError = 4100,
     TokenMissingSubjectError = 4101,
     TokenInvalidSubjectError = 4102,
@@ -17,7 +19,9 @@ pub enum ErrorCode {
     AccountNotExistsError = 4106,
     AgentNotExistsError = 4107,
     AccountHasNoAgentError = 4108,
-    RegisterAgentExistsError = 4111,
+    RegisterAgentExistsError = 4109,
+    RegisterAgentSymbolReservedError = 4110,
+    RegisterAgentConflictSymbolError = 4111,
     NavigateInTransitError = 4200,
     NavigateInvalidDestinationError = 4201,
     NavigateOutsideSystemError = 4202,
@@ -56,8 +60,28 @@ pub enum ErrorCode {
     ShipInvalidRefineryGoodError = 4237,
     ShipInvalidRefineryTypeError = 4238,
     ShipMissingRefineryError = 4239,
-    ShipNotInShipyardForTransaction = 4245,
     ShipMissingSurveyorError = 4240,
+    ShipMissingWarpDriveError = 4241,
+    ShipMissingMineralProcessorError = 4242,
+    ShipMissingMiningLasersError = 4243,
+    ShipNotDockedError = 4244,
+    PurchaseShipNotPresentError = 4245,
+    ShipMountNoShipyardError = 4246,
+    ShipMissingMountError = 4247,
+    ShipMountInsufficientCreditsError = 4248,
+    ShipMissingPowerError = 4249,
+    ShipMissingSlotsError = 4250,
+    ShipMissingCrewError = 4251,
+    ShipExtractDestabilizedError = 4252,
+    ShipJumpInvalidOriginError = 4253,
+    ShipJumpInvalidWaypointError = 4254,
+    ShipJumpOriginUnderConstructionError = 4255,
+    ShipMissingGasProcessorError = 4256,
+    ShipMissingGasSiphonsError = 4257,
+    ShipSiphonInvalidWaypointError = 4258,
+    ShipSiphonPermissionError = 4259,
+    WaypointNoYieldError = 4260,
+    ShipJumpDestinationUnderConstructionError = 4261,
     AcceptContractNotAuthorizedError = 4500,
     AcceptContractConflictError = 4501,
     FulfillContractDeliveryError = 4502,
@@ -68,11 +92,16 @@ pub enum ErrorCode {
     ShipDeliverTermsError = 4508,
     ShipDeliverFulfilledError = 4509,
     ShipDeliverInvalidLocationError = 4510,
+    ExistingContractError = 4511,
     MarketTradeInsufficientCreditsError = 4600,
     MarketTradeNoPurchaseError = 4601,
     MarketTradeNotSoldError = 4602,
     MarketNotFoundError = 4603,
     MarketTradeUnitLimitError = 4604,
+    WaypointNoFactionError = 4700,
+    ConstructionMaterialNotRequired = 4800,
+    ConstructionMaterialFulfilled = 4801,
+    ShipConstructionInvalidLocationError = 4802,
 }
 
 impl Display for ErrorCode {
@@ -1,4 +1,5 @@
-use self::server_error::SpaceTraderError;
+use self::{server_error::SpaceTraderError, code::ErrorCode};
+use crate::schemas::ship::ship_capabilities::MissingCapability;
 
 pub mod server_error;
 pub mod code;
@@ -15,6 +16,40 @@ pub enum Error {
     },
     ErrorSendingRequest(reqwest::Error),
     JsonParsingError(serde_json::Error),
+    /// The request was not sent, as the ship is missing a capability to perform it.
+    MissingCapability(MissingCapability),
+    /// No route was found between the ship's location and the destination.
+    NoRouteFound {
+        origin: String,
+        destination: String,
+    },
+    /// Reading or writing a local file failed.
+    Io(std::io::Error),
+    /// The actor of the ship stopped before answering.
+    ShipActorStopped {
+        ship_symbol: String,
+    },
+    /// The refinery of a mining ship is the ship itself, which can't hand its cargo over to itself.
+    RefineryIsMiner {
+        ship_symbol: String,
+    },
+    /// A good of the game data is not a known trade symbol.
+    UnknownTradeSymbol(String),
+    /// The cargo of the ship is still full after selling at the market, so nothing more can be extracted.
+    CargoNotSold {
+        ship_symbol: String,
+        market: String,
+    },
+}
+
+impl Error {
+    /// The code of the error sent by the server, if the server answered with an error.
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            Error::ServerErrorResponse { error, .. } => Some(error.code()),
+            _ => None,
+        }
+    }
 }
 
 impl From<reqwest::Error> for Error {
@@ -36,4 +71,16 @@ impl From<serde_json::Error> for Error {
     fn from(value: serde_json::Error) -> Self {
         Error::JsonParsingError(value)
     }
-}
\ No newline at end of file
+}
+
+impl From<MissingCapability> for Error {
+    fn from(value: MissingCapability) -> Self {
+        Error::MissingCapability(value)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
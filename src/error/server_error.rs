@@ -10,11 +10,23 @@ pub struct SpaceTraderError {
     code: ErrorCode,
 }
 
+impl SpaceTraderError {
+    /// The message sent by the server.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The code of the error.
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+}
+
 impl Display for SpaceTraderError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "SpaceTraders Error:\n")?;
-        write!(f, "\tMessage: {}\n", self.message)?;
-        write!(f, "\tError Code: {}\n", self.code)?;
+        writeln!(f, "SpaceTraders Error:")?;
+        writeln!(f, "\tMessage: {}", self.message)?;
+        writeln!(f, "\tError Code: {}", self.code)?;
         Ok(())
     }
 }
\ No newline at end of file
@@ -68,12 +68,9 @@ async fn tutorial() {
     println!("\nDrone will arrive at {}", arrival_time);
     let now = chrono::offset::Utc::now();
     let waiting_time = arrival_time - now;
-    match waiting_time.to_std() {
-        Ok(positive_duration) => {
-            println!("\nSleeping for {positive_duration:?}");
-            tokio::time::sleep(positive_duration).await;
-        },
-        Err(_) => {},
+    if let Ok(positive_duration) = waiting_time.to_std() {
+        println!("\nSleeping for {positive_duration:?}");
+        tokio::time::sleep(positive_duration).await;
     }
     println!("\nDrone arrived!");
     // dock the ship to refuel
@@ -2,7 +2,16 @@
 pub mod client;
 pub mod error;
 #[cfg(test)]
+pub mod conformance;
+#[cfg(test)]
 pub mod example;
 pub mod api;
 pub mod schemas;
+pub mod navigation;
+pub mod cache;
+pub mod trading;
+pub mod contracts;
+pub mod mining;
+pub mod crawler;
+pub mod runtime;
 pub(crate) mod utils;
\ No newline at end of file
@@ -0,0 +1,5 @@
+//* Tools to mine and siphon resources efficiently.
+
+pub mod pipeline;
+pub mod survey_store;
+pub mod yields;
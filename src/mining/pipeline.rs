@@ -0,0 +1,347 @@
+use std::{
+    num::NonZeroU64,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use crate::{
+    api::fleet::{refining::FromRefinedTradeSymbol, travel::TravelOptions},
+    error::Error,
+    runtime::{
+        actor::{ShipHandle, ShipTaskFuture},
+        behavior::{ShipBehavior, Step},
+        context::ShipContext,
+    },
+    schemas::{
+        ship::{Ship, ship_capabilities::ShipCapabilities, ship_cargo::ShipCargo, ship_nav::ship_nav_status::ShipNavStatus},
+        trade_symbol::{REFINING_INPUT_UNITS, TradeSymbol},
+    },
+};
+use super::{survey_store::SurveyStore, yields::YieldStats};
+
+/// Goods that can be produced from the cargo right now, with the refineries of the capabilities.
+pub fn refinable(cargo: &ShipCargo, capabilities: &ShipCapabilities) -> Vec<FromRefinedTradeSymbol> {
+    cargo.iter()
+        .filter(|(_, units)| units.get() >= REFINING_INPUT_UNITS)
+        .filter_map(|(symbol, _)| symbol.refines_into())
+        .filter(|product| capabilities.can_refine_into(*product))
+        .filter_map(|product| FromRefinedTradeSymbol::try_from(product).ok())
+        .collect()
+}
+
+/// Refine the cargo until no raw good has enough units left to be refined. Returns the number of refinings.
+pub async fn refine_cargo(context: &mut ShipContext) -> Result<u64, Error> {
+    let capabilities = context.ship.capabilities();
+    let mut refinings = 0;
+    while let Some(&product) = refinable(&context.ship.cargo, &capabilities).first() {
+        context.refine(product).await?;
+        refinings += 1;
+    }
+    Ok(refinings)
+}
+
+/// Raw goods of the cargo that the refinery ship can refine, in the units its cargo has space for.
+pub fn hand_over_goods(cargo: &ShipCargo, refinery: &Ship) -> Vec<(TradeSymbol, NonZeroU64)> {
+    let capabilities = refinery.capabilities();
+    let mut free_space = refinery.cargo.free_space();
+    let mut goods = Vec::new();
+    for (symbol, units) in cargo.iter().filter(|(symbol, _)| symbol.refines_into().is_some_and(|product| capabilities.can_refine_into(product))) {
+        let Some(units) = NonZeroU64::new(units.get().min(free_space)) else {
+            break;
+        };
+        free_space -= units.get();
+        goods.push((symbol, units));
+    }
+    goods
+}
+
+/// Time between two checks of the task of the refinery ship, while the miner waits for it.
+const REFINERY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What a [`MiningLoop`] does next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MiningAction {
+    GoToAsteroid,
+    Extract,
+    /// Refine the cargo with the refinery of the ship itself.
+    Refine,
+    /// Put the refinery ship in orbit, as goods are only transferred between ships in the same state.
+    OrbitRefinery,
+    /// Transfer raw goods to the refinery ship.
+    HandOver(Vec<(TradeSymbol, NonZeroU64)>),
+    /// The cargo is full, and nothing more can be refined.
+    Done,
+}
+
+/// Mine at an asteroid until the cargo is full, refining the ores if a refinery is available.
+///
+/// Extractions use the best stored survey for the target good, and their yields are recorded.
+/// Ores are refined by the ship itself if it has a refinery, or else handed over to the refinery ship
+/// when it is at the asteroid. The cycle is done once the cargo is full and nothing more can be refined,
+/// so it can be followed by selling the cargo.
+///
+/// The refinery ship is driven by tasks spawned outside of the actor of the miner, which never waits for
+/// another actor: the miner keeps extracting while the goods handed over are refined, and sleeps between
+/// steps when it needs the refinery before its task is done.
+pub struct MiningLoop {
+    pub asteroid: String,
+    /// Good to pick surveys for, extractions are made without survey if `None`.
+    pub target: Option<TradeSymbol>,
+    pub surveys: Arc<Mutex<SurveyStore>>,
+    pub stats: Arc<Mutex<YieldStats>>,
+    /// Ship refining the ores of the miner.
+    pub refinery: Option<ShipHandle>,
+    pub travel: TravelOptions,
+    /// Task of the refinery ship started by the last hand-over, if any.
+    refinery_task: Option<JoinHandle<Result<(), Error>>>,
+}
+
+impl MiningLoop {
+    /// Mine at an asteroid, with the surveys of the store, recording the yields.
+    pub fn new(asteroid: &str, surveys: Arc<Mutex<SurveyStore>>, stats: Arc<Mutex<YieldStats>>) -> MiningLoop {
+        MiningLoop {
+            asteroid: asteroid.to_string(),
+            target: None,
+            surveys,
+            stats,
+            refinery: None,
+            travel: TravelOptions::default(),
+            refinery_task: None,
+        }
+    }
+
+    /// What to do next with the ship, given the last known state of the refinery ship.
+    pub fn next_action(&self, ship: &Ship, refinery: Option<&Ship>) -> MiningAction {
+        if ship.nav.waypoint_symbol != self.asteroid {
+            return MiningAction::GoToAsteroid;
+        }
+        if !ship.cargo.is_full() {
+            return MiningAction::Extract;
+        }
+        if !refinable(&ship.cargo, &ship.capabilities()).is_empty() {
+            return MiningAction::Refine;
+        }
+        let Some(refinery) = refinery.filter(|refinery| refinery.nav.waypoint_symbol == ship.nav.waypoint_symbol) else {
+            return MiningAction::Done;
+        };
+        let goods = hand_over_goods(&ship.cargo, refinery);
+        match (goods.is_empty(), refinery.nav.status) {
+            (true, _) => MiningAction::Done,
+            (false, ShipNavStatus::InOrbit) => MiningAction::HandOver(goods),
+            (false, _) => MiningAction::OrbitRefinery,
+        }
+    }
+
+    /// Return the error of the task of the refinery ship once it is done, and forget the task.
+    async fn check_refinery_task(&mut self) -> Result<(), Error> {
+        let Some(task) = self.refinery_task.take_if(|task| task.is_finished()) else {
+            return Ok(());
+        };
+        match task.await {
+            Ok(result) => result,
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            // aborted
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Extract once, forgetting the survey if it can't be used anymore.
+    async fn extract(&self, context: &mut ShipContext) -> Result<(), Error> {
+        let survey = self.target.and_then(|target| {
+            let surveys = self.surveys.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            surveys.best(&self.asteroid, target, Utc::now()).cloned()
+        });
+        match context.extract(survey.as_ref()).await {
+            Ok(extraction) => {
+                let strength = context.ship.capabilities().extraction_strength;
+                let mut stats = self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                stats.record(&self.asteroid, strength, survey.as_ref(), &extraction, Utc::now());
+                Ok(())
+            },
+            Err(error) => match survey {
+                Some(survey) if self.surveys.lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .handle_extraction_error(&survey, &error) => Ok(()),
+                _ => Err(error),
+            },
+        }
+    }
+}
+
+impl ShipBehavior for MiningLoop {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        Box::pin(async move {
+            if let Some(refinery) = self.refinery.as_ref().filter(|refinery| refinery.symbol() == context.symbol()) {
+                return Err(Error::RefineryIsMiner { ship_symbol: refinery.symbol().to_string() });
+            }
+            self.check_refinery_task().await?;
+            let refinery = self.refinery.clone();
+            let refinery_state = refinery.as_ref().map(ShipHandle::ship);
+            match (self.next_action(&context.ship, refinery_state.as_ref()), refinery) {
+                (MiningAction::GoToAsteroid, _) => context.travel_to(&self.asteroid, &self.travel).await?,
+                (MiningAction::Extract, _) => self.extract(context).await?,
+                (MiningAction::Refine, _) => {
+                    refine_cargo(context).await?;
+                },
+                (MiningAction::OrbitRefinery | MiningAction::HandOver(_), Some(_)) if self.refinery_task.is_some() => {
+                    return Ok(Step::Sleep(REFINERY_POLL_INTERVAL));
+                },
+                (MiningAction::OrbitRefinery, Some(refinery)) => {
+                    self.refinery_task = Some(tokio::spawn(async move { refinery.orbit().await.map(|_| ()) }));
+                },
+                (MiningAction::HandOver(goods), Some(refinery)) => {
+                    // the miner is in orbit after extracting, like the refinery
+                    context.orbit().await?;
+                    let mut transferred = Vec::new();
+                    for (symbol, units) in goods {
+                        context.transfer(symbol, units, refinery.symbol()).await?;
+                        transferred.push((symbol, units.get()));
+                    }
+                    self.refinery_task = Some(tokio::spawn(async move {
+                        refinery.run(move |refinery| Box::pin(async move {
+                            for (symbol, units) in transferred {
+                                if refinery.ship.cargo.add(symbol, units).is_err() {
+                                    refinery.refresh().await?;
+                                    break;
+                                }
+                            }
+                            refine_cargo(refinery).await.map(|_| ())
+                        })).await?
+                    }));
+                },
+                // the refinery actions are only taken with a refinery ship
+                (MiningAction::Done | MiningAction::OrbitRefinery | MiningAction::HandOver(_), _) => return Ok(Step::Done),
+            }
+            Ok(Step::Continue)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        runtime::{supervisor::{Fleet, SupervisorOptions}, tests::{client, context, ship}},
+        schemas::ship::ship_module::ShipModuleType,
+    };
+
+    /// The miner of the fixture at its asteroid, without a refinery, with 8 units of aluminum ore,
+    /// 4 of ice water, and the rest of its 40 units of iron ore.
+    fn miner() -> Ship {
+        let mut miner = ship();
+        miner.cargo.add(TradeSymbol::IronOre, miner.cargo.free_space()).unwrap();
+        miner
+    }
+
+    /// A ship with an ore refinery, at the asteroid, with 28 units of free space.
+    fn refinery() -> Ship {
+        let mut refinery = ship();
+        refinery.symbol = "REFINERY".to_string();
+        refinery.modules[2].symbol = ShipModuleType::ModuleOreRefineryI;
+        refinery
+    }
+
+    fn mining_loop() -> MiningLoop {
+        MiningLoop::new(&ship().nav.waypoint_symbol, Arc::default(), Arc::default())
+    }
+
+    fn fleet() -> Fleet {
+        Fleet::new(client(), SupervisorOptions { refresh_on_restart: false, ..Default::default() })
+    }
+
+    #[test]
+    fn refining() {
+        let mut cargo = ShipCargo { capacity: 100, units: 0, inventory: Vec::new() };
+        cargo.add(TradeSymbol::IronOre, 45).unwrap();
+        cargo.add(TradeSymbol::CopperOre, 29).unwrap();
+        cargo.add(TradeSymbol::QuartzSand, 20).unwrap();
+        let capabilities = ShipCapabilities {
+            refines: vec![TradeSymbol::Iron, TradeSymbol::Copper],
+            ..Default::default()
+        };
+        // not enough copper ore to refine
+        assert_eq!(refinable(&cargo, &capabilities), [FromRefinedTradeSymbol::Iron]);
+        assert!(refinable(&cargo, &ShipCapabilities::default()).is_empty());
+        cargo.add(TradeSymbol::CopperOre, 1).unwrap();
+        assert_eq!(refinable(&cargo, &capabilities).len(), 2);
+    }
+
+    #[test]
+    fn hand_over() {
+        let refinery = refinery();
+        assert_eq!(hand_over_goods(&miner().cargo, &refinery), [
+            (TradeSymbol::AluminumOre, NonZeroU64::new(8).unwrap()),
+            (TradeSymbol::IronOre, NonZeroU64::new(20).unwrap()),
+        ]);
+        assert!(hand_over_goods(&miner().cargo, &ship()).is_empty());
+        let mut full = refinery.clone();
+        full.cargo.add(TradeSymbol::Iron, full.cargo.free_space()).unwrap();
+        assert!(hand_over_goods(&miner().cargo, &full).is_empty());
+    }
+
+    #[test]
+    fn actions() {
+        let mining = mining_loop();
+        let mut not_full = ship();
+        assert_eq!(mining.next_action(&not_full, None), MiningAction::Extract);
+        not_full.nav.waypoint_symbol = "X1-A-B1".to_string();
+        assert_eq!(mining.next_action(&not_full, None), MiningAction::GoToAsteroid);
+
+        let miner = miner();
+        assert_eq!(mining.next_action(&miner, None), MiningAction::Done);
+        let mut refinery = refinery();
+        assert_eq!(mining.next_action(&miner, Some(&refinery)), MiningAction::OrbitRefinery);
+        refinery.nav.status = ShipNavStatus::InOrbit;
+        let MiningAction::HandOver(goods) = mining.next_action(&miner, Some(&refinery)) else {
+            panic!("the goods are not handed over");
+        };
+        assert_eq!(goods, hand_over_goods(&miner.cargo, &refinery));
+        refinery.nav.waypoint_symbol = "X1-A-B1".to_string();
+        assert_eq!(mining.next_action(&miner, Some(&refinery)), MiningAction::Done);
+
+        // the miner refines its own ores first, once it has enough of them
+        let mut miner = miner.clone();
+        miner.modules = refinery.modules.clone();
+        assert_eq!(mining.next_action(&miner, Some(&refinery)), MiningAction::Done);
+        miner.cargo.remove(TradeSymbol::IceWater, 4).unwrap();
+        miner.cargo.add(TradeSymbol::IronOre, 4).unwrap();
+        assert_eq!(mining.next_action(&miner, Some(&refinery)), MiningAction::Refine);
+    }
+
+    #[tokio::test]
+    async fn own_refinery() {
+        let mut fleet = fleet();
+        let miner = fleet.spawn(miner());
+        let mut mining = mining_loop();
+        mining.refinery = Some(miner.clone());
+        let failed = miner.assign(mining).join().await;
+        assert!(matches!(failed, Err(Error::RefineryIsMiner { .. })));
+        fleet.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn refinery_task() {
+        let mut fleet = fleet();
+        let mut mining = mining_loop();
+        mining.refinery = Some(fleet.spawn(refinery()));
+        let mut context = context();
+        context.ship = miner();
+
+        // the miner waits for the refinery to be in orbit
+        mining.refinery_task = Some(tokio::spawn(std::future::pending()));
+        assert_eq!(mining.step(&mut context).await.unwrap(), Step::Sleep(REFINERY_POLL_INTERVAL));
+        mining.refinery_task.take().unwrap().abort();
+
+        // the error of the refinery is returned once its task is done
+        let task = tokio::spawn(async { Err(Error::ShipActorStopped { ship_symbol: "REFINERY".to_string() }) });
+        while !task.is_finished() {
+            tokio::task::yield_now().await;
+        }
+        mining.refinery_task = Some(task);
+        assert!(matches!(mining.step(&mut context).await, Err(Error::ShipActorStopped { .. })));
+        assert!(mining.refinery_task.is_none());
+        fleet.shutdown().await;
+    }
+}
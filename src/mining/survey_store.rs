@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use crate::{
+    api::fleet::survey::CooldownAndSurveys,
+    error::{Error, code::ErrorCode},
+    schemas::{
+        survey::{Survey, SurveySize},
+        trade_symbol::TradeSymbol,
+    },
+};
+
+/// Relative amount of resources that can be extracted from a survey of the given size before it is exhausted.
+fn size_weight(size: SurveySize) -> f64 {
+    match size {
+        SurveySize::Small => 1.,
+        SurveySize::Moderate => 2.,
+        SurveySize::Large => 3.,
+    }
+}
+
+/// How good a survey is to extract the target good.
+///
+/// Each deposit is equally likely to be extracted, so the score is the share of the deposits that are the target,
+/// weighted by the size of the survey. A survey without the target scores 0.
+pub fn score(survey: &Survey, target: TradeSymbol) -> f64 {
+    if survey.deposits.is_empty() {
+        return 0.;
+    }
+    let matching = survey.deposits.iter()
+        .filter(|deposit| deposit.symbol.parse::<TradeSymbol>() == Ok(target))
+        .count();
+    matching as f64 / survey.deposits.len() as f64 * size_weight(survey.size)
+}
+
+/// Whether the error means the survey can't be used anymore.
+pub fn is_survey_spent(error: &Error) -> bool {
+    matches!(error.code(), Some(ErrorCode::ShipSurveyExhaustedError | ErrorCode::ShipSurveyExpirationError))
+}
+
+/// Surveys of each waypoint that can still be used for extraction.
+#[derive(Debug, Clone, Default)]
+pub struct SurveyStore {
+    surveys: HashMap<String, Vec<Survey>>,
+    /// Signatures of the surveys reported exhausted by the server, with their expiration.
+    exhausted: HashMap<String, DateTime<Utc>>,
+}
+
+impl SurveyStore {
+    /// Create an empty store.
+    pub fn new() -> SurveyStore {
+        SurveyStore::default()
+    }
+
+    /// Store a survey. Surveys already stored or exhausted are ignored.
+    pub fn add(&mut self, survey: Survey) {
+        if self.exhausted.contains_key(&survey.signature) {
+            return;
+        }
+        let surveys = self.surveys.entry(survey.symbol.clone()).or_default();
+        if !surveys.iter().any(|s| s.signature == survey.signature) {
+            surveys.push(survey);
+        }
+    }
+
+    /// Store the surveys created by a ship.
+    pub fn add_surveys(&mut self, result: &CooldownAndSurveys) {
+        for survey in result.surveys.iter() {
+            self.add(survey.clone());
+        }
+    }
+
+    /// Remove a survey, as it can't be extracted anymore. It is ignored if added again until it expires.
+    pub fn mark_exhausted(&mut self, survey: &Survey) {
+        self.exhausted.insert(survey.signature.clone(), survey.expiration);
+        for surveys in self.surveys.values_mut() {
+            surveys.retain(|s| s.signature != survey.signature);
+        }
+        self.surveys.retain(|_, surveys| !surveys.is_empty());
+    }
+
+    /// Remove the survey if the extraction with it failed because it is exhausted or expired.
+    /// Returns whether it was removed, in which case the extraction can be tried again with another survey.
+    pub fn handle_extraction_error(&mut self, survey: &Survey, error: &Error) -> bool {
+        if is_survey_spent(error) {
+            self.mark_exhausted(survey);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove the surveys that expired at the given time, and forget the exhausted ones that expired.
+    pub fn remove_expired(&mut self, now: DateTime<Utc>) {
+        for surveys in self.surveys.values_mut() {
+            surveys.retain(|survey| survey.expiration > now);
+        }
+        self.surveys.retain(|_, surveys| !surveys.is_empty());
+        self.exhausted.retain(|_, expiration| *expiration > now);
+    }
+
+    /// Surveys of a waypoint that are not expired at the given time.
+    pub fn surveys(&self, waypoint_symbol: &str, now: DateTime<Utc>) -> impl Iterator<Item = &Survey> + '_ {
+        self.surveys.get(waypoint_symbol)
+            .into_iter()
+            .flatten()
+            .filter(move |survey| survey.expiration > now)
+    }
+
+    /// Number of stored surveys, expired or not.
+    pub fn len(&self) -> usize {
+        self.surveys.values().map(Vec::len).sum()
+    }
+
+    /// Whether no survey is stored.
+    pub fn is_empty(&self) -> bool {
+        self.surveys.is_empty()
+    }
+
+    /// The survey of the waypoint with the best score for the target good, not expired at the given time.
+    /// Surveys expiring later are preferred among equally good ones.
+    pub fn best(&self, waypoint_symbol: &str, target: TradeSymbol, now: DateTime<Utc>) -> Option<&Survey> {
+        self.surveys(waypoint_symbol, now)
+            .map(|survey| (survey, score(survey, target)))
+            .filter(|(_, score)| *score > 0.)
+            .max_by(|(a, a_score), (b, b_score)| a_score.total_cmp(b_score).then(a.expiration.cmp(&b.expiration)))
+            .map(|(survey, _)| survey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        conformance::fixture_data,
+        error::server_error::SpaceTraderError,
+        schemas::survey::survey_deposit::SurveyDeposit,
+    };
+
+    fn survey(signature: &str, deposits: &[&str], size: SurveySize, expiration: DateTime<Utc>) -> Survey {
+        Survey {
+            signature: signature.to_string(),
+            symbol: "X1-A-B1".to_string(),
+            deposits: deposits.iter().map(|d| SurveyDeposit { symbol: d.to_string() }).collect(),
+            expiration,
+            size,
+        }
+    }
+
+    #[test]
+    fn scoring() {
+        let result: CooldownAndSurveys = fixture_data("create-survey");
+        let survey = &result.surveys[0];
+        assert_eq!(score(survey, TradeSymbol::AluminumOre), 1.);
+        assert_eq!(score(survey, TradeSymbol::IronOre), 0.5);
+        assert_eq!(score(survey, TradeSymbol::Gold), 0.);
+    }
+
+    #[test]
+    fn best() {
+        let now = Utc::now();
+        let soon = now + chrono::Duration::minutes(5);
+        let later = now + chrono::Duration::minutes(10);
+        let mut store = SurveyStore::new();
+        store.add(survey("A", &["IRON_ORE", "COPPER_ORE"], SurveySize::Large, soon));
+        store.add(survey("B", &["IRON_ORE", "IRON_ORE", "QUARTZ_SAND"], SurveySize::Small, later));
+        store.add(survey("C", &["IRON_ORE", "COPPER_ORE"], SurveySize::Large, later));
+        store.add(survey("C", &["IRON_ORE", "COPPER_ORE"], SurveySize::Large, later));
+        assert_eq!(store.len(), 3);
+        assert_eq!(store.best("X1-A-B1", TradeSymbol::IronOre, now).unwrap().signature, "C");
+        assert_eq!(store.best("X1-A-B1", TradeSymbol::QuartzSand, now).unwrap().signature, "B");
+        assert!(store.best("X1-A-B1", TradeSymbol::Gold, now).is_none());
+        assert!(store.best("X1-A-B2", TradeSymbol::IronOre, now).is_none());
+
+        store.remove_expired(soon);
+        assert_eq!(store.len(), 2);
+        store.mark_exhausted(&survey("C", &["IRON_ORE", "COPPER_ORE"], SurveySize::Large, later));
+        assert_eq!(store.best("X1-A-B1", TradeSymbol::IronOre, now).unwrap().signature, "B");
+        // an exhausted survey is not stored again
+        store.add(survey("C", &["IRON_ORE"], SurveySize::Large, later));
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.exhausted.len(), 1);
+
+        // exhausted signatures are forgotten once their survey expired
+        store.remove_expired(soon);
+        assert_eq!(store.exhausted.len(), 1);
+        store.remove_expired(later);
+        assert!(store.exhausted.is_empty());
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn extraction_error() {
+        let now = Utc::now();
+        let spent = survey("A", &["IRON_ORE"], SurveySize::Small, now + chrono::Duration::minutes(5));
+        let mut store = SurveyStore::new();
+        store.add(spent.clone());
+        let error = |code: ErrorCode| {
+            let json = serde_json::json!({ "message": "", "code": code as u16 });
+            Error::from((400, serde_json::from_value::<SpaceTraderError>(json).unwrap()))
+        };
+        assert!(!store.handle_extraction_error(&spent, &error(ErrorCode::ShipCargoFullError)));
+        assert_eq!(store.len(), 1);
+        assert!(store.handle_extraction_error(&spent, &error(ErrorCode::ShipSurveyExhaustedError)));
+        assert!(store.is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use crate::{
+    api::fleet::resources::ExtractResourcesResponse,
+    schemas::{survey::Survey, trade_symbol::TradeSymbol},
+};
+
+/// An extraction, and what it was made with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YieldRecord {
+    pub waypoint: String,
+    /// Combined strength of the mining lasers of the ship, see [`crate::schemas::ship::ship_capabilities::ShipCapabilities::extraction_strength`].
+    pub strength: u64,
+    /// Signature of the survey used, if any.
+    pub survey: Option<String>,
+    pub symbol: TradeSymbol,
+    pub units: u64,
+    /// Cooldown of the ship after the extraction.
+    pub cooldown: Duration,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Totals of a set of extractions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct YieldSummary {
+    pub extractions: u64,
+    pub units: u64,
+    /// Sum of the cooldowns after the extractions.
+    pub cooldown: Duration,
+    /// Units extracted of each good.
+    pub goods: HashMap<TradeSymbol, u64>,
+}
+
+impl YieldSummary {
+    /// Average units per extraction, `None` without any extraction.
+    pub fn units_per_extraction(&self) -> Option<f64> {
+        (self.extractions > 0).then(|| self.units as f64 / self.extractions as f64)
+    }
+
+    /// Average units per second of cooldown, `None` without any cooldown.
+    pub fn units_per_cooldown_second(&self) -> Option<f64> {
+        let seconds = self.cooldown.as_secs_f64();
+        (seconds > 0.).then(|| self.units as f64 / seconds)
+    }
+
+    /// Average units of a good per extraction, `None` without any extraction.
+    pub fn expected_units_of(&self, symbol: TradeSymbol) -> Option<f64> {
+        (self.extractions > 0).then(|| self.goods.get(&symbol).copied().unwrap_or(0) as f64 / self.extractions as f64)
+    }
+}
+
+/// Yields of the extractions, to compare waypoints, mounts and surveys.
+#[derive(Debug, Clone, Default)]
+pub struct YieldStats {
+    records: Vec<YieldRecord>,
+}
+
+impl YieldStats {
+    /// Create empty statistics.
+    pub fn new() -> YieldStats {
+        YieldStats::default()
+    }
+
+    /// Record an extraction made at a waypoint, by a ship with mining lasers of the given strength.
+    pub fn record(&mut self, waypoint: &str, strength: u64, survey: Option<&Survey>, extraction: &ExtractResourcesResponse, timestamp: DateTime<Utc>) {
+        let extraction_yield = &extraction.extraction.extraction_yield;
+        self.records.push(YieldRecord {
+            waypoint: waypoint.to_string(),
+            strength,
+            survey: survey.map(|survey| survey.signature.clone()),
+            symbol: extraction_yield.symbol,
+            units: extraction_yield.units.max(0) as u64,
+            cooldown: Duration::from_secs(extraction.cooldown.total_seconds),
+            timestamp,
+        });
+    }
+
+    /// Every recorded extraction, oldest first.
+    pub fn records(&self) -> &[YieldRecord] {
+        &self.records
+    }
+
+    /// Number of recorded extractions.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether no extraction is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Totals of the extractions matching the filter.
+    pub fn summary(&self, filter: impl Fn(&YieldRecord) -> bool) -> YieldSummary {
+        let mut summary = YieldSummary::default();
+        for record in self.records.iter().filter(|record| filter(record)) {
+            summary.extractions += 1;
+            summary.units += record.units;
+            summary.cooldown += record.cooldown;
+            *summary.goods.entry(record.symbol).or_default() += record.units;
+        }
+        summary
+    }
+
+    /// Totals of the extractions at a waypoint.
+    pub fn at_waypoint(&self, waypoint: &str) -> YieldSummary {
+        self.summary(|record| record.waypoint == waypoint)
+    }
+
+    /// Totals of the extractions at a waypoint by ships with mining lasers of the given strength,
+    /// with or without a survey.
+    pub fn with_strength(&self, waypoint: &str, strength: u64) -> YieldSummary {
+        self.summary(|record| record.waypoint == waypoint && record.strength == strength)
+    }
+
+    /// Totals of the extractions made with a survey.
+    pub fn with_survey(&self, signature: &str) -> YieldSummary {
+        self.summary(|record| record.survey.as_deref() == Some(signature))
+    }
+
+    /// Totals of the extractions at a waypoint made without a survey.
+    pub fn without_survey(&self, waypoint: &str) -> YieldSummary {
+        self.summary(|record| record.waypoint == waypoint && record.survey.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{conformance::fixture_data, schemas::survey::SurveySize};
+
+    fn extraction() -> ExtractResourcesResponse {
+        fixture_data("extract-resources")
+    }
+
+    #[test]
+    fn statistics() {
+        let now = Utc::now();
+        let survey = Survey {
+            signature: "S".to_string(),
+            symbol: "X1-A-B1".to_string(),
+            deposits: Vec::new(),
+            expiration: now,
+            size: SurveySize::Small,
+        };
+        let mut stats = YieldStats::new();
+        assert_eq!(stats.at_waypoint("X1-A-B1").units_per_extraction(), None);
+
+        // 8 units of aluminum ore, 70 seconds of cooldown
+        let mut extraction = extraction();
+        stats.record("X1-A-B1", 10, None, &extraction, now);
+        extraction.extraction.extraction_yield.units = 12;
+        stats.record("X1-A-B1", 10, Some(&survey), &extraction, now);
+        extraction.extraction.extraction_yield.symbol = TradeSymbol::IronOre;
+        extraction.extraction.extraction_yield.units = 16;
+        stats.record("X1-A-B1", 20, Some(&survey), &extraction, now);
+        stats.record("X1-A-B2", 10, None, &extraction, now);
+        assert_eq!(stats.len(), 4);
+
+        let waypoint = stats.at_waypoint("X1-A-B1");
+        assert_eq!(waypoint.extractions, 3);
+        assert_eq!(waypoint.units, 36);
+        assert_eq!(waypoint.units_per_extraction(), Some(12.));
+        assert_eq!(waypoint.units_per_cooldown_second(), Some(36. / 210.));
+        assert_eq!(waypoint.expected_units_of(TradeSymbol::AluminumOre), Some(20. / 3.));
+        assert_eq!(waypoint.expected_units_of(TradeSymbol::Gold), Some(0.));
+
+        assert_eq!(stats.with_strength("X1-A-B1", 10).units_per_extraction(), Some(10.));
+        assert_eq!(stats.with_survey("S").units_per_extraction(), Some(14.));
+        assert_eq!(stats.without_survey("X1-A-B1").units, 8);
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::schemas::{
+    construction::Construction,
+    jump_gate::JumpGate,
+    waypoint::{Waypoint, waypoint_type::WaypointType},
+};
+use super::system_symbol;
+
+/// Units of antimatter consumed by a single jump.
+pub const ANTIMATTER_PER_JUMP: u64 = 1;
+
+/// A jump gate known to the network.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Gate {
+    /// Gates this gate is connected to.
+    connections: BTreeSet<String>,
+    /// Whether the connections of the gate were fetched, rather than deduced from other gates.
+    charted: bool,
+    /// Whether the gate is still under construction, and can't be used.
+    under_construction: bool,
+}
+
+/// Graph of the jump gates of the universe, built incrementally as gates are discovered.
+///
+/// Connections are symmetric: a gate listed as a connection of another gate is connected back to it,
+/// even if its own connections were not fetched yet.
+#[derive(Debug, Clone, Default)]
+pub struct JumpNetwork {
+    gates: BTreeMap<String, Gate>,
+}
+
+/// A sequence of jumps between two systems.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpPath {
+    /// Symbols of the gates along the path, from the departure gate to the arrival gate.
+    pub gates: Vec<String>,
+}
+
+impl JumpPath {
+    /// Number of jumps along the path.
+    pub fn jumps(&self) -> usize {
+        self.gates.len().saturating_sub(1)
+    }
+
+    /// Systems along the path, from the departure system to the arrival system.
+    pub fn systems(&self) -> impl Iterator<Item = &str> + '_ {
+        self.gates.iter().map(|gate| system_symbol(gate))
+    }
+
+    /// Units of antimatter needed to travel the path.
+    pub fn antimatter(&self) -> u64 {
+        self.jumps() as u64 * ANTIMATTER_PER_JUMP
+    }
+
+    /// Estimated credits spent on antimatter along the path, at the given price per unit.
+    pub fn estimated_cost(&self, antimatter_price: u64) -> u64 {
+        self.antimatter() * antimatter_price
+    }
+}
+
+impl JumpNetwork {
+    /// Create an empty network.
+    pub fn new() -> JumpNetwork {
+        JumpNetwork::default()
+    }
+
+    /// Add the connections of a jump gate.
+    pub fn add_gate(&mut self, gate: &JumpGate) {
+        let node = self.gates.entry(gate.symbol.clone()).or_default();
+        node.charted = true;
+        node.connections.extend(gate.connections.iter().cloned());
+        for connection in gate.connections.iter() {
+            self.gates.entry(connection.clone()).or_default().connections.insert(gate.symbol.clone());
+        }
+    }
+
+    /// Record the construction state of a jump gate.
+    pub fn add_construction(&mut self, construction: &Construction) {
+        self.set_under_construction(&construction.symbol, !construction.is_complete);
+    }
+
+    /// Record the construction state of a waypoint, if it is a jump gate.
+    pub fn add_waypoint(&mut self, waypoint: &Waypoint) {
+        if waypoint.waypoint_type == WaypointType::JumpGate {
+            self.set_under_construction(&waypoint.symbol, waypoint.is_under_construction);
+        }
+    }
+
+    /// Set whether a gate is under construction. Gates under construction can't be jumped from or to.
+    pub fn set_under_construction(&mut self, gate_symbol: &str, under_construction: bool) {
+        self.gates.entry(gate_symbol.to_string()).or_default().under_construction = under_construction;
+    }
+
+    /// Whether the gate is known and usable.
+    pub fn is_usable(&self, gate_symbol: &str) -> bool {
+        self.gates.get(gate_symbol).is_some_and(|gate| !gate.under_construction)
+    }
+
+    /// Gates that are known to exist, but whose connections were not fetched yet.
+    pub fn uncharted_gates(&self) -> impl Iterator<Item = &str> + '_ {
+        self.gates.iter().filter(|(_, gate)| !gate.charted).map(|(symbol, _)| symbol.as_str())
+    }
+
+    /// The jump gate of a system, if known.
+    pub fn gate_of_system(&self, system: &str) -> Option<&str> {
+        self.gates.keys().map(String::as_str).find(|gate| system_symbol(gate) == system)
+    }
+
+    /// Usable connections of a gate.
+    fn usable_connections<'a>(&'a self, gate_symbol: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let connections = self.gates.get(gate_symbol)
+            .filter(|gate| !gate.under_construction)
+            .map(|gate| &gate.connections);
+        connections.into_iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|connection| self.is_usable(connection))
+    }
+
+    /// The path with the fewest jumps between two systems, if they are connected.
+    pub fn shortest_path(&self, from_system: &str, to_system: &str) -> Option<JumpPath> {
+        let start = self.gate_of_system(from_system)?;
+        let target = self.gate_of_system(to_system)?;
+        if start == target {
+            return Some(JumpPath { gates: vec![start.to_string()] });
+        }
+        let mut previous = BTreeMap::<&str, &str>::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(gate) = queue.pop_front() {
+            for connection in self.usable_connections(gate) {
+                if connection == start || previous.contains_key(connection) {
+                    continue;
+                }
+                previous.insert(connection, gate);
+                if connection == target {
+                    let mut gates = vec![target.to_string()];
+                    let mut current = target;
+                    while let Some(gate) = previous.get(current) {
+                        gates.push(gate.to_string());
+                        current = gate;
+                    }
+                    gates.reverse();
+                    return Some(JumpPath { gates });
+                }
+                queue.push_back(connection);
+            }
+        }
+        None
+    }
+
+    /// Systems that can be reached by jumping from the given system, including itself.
+    pub fn reachable_systems(&self, from_system: &str) -> BTreeSet<String> {
+        let mut reached = BTreeSet::new();
+        let start = match self.gate_of_system(from_system) {
+            Some(start) => start,
+            None => return reached,
+        };
+        let mut visited = BTreeSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(gate) = queue.pop_front() {
+            reached.insert(system_symbol(gate).to_string());
+            for connection in self.usable_connections(gate) {
+                if visited.insert(connection) {
+                    queue.push_back(connection);
+                }
+            }
+        }
+        reached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance::fixture_data;
+
+    fn gate(symbol: &str, connections: &[&str]) -> JumpGate {
+        JumpGate {
+            symbol: symbol.to_string(),
+            connections: connections.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    /// X1-A -- X1-B -- X1-C -- X1-D, and X1-A -- X1-E -- X1-D
+    fn network() -> JumpNetwork {
+        let mut network = JumpNetwork::new();
+        network.add_gate(&gate("X1-A-G", &["X1-B-G", "X1-E-G"]));
+        network.add_gate(&gate("X1-B-G", &["X1-C-G"]));
+        network.add_gate(&gate("X1-C-G", &["X1-D-G"]));
+        network.add_gate(&gate("X1-E-G", &["X1-D-G"]));
+        network
+    }
+
+    #[test]
+    fn fixture_gate() {
+        let jump_gate: JumpGate = fixture_data("get-jump-gate");
+        let mut network = JumpNetwork::new();
+        network.add_gate(&jump_gate);
+        assert_eq!(network.gate_of_system("X1-MH34"), Some("X1-MH34-I57"));
+        assert_eq!(network.reachable_systems("X1-MH34").len(), 4);
+        assert_eq!(network.uncharted_gates().count(), 3);
+        let path = network.shortest_path("X1-QN2", "X1-KS52").unwrap();
+        assert_eq!(path.systems().collect::<Vec<_>>(), ["X1-QN2", "X1-MH34", "X1-KS52"]);
+    }
+
+    #[test]
+    fn shortest_path() {
+        let network = network();
+        let path = network.shortest_path("X1-A", "X1-D").unwrap();
+        assert_eq!(path.gates, ["X1-A-G", "X1-E-G", "X1-D-G"]);
+        assert_eq!(path.jumps(), 2);
+        assert_eq!(path.antimatter(), 2);
+        assert_eq!(path.estimated_cost(5000), 10000);
+        assert_eq!(network.shortest_path("X1-A", "X1-A").unwrap().jumps(), 0);
+        assert!(network.shortest_path("X1-A", "X1-Z").is_none());
+    }
+
+    #[test]
+    fn construction() {
+        let mut network = network();
+        network.set_under_construction("X1-E-G", true);
+        let path = network.shortest_path("X1-A", "X1-D").unwrap();
+        assert_eq!(path.systems().collect::<Vec<_>>(), ["X1-A", "X1-B", "X1-C", "X1-D"]);
+        network.set_under_construction("X1-C-G", true);
+        assert!(network.shortest_path("X1-A", "X1-D").is_none());
+        assert_eq!(network.reachable_systems("X1-A"), BTreeSet::from(["X1-A".to_string(), "X1-B".to_string()]));
+    }
+}
@@ -0,0 +1,61 @@
+pub mod jump_network;
+pub mod model;
+pub mod route;
+pub mod spatial;
+pub mod warp;
+
+use crate::schemas::{
+    waypoint::Waypoint,
+    system::{System, system_waypoint::SystemWaypoint},
+    scanned::{scanned_system::ScannedSystem, scanned_waypoint::ScannedWaypoint},
+    ship::ship_nav::ship_nav_route::ship_nav_route_waypoint::ShipNavRouteWaypoint,
+};
+
+/// Symbol of the system a waypoint belongs to: waypoint symbols are the system symbol followed by the waypoint identifier.
+pub fn system_symbol(waypoint_symbol: &str) -> &str {
+    match waypoint_symbol.rfind('-') {
+        Some(index) if waypoint_symbol[..index].contains('-') => &waypoint_symbol[..index],
+        _ => waypoint_symbol,
+    }
+}
+
+/// Anything that has coordinates: waypoints in a system, or systems in the universe.
+pub trait Position {
+    /// Position on the x axis.
+    fn x(&self) -> i64;
+    /// Position on the y axis.
+    fn y(&self) -> i64;
+
+    /// Euclidean distance to another position.
+    fn distance_to<P: Position + ?Sized>(&self, other: &P) -> f64 {
+        let dx = (self.x() - other.x()) as f64;
+        let dy = (self.y() - other.y()) as f64;
+        dx.hypot(dy)
+    }
+}
+
+impl Position for (i64, i64) {
+    fn x(&self) -> i64 {
+        self.0
+    }
+    fn y(&self) -> i64 {
+        self.1
+    }
+}
+
+macro_rules! impl_position {
+    ($($ty:ty),*) => {
+        $(
+            impl Position for $ty {
+                fn x(&self) -> i64 {
+                    self.x
+                }
+                fn y(&self) -> i64 {
+                    self.y
+                }
+            }
+        )*
+    };
+}
+
+impl_position!(Waypoint, SystemWaypoint, ShipNavRouteWaypoint, ScannedWaypoint, System, ScannedSystem);
@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use crate::schemas::ship::{Ship, ship_nav::ship_nav_flight_mode::ShipNavFlightMode};
+use super::Position;
+
+/// Fixed time, in seconds, added to every navigation regardless of distance.
+pub const NAVIGATION_BASE_SECONDS: f64 = 15.;
+
+/// Fuel and time needed to travel between two waypoints of a system.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Leg {
+    /// The distance between the two waypoints.
+    pub distance: f64,
+    /// The flight mode of the ship during the travel.
+    pub flight_mode: ShipNavFlightMode,
+    /// The fuel consumed by the travel.
+    pub fuel: u64,
+    /// The duration of the travel.
+    pub duration: Duration,
+}
+
+/// Multiplier of the travel time for each flight mode, relative to the engine speed.
+pub fn speed_multiplier(mode: ShipNavFlightMode) -> f64 {
+    match mode {
+        ShipNavFlightMode::Cruise => 25.,
+        ShipNavFlightMode::Drift => 250.,
+        ShipNavFlightMode::Burn => 12.5,
+        ShipNavFlightMode::Stealth => 30.,
+    }
+}
+
+/// Distance counted by the server: rounded, and at least 1 unless the waypoints are at the same coordinates.
+fn rounded_distance(distance: f64) -> u64 {
+    if distance <= 0. {
+        return 0;
+    }
+    (distance.round() as u64).max(1)
+}
+
+/// Fuel consumed to travel the given distance in the given flight mode.
+///
+/// Drifting always costs a single unit of fuel, burning costs twice as much as cruising,
+/// and travelling to a waypoint at the same coordinates (such as an orbital) costs nothing.
+pub fn fuel_cost(distance: f64, mode: ShipNavFlightMode) -> u64 {
+    let distance = rounded_distance(distance);
+    if distance == 0 {
+        return 0;
+    }
+    match mode {
+        ShipNavFlightMode::Cruise | ShipNavFlightMode::Stealth => distance,
+        ShipNavFlightMode::Drift => 1,
+        ShipNavFlightMode::Burn => 2 * distance,
+    }
+}
+
+/// Time needed to travel the given distance with an engine of the given speed, in the given flight mode.
+/// Travelling to a waypoint at the same coordinates only takes [`NAVIGATION_BASE_SECONDS`].
+pub fn travel_time(distance: f64, engine_speed: u64, mode: ShipNavFlightMode) -> Duration {
+    let seconds = rounded_distance(distance) as f64 * speed_multiplier(mode) / engine_speed.max(1) as f64 + NAVIGATION_BASE_SECONDS;
+    Duration::from_secs(seconds.round() as u64)
+}
+
+/// Plan a leg between two positions with an engine of the given speed.
+pub fn plan_leg_with_speed<F: Position + ?Sized, T: Position + ?Sized>(engine_speed: u64, from: &F, to: &T, mode: ShipNavFlightMode) -> Leg {
+    let distance = from.distance_to(to);
+    Leg {
+        distance,
+        flight_mode: mode,
+        fuel: fuel_cost(distance, mode),
+        duration: travel_time(distance, engine_speed, mode),
+    }
+}
+
+/// Plan a leg of the given ship between two positions.
+pub fn plan_leg<F: Position + ?Sized, T: Position + ?Sized>(ship: &Ship, from: &F, to: &T, mode: ShipNavFlightMode) -> Leg {
+    plan_leg_with_speed(ship.engine.speed.get(), from, to, mode)
+}
+
+impl Leg {
+    /// Whether a ship with the given amount of fuel can travel this leg.
+    /// Ships without fuel capacity, such as probes, don't need fuel to travel.
+    pub fn is_affordable(&self, current_fuel: u64, fuel_capacity: u64) -> bool {
+        fuel_capacity == 0 || self.fuel <= current_fuel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::fleet::nav::NavResult;
+
+    /// A navigation recorded from the server: the `data` of a navigate-ship response, and the speed of the engine of the ship.
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RecordedNavigation {
+        engine_speed: u64,
+        data: NavResult,
+    }
+
+    #[test]
+    #[ignore = "needs navigations recorded from the live server in fixtures/recorded/navigations.json"]
+    fn recorded_navigations() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/recorded/navigations.json");
+        let recorded: Vec<RecordedNavigation> = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert!(!recorded.is_empty(), "no recorded navigation");
+        for RecordedNavigation { engine_speed, data } in recorded {
+            let route = &data.nav.route;
+            let leg = plan_leg_with_speed(engine_speed, &route.origin, &route.destination, data.nav.flight_mode);
+            let duration = (route.arrival - route.departure_time).num_milliseconds() as f64 / 1000.;
+            let description = format!("{} to {} at speed {engine_speed} in {:?}", route.origin.symbol, route.destination.symbol, data.nav.flight_mode);
+            assert_eq!(leg.fuel, data.fuel.consumed.unwrap().amount, "fuel of {description}");
+            assert_eq!(leg.duration, Duration::from_secs(duration.round() as u64), "duration of {description}");
+        }
+    }
+
+    #[test]
+    fn fuel() {
+        assert_eq!(fuel_cost(0., ShipNavFlightMode::Cruise), 0);
+        assert_eq!(fuel_cost(0.4, ShipNavFlightMode::Cruise), 1);
+        assert_eq!(fuel_cost(42.5, ShipNavFlightMode::Cruise), 43);
+        assert_eq!(fuel_cost(42.5, ShipNavFlightMode::Stealth), 43);
+        assert_eq!(fuel_cost(42.5, ShipNavFlightMode::Burn), 86);
+        assert_eq!(fuel_cost(42.5, ShipNavFlightMode::Drift), 1);
+    }
+
+    #[test]
+    fn time() {
+        // probes have a speed of 3
+        assert_eq!(travel_time(100., 3, ShipNavFlightMode::Cruise), Duration::from_secs(848));
+        assert_eq!(travel_time(100., 30, ShipNavFlightMode::Burn), Duration::from_secs(57));
+        assert_eq!(travel_time(100., 30, ShipNavFlightMode::Drift), Duration::from_secs(848));
+        assert_eq!(travel_time(0.4, 30, ShipNavFlightMode::Cruise), Duration::from_secs(16));
+        // orbitals: no fuel and the base time only
+        assert_eq!(travel_time(0., 30, ShipNavFlightMode::Cruise), Duration::from_secs(15));
+    }
+}
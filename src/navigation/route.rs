@@ -0,0 +1,371 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    time::Duration,
+};
+
+use crate::schemas::{
+    market::Market,
+    ship::{Ship, ship_nav::ship_nav_flight_mode::ShipNavFlightMode},
+    system::System,
+    trade_symbol::TradeSymbol,
+    waypoint::Waypoint,
+};
+use super::{
+    Position,
+    model::{Leg, plan_leg_with_speed},
+};
+
+/// A waypoint known to the route planner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapWaypoint {
+    /// Position of the waypoint in the system.
+    pub x: i64,
+    /// Position of the waypoint in the system.
+    pub y: i64,
+    /// Whether fuel can be bought at this waypoint.
+    pub sells_fuel: bool,
+}
+
+impl Position for MapWaypoint {
+    fn x(&self) -> i64 {
+        self.x
+    }
+    fn y(&self) -> i64 {
+        self.y
+    }
+}
+
+/// The waypoints of a system, and where fuel can be bought.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemMap {
+    waypoints: BTreeMap<String, MapWaypoint>,
+}
+
+/// What the route planner optimizes for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RouteObjective {
+    /// Arrive as soon as possible.
+    #[default]
+    Fastest,
+    /// Consume as little fuel as possible.
+    LeastFuel,
+}
+
+impl RouteObjective {
+    /// Cost of a leg for this objective, lower is better.
+    fn cost(self, leg: &Leg) -> (u64, u64) {
+        match self {
+            RouteObjective::Fastest => (leg.duration.as_secs(), leg.fuel),
+            RouteObjective::LeastFuel => (leg.fuel, leg.duration.as_secs()),
+        }
+    }
+}
+
+/// Options of the route planner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteOptions {
+    /// What the planner optimizes for.
+    pub objective: RouteObjective,
+    /// Flight modes the ship is allowed to use.
+    pub flight_modes: Vec<ShipNavFlightMode>,
+}
+
+impl Default for RouteOptions {
+    fn default() -> Self {
+        RouteOptions {
+            objective: RouteObjective::Fastest,
+            flight_modes: vec![ShipNavFlightMode::Burn, ShipNavFlightMode::Cruise, ShipNavFlightMode::Drift],
+        }
+    }
+}
+
+/// A single navigation of a route, or a single warp of a route planned by a [`WarpMap`](super::warp::WarpMap).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hop {
+    /// Symbol of the waypoint the hop starts from, or of the system for a warp.
+    pub origin: String,
+    /// Symbol of the waypoint the hop goes to, or of the system for a warp.
+    pub destination: String,
+    /// Whether the ship must refuel at the origin before departing. For a warp, the ship refuels at the fuel station of the system.
+    pub refuel: bool,
+    /// Fuel, duration and flight mode of the hop.
+    pub leg: Leg,
+}
+
+/// A sequence of hops between two waypoints of a system, or between two systems for a warp route.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Route {
+    /// The hops of the route, in order.
+    pub hops: Vec<Hop>,
+}
+
+impl Route {
+    /// Total fuel consumed by the route.
+    pub fn fuel(&self) -> u64 {
+        self.hops.iter().map(|hop| hop.leg.fuel).sum()
+    }
+
+    /// Total travel time of the route.
+    pub fn duration(&self) -> Duration {
+        self.hops.iter().map(|hop| hop.leg.duration).sum()
+    }
+
+    /// Waypoints where the ship refuels along the route.
+    pub fn refuel_stops(&self) -> impl Iterator<Item = &str> + '_ {
+        self.hops.iter().filter(|hop| hop.refuel).map(|hop| hop.origin.as_str())
+    }
+}
+
+impl SystemMap {
+    /// Create an empty map.
+    pub fn new() -> SystemMap {
+        SystemMap::default()
+    }
+
+    /// Create a map with the waypoints of a system. No waypoint sells fuel until markets are added.
+    pub fn from_system(system: &System) -> SystemMap {
+        let mut map = SystemMap::new();
+        for waypoint in system.waypoints.iter() {
+            map.insert(&waypoint.symbol, waypoint);
+        }
+        map
+    }
+
+    /// Create a map with the given waypoints. No waypoint sells fuel until markets are added.
+    pub fn from_waypoints<'a>(waypoints: impl IntoIterator<Item = &'a Waypoint>) -> SystemMap {
+        let mut map = SystemMap::new();
+        for waypoint in waypoints {
+            map.insert(&waypoint.symbol, waypoint);
+        }
+        map
+    }
+
+    /// Add or move a waypoint. Whether it sells fuel is kept if it was already known.
+    pub fn insert<P: Position + ?Sized>(&mut self, symbol: &str, position: &P) {
+        let waypoint = self.waypoints.entry(symbol.to_string()).or_insert(MapWaypoint { x: 0, y: 0, sells_fuel: false });
+        waypoint.x = position.x();
+        waypoint.y = position.y();
+    }
+
+    /// Set whether fuel can be bought at a known waypoint.
+    pub fn set_sells_fuel(&mut self, symbol: &str, sells_fuel: bool) {
+        if let Some(waypoint) = self.waypoints.get_mut(symbol) {
+            waypoint.sells_fuel = sells_fuel;
+        }
+    }
+
+    /// Record whether the market sells fuel.
+    pub fn add_market(&mut self, market: &Market) {
+        self.set_sells_fuel(&market.symbol, market.sells(TradeSymbol::Fuel));
+    }
+
+    /// The waypoint with the given symbol, if known.
+    pub fn get(&self, symbol: &str) -> Option<&MapWaypoint> {
+        self.waypoints.get(symbol)
+    }
+
+    /// Iterate over the known waypoints.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &MapWaypoint)> + '_ {
+        self.waypoints.iter().map(|(symbol, waypoint)| (symbol.as_str(), waypoint))
+    }
+
+    /// Plan a route for the ship from its current waypoint to the destination, starting with its current fuel.
+    pub fn plan_route(&self, ship: &Ship, destination: &str, options: &RouteOptions) -> Option<Route> {
+        self.plan_route_with(
+            ship.engine.speed.get(),
+            ship.fuel.current,
+            ship.fuel.capacity,
+            &ship.nav.waypoint_symbol,
+            destination,
+            options,
+        )
+    }
+
+    /// Plan a route between two waypoints for a ship with the given engine speed and fuel.
+    ///
+    /// The ship refuels to full capacity at every intermediate stop, and at the origin when it needs to.
+    /// Ships without fuel capacity, such as probes, always fly directly.
+    /// Returns `None` if a waypoint is unknown, or if the destination can't be reached.
+    pub fn plan_route_with(
+        &self,
+        engine_speed: u64,
+        current_fuel: u64,
+        fuel_capacity: u64,
+        origin: &str,
+        destination: &str,
+        options: &RouteOptions,
+    ) -> Option<Route> {
+        self.plan(current_fuel, fuel_capacity, origin, destination, options, |from, to, mode| {
+            Some(plan_leg_with_speed(engine_speed, from, to, mode))
+        })
+    }
+
+    /// Plan a route between two entries of the map, where `leg` gives the leg between two entries in a flight mode,
+    /// or `None` if the ship can't travel between them regardless of its fuel.
+    pub(crate) fn plan(
+        &self,
+        current_fuel: u64,
+        fuel_capacity: u64,
+        origin: &str,
+        destination: &str,
+        options: &RouteOptions,
+        leg: impl Fn(&MapWaypoint, &MapWaypoint, ShipNavFlightMode) -> Option<Leg>,
+    ) -> Option<Route> {
+        let origin_waypoint = self.waypoints.get(origin)?;
+        let destination_waypoint = self.waypoints.get(destination)?;
+        if origin == destination {
+            return Some(Route::default());
+        }
+        if fuel_capacity == 0 {
+            let leg = best_leg(&leg, origin_waypoint, destination_waypoint, None, options)?;
+            return Some(Route { hops: vec![Hop { origin: origin.to_string(), destination: destination.to_string(), refuel: false, leg }] });
+        }
+
+        // the only places worth stopping at are fuel stations: nodes are the origin, the stations and the destination.
+        let mut nodes = vec![(origin, origin_waypoint)];
+        nodes.extend(self.waypoints.iter()
+            .filter(|(symbol, waypoint)| waypoint.sells_fuel && *symbol != origin && *symbol != destination)
+            .map(|(symbol, waypoint)| (symbol.as_str(), waypoint)));
+        nodes.push((destination, destination_waypoint));
+        let target = nodes.len() - 1;
+
+        // fuel available when leaving a node: stations are left with a full tank.
+        let available_fuel = |node: usize| match node {
+            0 if !origin_waypoint.sells_fuel => current_fuel,
+            _ => fuel_capacity,
+        };
+        let mut best = vec![None::<(u64, u64)>; nodes.len()];
+        let mut previous = vec![None::<(usize, Leg)>; nodes.len()];
+        let mut queue = BinaryHeap::new();
+        best[0] = Some((0, 0));
+        queue.push(Reverse(((0, 0), 0)));
+        while let Some(Reverse((node_cost, node))) = queue.pop() {
+            if best[node].is_some_and(|best| best < node_cost) {
+                continue;
+            }
+            if node == target {
+                break;
+            }
+            for (next, (_, next_waypoint)) in nodes.iter().enumerate().skip(1) {
+                if next == node {
+                    continue;
+                }
+                let leg = match best_leg(&leg, nodes[node].1, next_waypoint, Some(available_fuel(node)), options) {
+                    Some(leg) => leg,
+                    None => continue,
+                };
+                let leg_cost = options.objective.cost(&leg);
+                let next_cost = (node_cost.0 + leg_cost.0, node_cost.1 + leg_cost.1);
+                if best[next].is_none_or(|best| next_cost < best) {
+                    best[next] = Some(next_cost);
+                    previous[next] = Some((node, leg));
+                    queue.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+
+        let mut hops = Vec::new();
+        let mut node = target;
+        while let Some((from, leg)) = previous[node].take() {
+            hops.push(Hop {
+                origin: nodes[from].0.to_string(),
+                destination: nodes[node].0.to_string(),
+                refuel: from != 0 || leg.fuel > current_fuel,
+                leg,
+            });
+            node = from;
+        }
+        if node != 0 {
+            return None;
+        }
+        hops.reverse();
+        Some(Route { hops })
+    }
+}
+
+/// Best leg between two waypoints among the allowed flight modes, within the available fuel.
+fn best_leg(
+    leg: &impl Fn(&MapWaypoint, &MapWaypoint, ShipNavFlightMode) -> Option<Leg>,
+    from: &MapWaypoint,
+    to: &MapWaypoint,
+    available_fuel: Option<u64>,
+    options: &RouteOptions,
+) -> Option<Leg> {
+    options.flight_modes.iter()
+        .filter_map(|mode| leg(from, to, *mode))
+        .filter(|leg| available_fuel.is_none_or(|fuel| leg.fuel <= fuel))
+        .min_by_key(|leg| options.objective.cost(leg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::model::travel_time;
+
+    /// A line of waypoints, 100 units apart, where only B and D sell fuel.
+    fn map() -> SystemMap {
+        let mut map = SystemMap::new();
+        for (index, symbol) in ["A", "B", "C", "D", "E"].into_iter().enumerate() {
+            map.insert(symbol, &(index as i64 * 100, 0));
+        }
+        map.set_sells_fuel("B", true);
+        map.set_sells_fuel("D", true);
+        map
+    }
+
+    fn cruise() -> RouteOptions {
+        RouteOptions { flight_modes: vec![ShipNavFlightMode::Cruise], ..Default::default() }
+    }
+
+    #[test]
+    fn direct_route() {
+        let route = map().plan_route_with(30, 400, 400, "A", "E", &cruise()).unwrap();
+        assert_eq!(route.hops.len(), 1);
+        assert_eq!(route.hops[0].leg.flight_mode, ShipNavFlightMode::Cruise);
+        assert_eq!(route.fuel(), 400);
+        assert_eq!(route.refuel_stops().count(), 0);
+    }
+
+    #[test]
+    fn burn_between_stations() {
+        // burning from station to station is faster than cruising directly
+        let route = map().plan_route_with(30, 400, 400, "A", "E", &RouteOptions::default()).unwrap();
+        assert!(route.hops.iter().all(|hop| hop.leg.flight_mode == ShipNavFlightMode::Burn));
+        assert_eq!(route.refuel_stops().collect::<Vec<_>>(), ["B", "D"]);
+        assert!(route.duration() < travel_time(400., 30, ShipNavFlightMode::Cruise));
+    }
+
+    #[test]
+    fn refuel_stops() {
+        let route = map().plan_route_with(30, 150, 250, "A", "E", &cruise()).unwrap();
+        let stops = route.hops.iter().map(|hop| hop.destination.as_str()).collect::<Vec<_>>();
+        assert_eq!(stops, ["B", "D", "E"]);
+        assert_eq!(route.refuel_stops().collect::<Vec<_>>(), ["B", "D"]);
+        assert_eq!(route.fuel(), 400);
+    }
+
+    #[test]
+    fn unreachable() {
+        assert!(map().plan_route_with(30, 50, 150, "A", "E", &cruise()).is_none());
+        assert!(map().plan_route_with(30, 50, 150, "A", "Z", &cruise()).is_none());
+        // drifting only takes a single unit of fuel
+        let route = map().plan_route_with(30, 1, 150, "A", "E", &RouteOptions::default()).unwrap();
+        assert_eq!(route.hops[0].leg.flight_mode, ShipNavFlightMode::Drift);
+    }
+
+    #[test]
+    fn objectives() {
+        let options = RouteOptions { objective: RouteObjective::LeastFuel, ..Default::default() };
+        let route = map().plan_route_with(30, 400, 400, "A", "E", &options).unwrap();
+        assert_eq!(route.fuel(), 1);
+        let route = map().plan_route_with(30, 800, 800, "A", "E", &RouteOptions::default()).unwrap();
+        assert_eq!(route.hops[0].leg.flight_mode, ShipNavFlightMode::Burn);
+    }
+
+    #[test]
+    fn probes() {
+        let route = map().plan_route_with(3, 0, 0, "A", "E", &cruise()).unwrap();
+        assert_eq!(route.hops.len(), 1);
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+
+use crate::schemas::{
+    system::System,
+    waypoint::{Waypoint, waypoint_trait::WaypointTraitType},
+};
+use super::Position;
+
+/// A k-d tree over items with coordinates, such as the systems of the universe or the waypoints of a system.
+#[derive(Debug, Clone)]
+pub struct SpatialIndex<T: Position> {
+    /// Items ordered as an implicit tree: the root of a range is its middle item,
+    /// splitting on x at even depths and on y at odd depths.
+    items: Vec<T>,
+}
+
+fn squared_distance<A: Position + ?Sized, B: Position + ?Sized>(a: &A, b: &B) -> f64 {
+    let dx = (a.x() - b.x()) as f64;
+    let dy = (a.y() - b.y()) as f64;
+    dx * dx + dy * dy
+}
+
+fn coordinate<P: Position + ?Sized>(position: &P, depth: usize) -> i64 {
+    match depth % 2 {
+        0 => position.x(),
+        _ => position.y(),
+    }
+}
+
+fn build<T: Position>(items: &mut [T], depth: usize) {
+    if items.len() <= 1 {
+        return;
+    }
+    let middle = items.len() / 2;
+    items.select_nth_unstable_by_key(middle, |item| coordinate(item, depth));
+    let (left, right) = items.split_at_mut(middle);
+    build(left, depth + 1);
+    build(&mut right[1..], depth + 1);
+}
+
+impl<T: Position> SpatialIndex<T> {
+    /// Build the index over the given items.
+    pub fn new(items: impl IntoIterator<Item = T>) -> SpatialIndex<T> {
+        let mut items = items.into_iter().collect::<Vec<_>>();
+        build(&mut items, 0);
+        SpatialIndex { items }
+    }
+
+    /// Number of items in the index.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterate over the items of the index, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.items.iter()
+    }
+
+    /// The `count` items closest to the point, closest first, with their distance.
+    pub fn nearest<P: Position + ?Sized>(&self, point: &P, count: usize) -> Vec<(&T, f64)> {
+        self.nearest_matching(point, count, |_| true)
+    }
+
+    /// The `count` items matching the predicate closest to the point, closest first, with their distance.
+    pub fn nearest_matching<P: Position + ?Sized>(&self, point: &P, count: usize, predicate: impl Fn(&T) -> bool) -> Vec<(&T, f64)> {
+        let mut found = Vec::with_capacity(count + 1);
+        if count > 0 {
+            self.search_nearest(&self.items, 0, point, count, &predicate, &mut found);
+        }
+        found.into_iter().map(|(item, distance): (&T, f64)| (item, distance.sqrt())).collect()
+    }
+
+    fn search_nearest<'a, P: Position + ?Sized>(
+        &self,
+        items: &'a [T],
+        depth: usize,
+        point: &P,
+        count: usize,
+        predicate: &impl Fn(&T) -> bool,
+        found: &mut Vec<(&'a T, f64)>,
+    ) {
+        if items.is_empty() {
+            return;
+        }
+        let middle = items.len() / 2;
+        let item = &items[middle];
+        if predicate(item) {
+            let distance = squared_distance(item, point);
+            if found.len() < count || distance < found[found.len() - 1].1 {
+                let index = found.partition_point(|(_, d)| *d <= distance);
+                found.insert(index, (item, distance));
+                found.truncate(count);
+            }
+        }
+        let offset = (coordinate(point, depth) - coordinate(item, depth)) as f64;
+        let (near, far) = match offset < 0. {
+            true => (&items[..middle], &items[middle + 1..]),
+            false => (&items[middle + 1..], &items[..middle]),
+        };
+        self.search_nearest(near, depth + 1, point, count, predicate, found);
+        if found.len() < count || offset * offset < found[found.len() - 1].1 {
+            self.search_nearest(far, depth + 1, point, count, predicate, found);
+        }
+    }
+
+    /// Items within the radius of the point, closest first, with their distance.
+    pub fn within_radius<P: Position + ?Sized>(&self, point: &P, radius: f64) -> Vec<(&T, f64)> {
+        let mut found = Vec::new();
+        self.search_radius(&self.items, 0, point, radius * radius, &mut found);
+        found.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        found.into_iter().map(|(item, distance)| (item, distance.sqrt())).collect()
+    }
+
+    fn search_radius<'a, P: Position + ?Sized>(&self, items: &'a [T], depth: usize, point: &P, squared_radius: f64, found: &mut Vec<(&'a T, f64)>) {
+        if items.is_empty() {
+            return;
+        }
+        let middle = items.len() / 2;
+        let item = &items[middle];
+        let distance = squared_distance(item, point);
+        if distance <= squared_radius {
+            found.push((item, distance));
+        }
+        let offset = (coordinate(point, depth) - coordinate(item, depth)) as f64;
+        if offset <= 0. || offset * offset <= squared_radius {
+            self.search_radius(&items[..middle], depth + 1, point, squared_radius, found);
+        }
+        if offset >= 0. || offset * offset <= squared_radius {
+            self.search_radius(&items[middle + 1..], depth + 1, point, squared_radius, found);
+        }
+    }
+}
+
+impl SpatialIndex<Waypoint> {
+    /// The waypoint with the given trait closest to the point, with its distance.
+    pub fn nearest_with_trait<P: Position + ?Sized>(&self, point: &P, waypoint_trait: WaypointTraitType) -> Option<(&Waypoint, f64)> {
+        self.nearest_matching(point, 1, |waypoint| waypoint.traits.iter().any(|t| t.symbol == waypoint_trait))
+            .into_iter()
+            .next()
+    }
+}
+
+/// Distances between every pair of waypoints of a system.
+#[derive(Debug, Clone)]
+pub struct DistanceMatrix {
+    symbols: Vec<String>,
+    /// Position of each symbol in `symbols`.
+    indices: HashMap<String, usize>,
+    distances: Vec<f64>,
+}
+
+impl DistanceMatrix {
+    /// Compute the distances between every pair of the given positions.
+    pub fn new<'a, P: Position + 'a>(positions: impl IntoIterator<Item = (&'a str, &'a P)>) -> DistanceMatrix {
+        let positions = positions.into_iter().collect::<Vec<_>>();
+        let distances = positions.iter()
+            .flat_map(|(_, from)| positions.iter().map(move |(_, to)| from.distance_to(*to)))
+            .collect();
+        let symbols = positions.into_iter().map(|(symbol, _)| symbol.to_string()).collect::<Vec<_>>();
+        let mut indices = HashMap::with_capacity(symbols.len());
+        for (index, symbol) in symbols.iter().enumerate() {
+            indices.entry(symbol.clone()).or_insert(index);
+        }
+        DistanceMatrix {
+            symbols,
+            indices,
+            distances,
+        }
+    }
+
+    /// Compute the distances between the waypoints of a system.
+    pub fn from_system(system: &System) -> DistanceMatrix {
+        DistanceMatrix::new(system.waypoints.iter().map(|waypoint| (waypoint.symbol.as_str(), waypoint)))
+    }
+
+    /// Symbols of the positions of the matrix.
+    pub fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    /// Distance between two positions of the matrix, if both are known.
+    pub fn distance(&self, from: &str, to: &str) -> Option<f64> {
+        let from = *self.indices.get(from)?;
+        let to = *self.indices.get(to)?;
+        Some(self.distances[from * self.symbols.len() + to])
+    }
+}
+
+/// Distance matrices of systems, computed on first use.
+#[derive(Debug, Clone, Default)]
+pub struct DistanceCache {
+    matrices: HashMap<String, DistanceMatrix>,
+}
+
+impl DistanceCache {
+    /// Create an empty cache.
+    pub fn new() -> DistanceCache {
+        DistanceCache::default()
+    }
+
+    /// Distance matrix of the system, computed if it is not cached yet.
+    pub fn matrix(&mut self, system: &System) -> &DistanceMatrix {
+        self.matrices.entry(system.symbol.clone()).or_insert_with(|| DistanceMatrix::from_system(system))
+    }
+
+    /// Distance matrix of the system, if cached.
+    pub fn get(&self, system_symbol: &str) -> Option<&DistanceMatrix> {
+        self.matrices.get(system_symbol)
+    }
+
+    /// Forget the distance matrix of a system, for instance after new waypoints were discovered.
+    pub fn invalidate(&mut self, system_symbol: &str) {
+        self.matrices.remove(system_symbol);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance::fixture_data;
+
+    /// Naive nearest search to compare the tree against.
+    fn brute_force(points: &[(i64, i64)], point: &(i64, i64), count: usize) -> Vec<f64> {
+        let mut distances = points.iter().map(|p| p.distance_to(point)).collect::<Vec<_>>();
+        distances.sort_by(f64::total_cmp);
+        distances.truncate(count);
+        distances
+    }
+
+    fn points() -> Vec<(i64, i64)> {
+        // deterministic pseudo random scatter
+        (0..200i64).map(|i| ((i * 7919) % 613 - 300, (i * 104729) % 587 - 290)).collect()
+    }
+
+    #[test]
+    fn nearest() {
+        let points = points();
+        let index = SpatialIndex::new(points.clone());
+        assert_eq!(index.len(), points.len());
+        for query in [(0, 0), (-300, 250), (1000, -1000), (17, -42)] {
+            let found = index.nearest(&query, 5).into_iter().map(|(_, d)| d).collect::<Vec<_>>();
+            assert_eq!(found, brute_force(&points, &query, 5));
+        }
+        assert!(index.nearest(&(0, 0), 0).is_empty());
+        assert_eq!(index.nearest(&(0, 0), 1000).len(), points.len());
+    }
+
+    #[test]
+    fn radius() {
+        let points = points();
+        let index = SpatialIndex::new(points.clone());
+        for radius in [0., 50., 120.5, 10000.] {
+            let found = index.within_radius(&(10, 10), radius);
+            let expected = points.iter().filter(|p| p.distance_to(&(10, 10)) <= radius).count();
+            assert_eq!(found.len(), expected);
+            assert!(found.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        }
+    }
+
+    #[test]
+    fn waypoints() {
+        let waypoints: Vec<Waypoint> = fixture_data("get-system-waypoints");
+        let index = SpatialIndex::new(waypoints.clone());
+        let marketplace = index.nearest_with_trait(&(0, 0), WaypointTraitType::Marketplace);
+        let expected = waypoints.iter()
+            .filter(|w| w.traits.iter().any(|t| t.symbol == WaypointTraitType::Marketplace))
+            .map(|w| w.distance_to(&(0, 0)))
+            .min_by(f64::total_cmp);
+        assert_eq!(marketplace.map(|(_, d)| d), expected);
+
+        let matrix = DistanceMatrix::new(waypoints.iter().map(|w| (w.symbol.as_str(), w)));
+        let (a, b) = (&waypoints[0], &waypoints[waypoints.len() - 1]);
+        assert_eq!(matrix.distance(&a.symbol, &b.symbol), Some(a.distance_to(b)));
+        assert_eq!(matrix.distance(&a.symbol, &a.symbol), Some(0.));
+        assert_eq!(matrix.distance(&a.symbol, "X1-NOPE-A1"), None);
+    }
+}
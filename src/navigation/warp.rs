@@ -0,0 +1,213 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use crate::schemas::{
+    market::Market,
+    ship::{Ship, ship_nav::ship_nav_flight_mode::ShipNavFlightMode},
+    system::System,
+    trade_symbol::TradeSymbol,
+};
+use super::{
+    Position,
+    system_symbol,
+    model::{Leg, fuel_cost, NAVIGATION_BASE_SECONDS},
+    route::{Route, RouteOptions, SystemMap},
+};
+
+/// Multiplier of the warp time for each flight mode, relative to the engine speed.
+pub fn warp_speed_multiplier(mode: ShipNavFlightMode) -> f64 {
+    match mode {
+        ShipNavFlightMode::Cruise => 50.,
+        ShipNavFlightMode::Drift => 300.,
+        ShipNavFlightMode::Burn => 25.,
+        ShipNavFlightMode::Stealth => 50.,
+    }
+}
+
+/// Time needed to warp the given distance with an engine of the given speed, in the given flight mode.
+pub fn warp_time(distance: f64, engine_speed: u64, mode: ShipNavFlightMode) -> Duration {
+    let seconds = distance.max(1.) * warp_speed_multiplier(mode) / engine_speed.max(1) as f64 + NAVIGATION_BASE_SECONDS;
+    Duration::from_secs(seconds.round() as u64)
+}
+
+/// Plan a warp between two systems with an engine of the given speed.
+/// Warping consumes as much fuel as navigating the same distance.
+pub fn plan_warp_leg_with_speed<F: Position + ?Sized, T: Position + ?Sized>(engine_speed: u64, from: &F, to: &T, mode: ShipNavFlightMode) -> Leg {
+    let distance = from.distance_to(to);
+    Leg {
+        distance,
+        flight_mode: mode,
+        fuel: fuel_cost(distance, mode),
+        duration: warp_time(distance, engine_speed, mode),
+    }
+}
+
+/// Estimate a warp of the ship between two systems in every flight mode.
+pub fn warp_legs<F: Position + ?Sized, T: Position + ?Sized>(ship: &Ship, from: &F, to: &T) -> Vec<Leg> {
+    [ShipNavFlightMode::Burn, ShipNavFlightMode::Cruise, ShipNavFlightMode::Stealth, ShipNavFlightMode::Drift]
+        .into_iter()
+        .map(|mode| plan_warp_leg_with_speed(ship.engine.speed.get(), from, to, mode))
+        .collect()
+}
+
+/// Systems of the universe, and which of them have a market selling fuel.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WarpMap {
+    systems: SystemMap,
+    /// A waypoint selling fuel in each system where fuel is sold.
+    fuel_stations: BTreeMap<String, String>,
+}
+
+impl WarpMap {
+    /// Create an empty map.
+    pub fn new() -> WarpMap {
+        WarpMap::default()
+    }
+
+    /// Create a map with the given systems. No system sells fuel until markets are added.
+    pub fn from_systems<'a>(systems: impl IntoIterator<Item = &'a System>) -> WarpMap {
+        let mut map = WarpMap::new();
+        for system in systems {
+            map.insert(system);
+        }
+        map
+    }
+
+    /// Add a system to the map.
+    pub fn insert(&mut self, system: &System) {
+        self.systems.insert(&system.symbol, system);
+    }
+
+    /// Record that the system of the market sells fuel, if the market does.
+    pub fn add_market(&mut self, market: &Market) {
+        if market.sells(TradeSymbol::Fuel) {
+            let system = system_symbol(&market.symbol);
+            self.systems.set_sells_fuel(system, true);
+            self.fuel_stations.entry(system.to_string()).or_insert_with(|| market.symbol.clone());
+        }
+    }
+
+    /// A waypoint of the system where fuel is sold, if known.
+    pub fn fuel_station(&self, system: &str) -> Option<&str> {
+        self.fuel_stations.get(system).map(String::as_str)
+    }
+
+    /// Systems within a single warp of the given system, with their distance, closest first.
+    ///
+    /// A system is in range if it is within the warp drive range, and the ship can carry enough fuel to cruise there.
+    pub fn systems_in_range(&self, ship: &Ship, from_system: &str) -> Vec<(&str, f64)> {
+        let range = match ship.capabilities().warp_range {
+            Some(range) => range,
+            None => return Vec::new(),
+        };
+        self.systems_in_range_with(range, ship.fuel.capacity, from_system)
+    }
+
+    /// Systems within a single warp of the given system for a warp drive of the given range and fuel capacity.
+    pub fn systems_in_range_with(&self, range: u64, fuel_capacity: u64, from_system: &str) -> Vec<(&str, f64)> {
+        let from = match self.systems.get(from_system) {
+            Some(from) => from,
+            None => return Vec::new(),
+        };
+        let mut result = self.systems.iter()
+            .filter(|(symbol, _)| *symbol != from_system)
+            .map(|(symbol, system)| (symbol, from.distance_to(system)))
+            .filter(|(_, distance)| *distance <= range as f64)
+            .filter(|(_, distance)| fuel_capacity == 0 || fuel_cost(*distance, ShipNavFlightMode::Cruise) <= fuel_capacity)
+            .collect::<Vec<_>>();
+        result.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        result
+    }
+
+    /// Plan a warp route for the ship from its current system to the destination system.
+    pub fn plan_route(&self, ship: &Ship, destination_system: &str, options: &RouteOptions) -> Option<Route> {
+        let range = ship.capabilities().warp_range?;
+        self.plan_route_with(
+            ship.engine.speed.get(),
+            range,
+            ship.fuel.current,
+            ship.fuel.capacity,
+            &ship.nav.system_symbol,
+            destination_system,
+            options,
+        )
+    }
+
+    /// Plan a warp route between two systems for a ship with the given engine speed, warp range and fuel.
+    ///
+    /// Each hop is at most the warp range long, and the ship refuels at every intermediate system.
+    /// Returns `None` if a system is unknown, or if the destination can't be reached.
+    #[allow(clippy::too_many_arguments)]
+    pub fn plan_route_with(
+        &self,
+        engine_speed: u64,
+        range: u64,
+        current_fuel: u64,
+        fuel_capacity: u64,
+        origin_system: &str,
+        destination_system: &str,
+        options: &RouteOptions,
+    ) -> Option<Route> {
+        self.systems.plan(current_fuel, fuel_capacity, origin_system, destination_system, options, |from, to, mode| {
+            let leg = plan_warp_leg_with_speed(engine_speed, from, to, mode);
+            (leg.distance <= range as f64).then_some(leg)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance::fixture_data;
+
+    /// Systems on a line, 1000 units apart.
+    fn map() -> WarpMap {
+        let mut map = WarpMap::new();
+        for (index, symbol) in ["X1-A", "X1-B", "X1-C", "X1-D"].into_iter().enumerate() {
+            map.systems.insert(symbol, &(index as i64 * 1000, 0));
+        }
+        map.systems.set_sells_fuel("X1-B", true);
+        map.systems.set_sells_fuel("X1-C", true);
+        map
+    }
+
+    fn cruise() -> RouteOptions {
+        RouteOptions { flight_modes: vec![ShipNavFlightMode::Cruise], ..Default::default() }
+    }
+
+    #[test]
+    fn in_range() {
+        let map = map();
+        let systems = map.systems_in_range_with(2000, 1500, "X1-A");
+        assert_eq!(systems.iter().map(|(symbol, _)| *symbol).collect::<Vec<_>>(), ["X1-B"]);
+        let systems = map.systems_in_range_with(2000, 2000, "X1-A");
+        assert_eq!(systems.iter().map(|(symbol, _)| *symbol).collect::<Vec<_>>(), ["X1-B", "X1-C"]);
+        assert!(map.systems_in_range_with(500, 2000, "X1-A").is_empty());
+    }
+
+    #[test]
+    fn multi_hop() {
+        let map = map();
+        let route = map.plan_route_with(30, 1500, 1200, 1200, "X1-A", "X1-D", &cruise()).unwrap();
+        let systems = route.hops.iter().map(|hop| hop.destination.as_str()).collect::<Vec<_>>();
+        assert_eq!(systems, ["X1-B", "X1-C", "X1-D"]);
+        assert_eq!(route.refuel_stops().collect::<Vec<_>>(), ["X1-B", "X1-C"]);
+        // out of range of the warp drive, even with enough fuel
+        assert!(map.plan_route_with(30, 900, 5000, 5000, "X1-A", "X1-D", &cruise()).is_none());
+    }
+
+    #[test]
+    fn fuel_station() {
+        let mut map = WarpMap::new();
+        map.systems.insert("X1-MH34", &(0, 0));
+        assert_eq!(map.fuel_station("X1-MH34"), None);
+        map.add_market(&fixture_data("get-market"));
+        assert_eq!(map.fuel_station("X1-MH34"), Some("X1-MH34-A1"));
+        assert!(map.systems.get("X1-MH34").unwrap().sells_fuel);
+    }
+
+    #[test]
+    fn time() {
+        assert_eq!(warp_time(1000., 30, ShipNavFlightMode::Cruise), Duration::from_secs(1682));
+        assert_eq!(warp_time(1000., 30, ShipNavFlightMode::Burn), Duration::from_secs(848));
+    }
+}
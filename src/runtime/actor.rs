@@ -0,0 +1,167 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, atomic::{AtomicUsize, Ordering}},
+};
+
+use tokio::sync::{Mutex, mpsc, oneshot, watch};
+use crate::{
+    api::fleet::travel::TravelOptions,
+    error::Error,
+    schemas::ship::Ship,
+};
+use super::context::ShipContext;
+
+/// Future of a task run by a ship actor, borrowing the context of the ship.
+pub type ShipTaskFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+type Task = Box<dyn for<'a> FnOnce(&'a mut ShipContext) -> ShipTaskFuture<'a, ()> + Send>;
+
+/// A message in the mailbox of a ship actor.
+pub(crate) enum ShipMessage {
+    /// Run a task with the context of the ship.
+    Run(Task),
+    /// Stop the actor once the previous messages are handled.
+    Stop,
+}
+
+/// What the supervisor keeps of an actor to restart it: its mailbox and last known state.
+pub(crate) struct ActorSlot {
+    pub(crate) mailbox: Arc<Mutex<mpsc::Receiver<ShipMessage>>>,
+    pub(crate) state: Arc<watch::Sender<Ship>>,
+    pub(crate) restarts: Arc<AtomicUsize>,
+}
+
+/// Handle to send work to the actor of a ship. Handles can be cloned and shared between tasks.
+///
+/// Tasks sent to the same ship run one after the other, in the order they were sent.
+#[derive(Clone)]
+pub struct ShipHandle {
+    symbol: String,
+    sender: mpsc::Sender<ShipMessage>,
+    state: watch::Receiver<Ship>,
+    restarts: Arc<AtomicUsize>,
+}
+
+/// Create the handle and the slot of a new actor.
+pub(crate) fn channel(ship: Ship, mailbox_size: usize) -> (ShipHandle, ActorSlot) {
+    let (sender, receiver) = mpsc::channel(mailbox_size.max(1));
+    let symbol = ship.symbol.clone();
+    let (state_sender, state) = watch::channel(ship);
+    let restarts = Arc::new(AtomicUsize::new(0));
+    let handle = ShipHandle { symbol, sender, state, restarts: restarts.clone() };
+    let slot = ActorSlot {
+        mailbox: Arc::new(Mutex::new(receiver)),
+        state: Arc::new(state_sender),
+        restarts,
+    };
+    (handle, slot)
+}
+
+/// Handle the messages of the mailbox until the actor is stopped, publishing the state of the ship after each task.
+pub(crate) async fn run_actor(mut context: ShipContext, mailbox: Arc<Mutex<mpsc::Receiver<ShipMessage>>>, state: Arc<watch::Sender<Ship>>) {
+    let mut mailbox = mailbox.lock().await;
+    while let Some(message) = mailbox.recv().await {
+        match message {
+            ShipMessage::Run(task) => {
+                task(&mut context).await;
+                state.send_replace(context.ship.clone());
+            },
+            ShipMessage::Stop => break,
+        }
+    }
+}
+
+impl ShipHandle {
+    /// The symbol of the ship.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// The state of the ship after the last task.
+    pub fn ship(&self) -> Ship {
+        self.state.borrow().clone()
+    }
+
+    /// Number of times the actor was restarted after a task panicked.
+    pub fn restarts(&self) -> usize {
+        self.restarts.load(Ordering::SeqCst)
+    }
+
+    fn stopped(&self) -> Error {
+        Error::ShipActorStopped { ship_symbol: self.symbol.clone() }
+    }
+
+    /// Run a task with the context of the ship, once the previous tasks are done, and return its result.
+    ///
+    /// ```ignore
+    /// let cargo = handle.run(|context| Box::pin(async move { context.ship.cargo.units })).await?;
+    /// ```
+    pub async fn run<T, F>(&self, task: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut ShipContext) -> ShipTaskFuture<'a, T> + Send + 'static,
+    {
+        let (reply, answer) = oneshot::channel();
+        let task: Task = Box::new(move |context| Box::pin(async move {
+            let _ = reply.send(task(context).await);
+        }));
+        self.sender.send(ShipMessage::Run(task)).await.map_err(|_| self.stopped())?;
+        answer.await.map_err(|_| self.stopped())
+    }
+
+    /// Stop the actor once the tasks already sent are done.
+    pub async fn stop(&self) {
+        let _ = self.sender.send(ShipMessage::Stop).await;
+    }
+
+    /// Fetch the ship again.
+    pub async fn refresh(&self) -> Result<Ship, Error> {
+        self.run(|context| Box::pin(async move {
+            context.refresh().await?;
+            Ok(context.ship.clone())
+        })).await?
+    }
+
+    /// Navigate to a waypoint of the system, and return the ship once arrived.
+    pub async fn navigate(&self, destination: &str) -> Result<Ship, Error> {
+        let destination = destination.to_string();
+        self.run(move |context| Box::pin(async move {
+            context.navigate(&destination).await?;
+            Ok(context.ship.clone())
+        })).await?
+    }
+
+    /// Travel to any waypoint, and return the ship once arrived.
+    pub async fn travel_to(&self, destination: &str, options: TravelOptions) -> Result<Ship, Error> {
+        let destination = destination.to_string();
+        self.run(move |context| Box::pin(async move {
+            context.travel_to(&destination, &options).await?;
+            Ok(context.ship.clone())
+        })).await?
+    }
+
+    /// Dock the ship.
+    pub async fn dock(&self) -> Result<Ship, Error> {
+        self.run(|context| Box::pin(async move {
+            context.dock().await?;
+            Ok(context.ship.clone())
+        })).await?
+    }
+
+    /// Put the ship in orbit.
+    pub async fn orbit(&self) -> Result<Ship, Error> {
+        self.run(|context| Box::pin(async move {
+            context.orbit().await?;
+            Ok(context.ship.clone())
+        })).await?
+    }
+
+    /// Dock and refuel the ship.
+    pub async fn refuel(&self) -> Result<Ship, Error> {
+        self.run(|context| Box::pin(async move {
+            context.refuel().await?;
+            Ok(context.ship.clone())
+        })).await?
+    }
+}
@@ -0,0 +1,512 @@
+//* Behaviors for the usual jobs of a ship. Each one does a single cycle of its job, loop them with [`super::combinator::Repeat`].
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::Utc;
+use crate::{
+    api::fleet::{trade::{LiquidationOptions, TradeOrder}, travel::TravelOptions},
+    error::{Error, code::ErrorCode},
+    runtime::{actor::ShipTaskFuture, context::ShipContext},
+    schemas::{ship::Ship, trade_symbol::TradeSymbol},
+    trading::history::MarketHistory,
+};
+use super::{ShipBehavior, Step};
+
+/// Travel to the destination, unless the ship is already there.
+async fn go_to(context: &mut ShipContext, destination: &str, options: &TravelOptions) -> Result<(), Error> {
+    if context.ship.nav.waypoint_symbol != destination {
+        context.travel_to(destination, options).await?;
+    }
+    Ok(())
+}
+
+/// How resources are extracted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extraction {
+    /// Mine ores and minerals, at an asteroid.
+    Mine,
+    /// Siphon gas, at a gas giant.
+    Siphon,
+}
+
+/// Extract resources until the cargo is full, then sell it at a market.
+pub struct ExtractAndSell {
+    /// Where resources are extracted.
+    pub source: String,
+    /// Where the cargo is sold.
+    pub market: String,
+    pub extraction: Extraction,
+    /// Throw away the goods the market doesn't trade, so the cargo has space for the next cycle.
+    pub jettison_unsold: bool,
+    pub travel: TravelOptions,
+}
+
+impl ExtractAndSell {
+    /// Mine at an asteroid, and sell at a market.
+    pub fn mine(asteroid: &str, market: &str) -> ExtractAndSell {
+        ExtractAndSell {
+            source: asteroid.to_string(),
+            market: market.to_string(),
+            extraction: Extraction::Mine,
+            jettison_unsold: true,
+            travel: TravelOptions::default(),
+        }
+    }
+
+    /// Siphon at a gas giant, and sell at a market.
+    pub fn siphon(gas_giant: &str, market: &str) -> ExtractAndSell {
+        ExtractAndSell { extraction: Extraction::Siphon, ..ExtractAndSell::mine(gas_giant, market) }
+    }
+}
+
+/// Next action of an [`ExtractAndSell`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractAction {
+    GoToSource,
+    Extract,
+    GoToMarket,
+    Sell,
+}
+
+impl ExtractAndSell {
+    /// What to do next with the ship: extract until the cargo is full, then sell it.
+    pub fn next_action(&self, ship: &Ship) -> ExtractAction {
+        let waypoint = &ship.nav.waypoint_symbol;
+        match ship.cargo.is_full() {
+            false if *waypoint != self.source => ExtractAction::GoToSource,
+            false => ExtractAction::Extract,
+            true if *waypoint != self.market => ExtractAction::GoToMarket,
+            true => ExtractAction::Sell,
+        }
+    }
+}
+
+impl ShipBehavior for ExtractAndSell {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        Box::pin(async move {
+            match self.next_action(&context.ship) {
+                ExtractAction::GoToSource => go_to(context, &self.source, &self.travel).await?,
+                ExtractAction::Extract => match self.extraction {
+                    Extraction::Mine => context.extract(None).await.map(|_| ())?,
+                    Extraction::Siphon => context.siphon().await.map(|_| ())?,
+                },
+                ExtractAction::GoToMarket => go_to(context, &self.market, &self.travel).await?,
+                ExtractAction::Sell => {
+                    let options = LiquidationOptions { jettison_unsellable: self.jettison_unsold, ..Default::default() };
+                    context.liquidate(&options).await?;
+                    // the next cycle would come back with the same cargo, and never sell it
+                    if context.ship.cargo.is_full() {
+                        return Err(Error::CargoNotSold { ship_symbol: context.ship.symbol.clone(), market: self.market.clone() });
+                    }
+                    return Ok(Step::Done);
+                },
+            }
+            Ok(Step::Continue)
+        })
+    }
+}
+
+/// Buy a good at a market, and sell it at another.
+pub struct HaulBetweenMarkets {
+    pub good: TradeSymbol,
+    /// Where the good is bought.
+    pub from: String,
+    /// Where the good is sold.
+    pub to: String,
+    /// Maximum number of units bought, the free space of the cargo if `None`.
+    pub units: Option<u64>,
+    pub travel: TravelOptions,
+    bought: bool,
+}
+
+impl HaulBetweenMarkets {
+    /// Fill the cargo with the good at `from`, and sell it at `to`.
+    pub fn new(good: TradeSymbol, from: &str, to: &str) -> HaulBetweenMarkets {
+        HaulBetweenMarkets {
+            good,
+            from: from.to_string(),
+            to: to.to_string(),
+            units: None,
+            travel: TravelOptions::default(),
+            bought: false,
+        }
+    }
+}
+
+/// Next action of a [`HaulBetweenMarkets`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HaulAction {
+    GoToSource,
+    Buy,
+    GoToDestination,
+    Sell,
+}
+
+impl HaulBetweenMarkets {
+    /// What to do next with the ship: buy the good, then sell it.
+    pub fn next_action(&self, ship: &Ship) -> HaulAction {
+        let waypoint = &ship.nav.waypoint_symbol;
+        match self.bought {
+            false if *waypoint != self.from => HaulAction::GoToSource,
+            false => HaulAction::Buy,
+            true if *waypoint != self.to => HaulAction::GoToDestination,
+            true => HaulAction::Sell,
+        }
+    }
+}
+
+impl ShipBehavior for HaulBetweenMarkets {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        Box::pin(async move {
+            match self.next_action(&context.ship) {
+                HaulAction::GoToSource => go_to(context, &self.from, &self.travel).await?,
+                HaulAction::Buy => {
+                    let bought = context.buy_order(&TradeOrder::new(self.good, self.units.unwrap_or(u64::MAX))).await?.filled();
+                    if bought == 0 && !context.ship.cargo.contains(self.good) {
+                        return Ok(Step::Done);
+                    }
+                    self.bought = true;
+                },
+                HaulAction::GoToDestination => go_to(context, &self.to, &self.travel).await?,
+                HaulAction::Sell => {
+                    context.sell_order(&TradeOrder::new(self.good, u64::MAX)).await?;
+                    self.bought = false;
+                    return Ok(Step::Done);
+                },
+            }
+            Ok(Step::Continue)
+        })
+    }
+
+    fn reset(&mut self) {
+        self.bought = false;
+    }
+}
+
+/// Deliver goods for a contract, buying them at a market if needed.
+pub struct ContractDeliver {
+    pub contract_id: String,
+    pub good: TradeSymbol,
+    /// Number of units to deliver.
+    pub units: u64,
+    /// Where the good is bought. Without a source, only the units already in the cargo are delivered.
+    pub source: Option<String>,
+    /// Where the good is delivered.
+    pub destination: String,
+    pub travel: TravelOptions,
+    delivered: u64,
+    /// Whether a purchase at the source filled nothing, so only the units held are delivered.
+    sold_out: bool,
+}
+
+impl ContractDeliver {
+    /// Deliver units of a good to the destination of the contract.
+    pub fn new(contract_id: &str, good: TradeSymbol, units: u64, source: Option<&str>, destination: &str) -> ContractDeliver {
+        ContractDeliver {
+            contract_id: contract_id.to_string(),
+            good,
+            units,
+            source: source.map(str::to_string),
+            destination: destination.to_string(),
+            travel: TravelOptions::default(),
+            delivered: 0,
+            sold_out: false,
+        }
+    }
+
+    /// Number of units delivered so far.
+    pub fn delivered(&self) -> u64 {
+        self.delivered
+    }
+}
+
+/// Next action of a [`ContractDeliver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryAction {
+    GoToSource,
+    /// Buy the units missing from the cargo.
+    Buy(u64),
+    GoToDestination,
+    /// Deliver units of the cargo.
+    Deliver(u64),
+    /// Every unit is delivered, or there is nothing left to deliver.
+    Done,
+}
+
+impl ContractDeliver {
+    /// What to do next with the ship: buy the missing units if there is space for them,
+    /// or deliver the units held once there is nothing more to buy.
+    pub fn next_action(&self, ship: &Ship) -> DeliveryAction {
+        let remaining = self.units.saturating_sub(self.delivered);
+        if remaining == 0 {
+            return DeliveryAction::Done;
+        }
+        let held = ship.cargo.quantity_of(self.good);
+        let at_source = self.source.as_deref() == Some(ship.nav.waypoint_symbol.as_str());
+        let buying = self.source.is_some() && !self.sold_out && held < remaining && ship.cargo.free_space() > 0;
+        // units already held are delivered first, unless the ship is at the source to top them up
+        if buying && (held == 0 || at_source) {
+            return match at_source {
+                true => DeliveryAction::Buy(remaining - held),
+                false => DeliveryAction::GoToSource,
+            };
+        }
+        match held {
+            0 => DeliveryAction::Done,
+            _ if ship.nav.waypoint_symbol != self.destination => DeliveryAction::GoToDestination,
+            _ => DeliveryAction::Deliver(held.min(remaining)),
+        }
+    }
+}
+
+impl ShipBehavior for ContractDeliver {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        Box::pin(async move {
+            match self.next_action(&context.ship) {
+                DeliveryAction::GoToSource => if let Some(source) = &self.source {
+                    go_to(context, source, &self.travel).await?;
+                },
+                DeliveryAction::Buy(units) => {
+                    if context.buy_order(&TradeOrder::new(self.good, units)).await?.filled() == 0 {
+                        self.sold_out = true;
+                    }
+                },
+                DeliveryAction::GoToDestination => go_to(context, &self.destination, &self.travel).await?,
+                DeliveryAction::Deliver(units) => {
+                    context.deliver(&self.contract_id, self.good, units).await?;
+                    self.delivered += units;
+                    self.sold_out = false;
+                },
+                DeliveryAction::Done => return Ok(Step::Done),
+            }
+            Ok(Step::Continue)
+        })
+    }
+
+    fn reset(&mut self) {
+        self.delivered = 0;
+        self.sold_out = false;
+    }
+}
+
+/// Visit waypoints one after the other, and chart the ones that are not charted yet.
+pub struct ExploreAndChart {
+    pub waypoints: Vec<String>,
+    pub travel: TravelOptions,
+    next: usize,
+}
+
+impl ExploreAndChart {
+    /// Visit the waypoints in the given order.
+    pub fn new(waypoints: impl IntoIterator<Item = String>) -> ExploreAndChart {
+        ExploreAndChart {
+            waypoints: waypoints.into_iter().collect(),
+            travel: TravelOptions::default(),
+            next: 0,
+        }
+    }
+
+    /// The waypoints left to visit.
+    pub fn remaining(&self) -> &[String] {
+        self.waypoints.get(self.next..).unwrap_or_default()
+    }
+}
+
+/// Next action of an [`ExploreAndChart`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExploreAction {
+    GoTo(String),
+    Chart,
+    Done,
+}
+
+impl ExploreAndChart {
+    /// What to do next with the ship: go to the next waypoint, and chart it.
+    pub fn next_action(&self, ship: &Ship) -> ExploreAction {
+        match self.waypoints.get(self.next) {
+            None => ExploreAction::Done,
+            Some(waypoint) if ship.nav.waypoint_symbol != *waypoint => ExploreAction::GoTo(waypoint.clone()),
+            Some(_) => ExploreAction::Chart,
+        }
+    }
+}
+
+impl ShipBehavior for ExploreAndChart {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        Box::pin(async move {
+            match self.next_action(&context.ship) {
+                ExploreAction::GoTo(waypoint) => go_to(context, &waypoint, &self.travel).await?,
+                ExploreAction::Chart => match context.chart().await {
+                    Err(error) if error.code() != Some(ErrorCode::WaypointChartedError) => return Err(error),
+                    _ => self.next += 1,
+                },
+                ExploreAction::Done => return Ok(Step::Done),
+            }
+            Ok(Step::Continue)
+        })
+    }
+
+    fn reset(&mut self) {
+        self.next = 0;
+    }
+}
+
+/// Stay at a market, and record its prices and transactions at regular intervals. Never done.
+pub struct IdleProbeAtMarket {
+    pub market: String,
+    /// Time between two observations of the market.
+    pub interval: Duration,
+    pub history: Arc<Mutex<MarketHistory>>,
+    pub travel: TravelOptions,
+}
+
+impl IdleProbeAtMarket {
+    /// Record the market in the shared history every `interval`.
+    pub fn new(market: &str, interval: Duration, history: Arc<Mutex<MarketHistory>>) -> IdleProbeAtMarket {
+        IdleProbeAtMarket {
+            market: market.to_string(),
+            interval,
+            history,
+            travel: TravelOptions::default(),
+        }
+    }
+}
+
+/// Next action of an [`IdleProbeAtMarket`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeAction {
+    GoToMarket,
+    /// Record the market, then wait for the interval.
+    Observe,
+}
+
+impl IdleProbeAtMarket {
+    /// What to do next with the ship: go to the market, and observe it.
+    pub fn next_action(&self, ship: &Ship) -> ProbeAction {
+        match ship.nav.waypoint_symbol == self.market {
+            true => ProbeAction::Observe,
+            false => ProbeAction::GoToMarket,
+        }
+    }
+}
+
+impl ShipBehavior for IdleProbeAtMarket {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        Box::pin(async move {
+            match self.next_action(&context.ship) {
+                ProbeAction::GoToMarket => {
+                    go_to(context, &self.market, &self.travel).await?;
+                    Ok(Step::Continue)
+                },
+                ProbeAction::Observe => {
+                    let market = context.market().await?;
+                    self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).record(&market, Utc::now());
+                    Ok(Step::Sleep(self.interval))
+                },
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::tests::ship;
+
+    /// The ship of the fixture, at a waypoint, with 12 units of cargo out of 40.
+    fn ship_at(waypoint: &str) -> Ship {
+        let mut ship = ship();
+        ship.nav.waypoint_symbol = waypoint.to_string();
+        ship
+    }
+
+    fn fill(ship: &mut Ship, symbol: TradeSymbol) {
+        let free_space = ship.cargo.free_space();
+        ship.cargo.add(symbol, free_space).unwrap();
+    }
+
+    #[test]
+    fn extract_and_sell() {
+        let behavior = ExtractAndSell::mine("X1-A-B1", "X1-A-M1");
+        assert_eq!(behavior.next_action(&ship_at("X1-A-M1")), ExtractAction::GoToSource);
+        let mut ship = ship_at("X1-A-B1");
+        assert_eq!(behavior.next_action(&ship), ExtractAction::Extract);
+        fill(&mut ship, TradeSymbol::IronOre);
+        assert_eq!(behavior.next_action(&ship), ExtractAction::GoToMarket);
+        ship.nav.waypoint_symbol = "X1-A-M1".to_string();
+        assert_eq!(behavior.next_action(&ship), ExtractAction::Sell);
+    }
+
+    #[test]
+    fn haul_between_markets() {
+        let mut behavior = HaulBetweenMarkets::new(TradeSymbol::Fuel, "X1-A-M1", "X1-A-M2");
+        assert_eq!(behavior.next_action(&ship_at("X1-A-M2")), HaulAction::GoToSource);
+        assert_eq!(behavior.next_action(&ship_at("X1-A-M1")), HaulAction::Buy);
+        behavior.bought = true;
+        assert_eq!(behavior.next_action(&ship_at("X1-A-M1")), HaulAction::GoToDestination);
+        assert_eq!(behavior.next_action(&ship_at("X1-A-M2")), HaulAction::Sell);
+        behavior.reset();
+        assert_eq!(behavior.next_action(&ship_at("X1-A-M2")), HaulAction::GoToSource);
+    }
+
+    #[test]
+    fn contract_deliver() {
+        let mut behavior = ContractDeliver::new("C", TradeSymbol::IronOre, 50, Some("X1-A-M1"), "X1-A-D1");
+        assert_eq!(behavior.next_action(&ship_at("X1-A-D1")), DeliveryAction::GoToSource);
+        // 28 units of free space
+        let mut ship = ship_at("X1-A-M1");
+        assert_eq!(behavior.next_action(&ship), DeliveryAction::Buy(50));
+        ship.cargo.add(TradeSymbol::IronOre, 20).unwrap();
+        assert_eq!(behavior.next_action(&ship), DeliveryAction::Buy(30));
+        fill(&mut ship, TradeSymbol::IronOre);
+        assert_eq!(behavior.next_action(&ship), DeliveryAction::GoToDestination);
+        ship.nav.waypoint_symbol = "X1-A-D1".to_string();
+        assert_eq!(behavior.next_action(&ship), DeliveryAction::Deliver(28));
+
+        // the units held are delivered before going back to the source
+        behavior.delivered = 28;
+        ship.cargo.remove(TradeSymbol::IronOre, 20).unwrap();
+        assert_eq!(behavior.next_action(&ship), DeliveryAction::Deliver(8));
+        ship.cargo.remove(TradeSymbol::IronOre, 8).unwrap();
+        assert_eq!(behavior.next_action(&ship), DeliveryAction::GoToSource);
+
+        // nothing more to buy at the source
+        ship.nav.waypoint_symbol = "X1-A-M1".to_string();
+        ship.cargo.add(TradeSymbol::IronOre, 5).unwrap();
+        behavior.sold_out = true;
+        assert_eq!(behavior.next_action(&ship), DeliveryAction::GoToDestination);
+        ship.cargo.remove(TradeSymbol::IronOre, 5).unwrap();
+        assert_eq!(behavior.next_action(&ship), DeliveryAction::Done);
+
+        behavior.delivered = 50;
+        behavior.sold_out = false;
+        assert_eq!(behavior.next_action(&ship), DeliveryAction::Done);
+
+        // without a source, only the units held are delivered
+        let behavior = ContractDeliver::new("C", TradeSymbol::AluminumOre, 50, None, "X1-A-D1");
+        assert_eq!(behavior.next_action(&ship_at("X1-A-M1")), DeliveryAction::GoToDestination);
+        assert_eq!(behavior.next_action(&ship_at("X1-A-D1")), DeliveryAction::Deliver(8));
+    }
+
+    #[test]
+    fn explore_and_chart() {
+        let mut behavior = ExploreAndChart::new(["X1-A-B1".to_string(), "X1-A-B2".to_string()]);
+        assert_eq!(behavior.next_action(&ship_at("X1-A-B2")), ExploreAction::GoTo("X1-A-B1".to_string()));
+        assert_eq!(behavior.next_action(&ship_at("X1-A-B1")), ExploreAction::Chart);
+        behavior.next = 2;
+        assert!(behavior.remaining().is_empty());
+        assert_eq!(behavior.next_action(&ship_at("X1-A-B2")), ExploreAction::Done);
+        behavior.reset();
+        assert_eq!(behavior.remaining().len(), 2);
+    }
+
+    #[test]
+    fn idle_probe_at_market() {
+        let behavior = IdleProbeAtMarket::new("X1-A-M1", Duration::from_secs(60), Arc::default());
+        assert_eq!(behavior.next_action(&ship_at("X1-A-B1")), ProbeAction::GoToMarket);
+        assert_eq!(behavior.next_action(&ship_at("X1-A-M1")), ProbeAction::Observe);
+    }
+}
@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use crate::{error::Error, runtime::{actor::ShipTaskFuture, context::ShipContext}};
+use super::{ShipBehavior, Step};
+
+/// Run behaviors one after the other, each until it is done.
+#[derive(Default)]
+pub struct Sequence {
+    behaviors: Vec<Box<dyn ShipBehavior>>,
+    current: usize,
+}
+
+impl Sequence {
+    /// A sequence without behaviors, done right away.
+    pub fn new() -> Sequence {
+        Sequence::default()
+    }
+
+    /// Run a behavior after the ones already in the sequence.
+    pub fn then<B: ShipBehavior + 'static>(mut self, behavior: B) -> Sequence {
+        self.behaviors.push(Box::new(behavior));
+        self
+    }
+}
+
+impl From<Vec<Box<dyn ShipBehavior>>> for Sequence {
+    fn from(behaviors: Vec<Box<dyn ShipBehavior>>) -> Self {
+        Sequence { behaviors, current: 0 }
+    }
+}
+
+impl ShipBehavior for Sequence {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        Box::pin(async move {
+            while let Some(behavior) = self.behaviors.get_mut(self.current) {
+                match behavior.step(context).await? {
+                    Step::Done => self.current += 1,
+                    step => return Ok(step),
+                }
+            }
+            Ok(Step::Done)
+        })
+    }
+
+    fn reset(&mut self) {
+        self.current = 0;
+        self.behaviors.iter_mut().for_each(|behavior| behavior.reset());
+    }
+}
+
+/// Delay before a repeated behavior is started over, when it was done without taking any step.
+pub const DEFAULT_IDLE_DELAY: Duration = Duration::from_secs(1);
+
+/// Run a behavior again each time it is done, forever or a number of times.
+///
+/// When the behavior is done right away, without taking any step, it is started over after a delay
+/// rather than immediately, so a behavior that has nothing to do doesn't keep the task busy.
+pub struct Repeat<B> {
+    behavior: B,
+    times: Option<usize>,
+    done: usize,
+    idle_delay: Duration,
+    /// Whether the behavior took a step since it was last started over.
+    stepped: bool,
+}
+
+impl<B: ShipBehavior> Repeat<B> {
+    /// Run the behavior again each time it is done, until it fails.
+    pub fn forever(behavior: B) -> Repeat<B> {
+        Repeat { behavior, times: None, done: 0, idle_delay: DEFAULT_IDLE_DELAY, stepped: false }
+    }
+
+    /// Run the behavior the given number of times.
+    pub fn times(behavior: B, times: usize) -> Repeat<B> {
+        Repeat { times: Some(times), ..Repeat::forever(behavior) }
+    }
+
+    /// Wait for the delay before starting the behavior over when it was done without taking any step.
+    pub fn with_idle_delay(mut self, delay: Duration) -> Repeat<B> {
+        self.idle_delay = delay;
+        self
+    }
+
+    /// Number of times the behavior was done.
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    fn is_over(&self) -> bool {
+        self.times.is_some_and(|times| self.done >= times)
+    }
+}
+
+impl<B: ShipBehavior> ShipBehavior for Repeat<B> {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        Box::pin(async move {
+            if self.is_over() {
+                return Ok(Step::Done);
+            }
+            match self.behavior.step(context).await? {
+                Step::Done => {
+                    self.done += 1;
+                    self.behavior.reset();
+                    let stepped = std::mem::take(&mut self.stepped);
+                    match self.is_over() {
+                        true => Ok(Step::Done),
+                        false if stepped => Ok(Step::Continue),
+                        false => Ok(Step::Sleep(self.idle_delay)),
+                    }
+                },
+                step => {
+                    self.stepped = true;
+                    Ok(step)
+                },
+            }
+        })
+    }
+
+    fn reset(&mut self) {
+        self.done = 0;
+        self.stepped = false;
+        self.behavior.reset();
+    }
+}
+
+/// Run a behavior, and switch to another one if it fails.
+///
+/// The fallback is started over when switching to it, and its errors are returned as is.
+pub struct Fallback<P, F> {
+    primary: P,
+    fallback: F,
+    error: Option<Error>,
+}
+
+impl<P: ShipBehavior, F: ShipBehavior> Fallback<P, F> {
+    /// Run `primary`, or `fallback` once `primary` failed.
+    pub fn new(primary: P, fallback: F) -> Fallback<P, F> {
+        Fallback { primary, fallback, error: None }
+    }
+
+    /// The error of the primary behavior, once it failed.
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+}
+
+impl<P: ShipBehavior, F: ShipBehavior> ShipBehavior for Fallback<P, F> {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        Box::pin(async move {
+            if self.error.is_some() {
+                return self.fallback.step(context).await;
+            }
+            match self.primary.step(context).await {
+                Err(error) => {
+                    self.error = Some(error);
+                    self.fallback.reset();
+                    Ok(Step::Continue)
+                },
+                step => step,
+            }
+        })
+    }
+
+    fn reset(&mut self) {
+        self.error = None;
+        self.primary.reset();
+        self.fallback.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::runtime::{behavior::{run_behavior, tests::Counter}, tests::context};
+
+    #[tokio::test]
+    async fn sequence() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut sequence = Sequence::new().then(Counter::new("a", 2, &log)).then(Counter::new("b", 0, &log)).then(Counter::new("c", 1, &log));
+        run_behavior(&mut sequence, &mut context()).await.unwrap();
+        assert_eq!(*log.lock().unwrap(), ["a", "a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn repeat() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut repeat = Repeat::times(Counter::new("a", 2, &log), 3);
+        run_behavior(&mut repeat, &mut context()).await.unwrap();
+        assert_eq!(repeat.done(), 3);
+        assert_eq!(log.lock().unwrap().len(), 6);
+
+        let mut forever = Repeat::forever(Counter::failing("b", 1, &log));
+        assert!(run_behavior(&mut forever, &mut context()).await.is_err());
+        assert_eq!(forever.done(), 0);
+    }
+
+    #[tokio::test]
+    async fn repeat_idle() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut context = context();
+        let delay = Duration::from_millis(10);
+        let mut idle = Repeat::forever(Counter::new("a", 0, &log)).with_idle_delay(delay);
+        assert_eq!(idle.step(&mut context).await.unwrap(), Step::Sleep(delay));
+        assert_eq!(idle.step(&mut context).await.unwrap(), Step::Sleep(delay));
+        assert_eq!(idle.done(), 2);
+
+        // started over right away after a cycle with steps
+        let mut busy = Repeat::forever(Counter::new("b", 1, &log)).with_idle_delay(delay);
+        assert_eq!(busy.step(&mut context).await.unwrap(), Step::Continue);
+        assert_eq!(busy.step(&mut context).await.unwrap(), Step::Continue);
+        assert_eq!(busy.done(), 1);
+    }
+
+    #[tokio::test]
+    async fn fallback() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut fallback = Fallback::new(Counter::failing("a", 1, &log), Counter::new("b", 2, &log));
+        run_behavior(&mut fallback, &mut context()).await.unwrap();
+        assert!(fallback.error().is_some());
+        assert_eq!(*log.lock().unwrap(), ["a", "b", "b"]);
+
+        // the loop starts over from the primary behavior
+        log.lock().unwrap().clear();
+        let mut looped = Repeat::times(Fallback::new(Counter::failing("a", 1, &log), Counter::new("b", 1, &log)), 2);
+        run_behavior(&mut looped, &mut context()).await.unwrap();
+        assert_eq!(*log.lock().unwrap(), ["a", "b", "a", "b"]);
+    }
+}
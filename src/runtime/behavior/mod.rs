@@ -0,0 +1,188 @@
+pub mod builtin;
+pub mod combinator;
+
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use crate::error::Error;
+use super::{
+    actor::{ShipHandle, ShipTaskFuture},
+    context::ShipContext,
+};
+
+/// What a behavior wants to do after a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Take the next step right away.
+    Continue,
+    /// Take the next step after the given duration, without holding the ship.
+    Sleep(Duration),
+    /// The behavior is over.
+    Done,
+}
+
+/// A strategy driving a ship one step at a time.
+///
+/// Each step should do a single action, such as navigating, extracting or selling, so that other tasks
+/// sent to the ship can run between steps.
+pub trait ShipBehavior: Send {
+    /// Take the next step with the ship.
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>>;
+
+    /// Start over from the beginning, as when the behavior is looped.
+    fn reset(&mut self) {}
+}
+
+impl<B: ShipBehavior + ?Sized> ShipBehavior for Box<B> {
+    fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+        (**self).step(context)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+}
+
+/// Take steps with the context until the behavior is done, or fails.
+pub async fn run_behavior<B: ShipBehavior + ?Sized>(behavior: &mut B, context: &mut ShipContext) -> Result<(), Error> {
+    loop {
+        match behavior.step(context).await? {
+            Step::Continue => {},
+            Step::Sleep(duration) => tokio::time::sleep(duration).await,
+            Step::Done => return Ok(()),
+        }
+    }
+}
+
+/// Handle to a behavior assigned to a ship.
+#[derive(Debug)]
+pub struct BehaviorHandle {
+    task: JoinHandle<Result<(), Error>>,
+}
+
+impl BehaviorHandle {
+    /// Stop the behavior. The step being taken, if any, is still completed by the ship.
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+
+    /// Whether the behavior is done, failed, or was aborted.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Wait for the behavior to be done, and return the error that stopped it, if any.
+    pub async fn join(self) -> Result<(), Error> {
+        match self.task.await {
+            Ok(result) => result,
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            // aborted
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+impl ShipHandle {
+    /// Drive the ship with a behavior, until it is done or fails.
+    ///
+    /// Each step is sent to the actor of the ship as a task, so it is interleaved with the other tasks sent to the ship.
+    /// Sleeps happen outside of the actor.
+    pub fn assign<B: ShipBehavior + 'static>(&self, behavior: B) -> BehaviorHandle {
+        let handle = self.clone();
+        let task = tokio::spawn(async move {
+            let mut behavior: Box<dyn ShipBehavior> = Box::new(behavior);
+            loop {
+                let (returned, step) = handle.run(move |context| Box::pin(async move {
+                    let step = behavior.step(context).await;
+                    (behavior, step)
+                })).await?;
+                behavior = returned;
+                match step? {
+                    Step::Continue => {},
+                    Step::Sleep(duration) => tokio::time::sleep(duration).await,
+                    Step::Done => return Ok(()),
+                }
+            }
+        });
+        BehaviorHandle { task }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::runtime::{
+        supervisor::{Fleet, SupervisorOptions},
+        tests::{client, context, ship},
+    };
+
+    /// Adds one unit of fuel per step, records its name in a log, and fails or is done after a number of steps.
+    pub(crate) struct Counter {
+        pub(crate) name: &'static str,
+        pub(crate) steps: usize,
+        pub(crate) fail: bool,
+        pub(crate) taken: usize,
+        pub(crate) log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Counter {
+        pub(crate) fn new(name: &'static str, steps: usize, log: &Arc<Mutex<Vec<&'static str>>>) -> Counter {
+            Counter { name, steps, fail: false, taken: 0, log: log.clone() }
+        }
+
+        pub(crate) fn failing(name: &'static str, steps: usize, log: &Arc<Mutex<Vec<&'static str>>>) -> Counter {
+            Counter { fail: true, ..Counter::new(name, steps, log) }
+        }
+    }
+
+    impl ShipBehavior for Counter {
+        fn step<'a>(&'a mut self, context: &'a mut ShipContext) -> ShipTaskFuture<'a, Result<Step, Error>> {
+            Box::pin(async move {
+                if self.taken == self.steps {
+                    return match self.fail {
+                        true => Err(Error::NoRouteFound { origin: self.name.to_string(), destination: self.name.to_string() }),
+                        false => Ok(Step::Done),
+                    };
+                }
+                self.taken += 1;
+                context.ship.fuel.current += 1;
+                self.log.lock().unwrap().push(self.name);
+                Ok(Step::Continue)
+            })
+        }
+
+        fn reset(&mut self) {
+            self.taken = 0;
+        }
+    }
+
+    #[tokio::test]
+    async fn run() {
+        let log = Arc::default();
+        let mut context = context();
+        let initial = context.ship.fuel.current;
+        run_behavior(&mut Counter::new("a", 3, &log), &mut context).await.unwrap();
+        assert_eq!(context.ship.fuel.current, initial + 3);
+        let failed = run_behavior(&mut Counter::failing("b", 1, &log), &mut context).await;
+        assert!(matches!(failed, Err(Error::NoRouteFound { .. })));
+        assert_eq!(*log.lock().unwrap(), ["a", "a", "a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn assign() {
+        let options = SupervisorOptions { refresh_on_restart: false, ..Default::default() };
+        let mut fleet = Fleet::new(client(), options);
+        let handle = fleet.spawn(ship());
+        let log = Arc::default();
+        handle.assign(Counter::new("a", 5, &log)).join().await.unwrap();
+        assert_eq!(handle.ship().fuel.current, ship().fuel.current + 5);
+        // the previous behavior is aborted
+        fleet.assign(handle.symbol(), Counter::new("b", usize::MAX, &log)).unwrap();
+        fleet.assign(handle.symbol(), Counter::failing("c", 0, &log)).unwrap();
+        let failed = fleet.unassign(handle.symbol()).unwrap();
+        assert!(matches!(failed.join().await, Err(Error::NoRouteFound { .. })));
+        fleet.shutdown().await;
+    }
+}
@@ -0,0 +1,233 @@
+use std::{
+    num::{NonZeroU32, NonZeroU64},
+    sync::Arc,
+};
+
+use chrono::Utc;
+use crate::{
+    api::{
+        contract::ContractAndCargo,
+        fleet::{
+            cargo::CagoTransactionResult,
+            chart::ChartAndWaypoint,
+            refining::{FromRefinedTradeSymbol, RefiningResult},
+            refuel::RefuelShipResponse,
+            resources::{ExtractResourcesResponse, SiphonResourcesResponse},
+            survey::CooldownAndSurveys,
+            trade::{FillReport, LiquidationError, LiquidationOptions, LiquidationReport, TradeOrder},
+            travel::TravelOptions,
+        },
+    },
+    client::{Authenticated, rate_limit::RateLimitedClient},
+    error::Error,
+    navigation::system_symbol,
+    schemas::{
+        market::Market,
+        ship::{Ship, ship_cargo::ShipCargo, ship_nav::{ship_nav_flight_mode::ShipNavFlightMode, ship_nav_status::ShipNavStatus}},
+        survey::Survey,
+        trade_symbol::TradeSymbol,
+    },
+};
+
+/// State of a ship owned by its actor, and the shared client to act with it.
+///
+/// Every action waits for the ship to be ready, puts it in the right state (docked or in orbit),
+/// sends the request through the rate limiter, and applies the result to the local state of the ship.
+pub struct ShipContext {
+    /// Local state of the ship, kept up to date with the results of the actions.
+    pub ship: Ship,
+    client: Arc<RateLimitedClient<Authenticated>>,
+}
+
+impl ShipContext {
+    /// Act with the ship through the client.
+    pub fn new(ship: Ship, client: Arc<RateLimitedClient<Authenticated>>) -> ShipContext {
+        ShipContext { ship, client }
+    }
+
+    /// The shared client.
+    pub fn client(&self) -> &Arc<RateLimitedClient<Authenticated>> {
+        &self.client
+    }
+
+    /// The symbol of the ship.
+    pub fn symbol(&self) -> &str {
+        &self.ship.symbol
+    }
+
+    /// Wait for the ship to be off cooldown and arrived at its destination.
+    pub async fn wait_until_ready(&mut self) {
+        self.ship.wait_until_ready().await;
+        self.ship.nav.update_status(Utc::now());
+    }
+
+    /// Fetch the ship again, replacing the local state.
+    pub async fn refresh(&mut self) -> Result<(), Error> {
+        let symbol = self.ship.symbol.clone();
+        self.ship = self.client.request(|client| client.get_ship(&symbol)).await?;
+        Ok(())
+    }
+
+    /// Put the ship in orbit, if it is not already.
+    pub async fn orbit(&mut self) -> Result<(), Error> {
+        self.wait_until_ready().await;
+        if self.ship.nav.status == ShipNavStatus::Docked {
+            let nav = self.client.request(|client| client.orbit_ship(&self.ship.symbol)).await?;
+            self.ship.apply(&nav);
+        }
+        Ok(())
+    }
+
+    /// Dock the ship, if it is not already.
+    pub async fn dock(&mut self) -> Result<(), Error> {
+        self.wait_until_ready().await;
+        if self.ship.nav.status != ShipNavStatus::Docked {
+            let nav = self.client.request(|client| client.dock_ship(&self.ship.symbol)).await?;
+            self.ship.apply(&nav);
+        }
+        Ok(())
+    }
+
+    /// Change the flight mode of the ship, if it is not already the given one.
+    pub async fn set_flight_mode(&mut self, mode: ShipNavFlightMode) -> Result<(), Error> {
+        if self.ship.nav.flight_mode != mode {
+            let nav = self.client.request(|client| client.patch_ship_nav(&self.ship.symbol, mode)).await?;
+            self.ship.apply(&nav);
+        }
+        Ok(())
+    }
+
+    /// Navigate to a waypoint of the system, and wait for the ship to arrive.
+    pub async fn navigate(&mut self, destination: &str) -> Result<(), Error> {
+        if self.ship.nav.waypoint_symbol == destination {
+            return Ok(());
+        }
+        self.orbit().await?;
+        let navigation = self.client.request(|client| client.navigate_ship(&self.ship.symbol, destination)).await?;
+        self.ship.apply(&navigation);
+        self.wait_until_ready().await;
+        Ok(())
+    }
+
+    /// Travel to any waypoint, see [`crate::client::SpaceTradersClient::travel`].
+    /// If the travel fails midway, the ship is kept where it stopped.
+    pub async fn travel_to(&mut self, destination: &str, options: &TravelOptions) -> Result<(), Error> {
+        self.client.travel(&mut self.ship, destination, options).await
+    }
+
+    /// Dock and refuel the ship to its full capacity.
+    pub async fn refuel(&mut self) -> Result<RefuelShipResponse, Error> {
+        self.dock().await?;
+        let refuel = self.client.request(|client| client.refuel_ship(&self.ship.symbol, None)).await?;
+        self.ship.apply(&refuel);
+        Ok(refuel)
+    }
+
+    /// Extract resources at the current waypoint, with a survey if given.
+    pub async fn extract(&mut self, survey: Option<&Survey>) -> Result<ExtractResourcesResponse, Error> {
+        self.orbit().await?;
+        let extraction = match survey {
+            Some(survey) => self.client.request(|client| client.exract_resources_with_survey(&self.ship.symbol, survey)).await?,
+            None => self.client.request(|client| client.exract_resources(&self.ship.symbol, None)).await?,
+        };
+        self.ship.apply(&extraction);
+        Ok(extraction)
+    }
+
+    /// Siphon gas at the current waypoint.
+    pub async fn siphon(&mut self) -> Result<SiphonResourcesResponse, Error> {
+        self.orbit().await?;
+        let siphon = self.client.request(|client| client.siphon_resources(&self.ship.symbol)).await?;
+        self.ship.apply(&siphon);
+        Ok(siphon)
+    }
+
+    /// Survey the current waypoint.
+    pub async fn survey(&mut self) -> Result<CooldownAndSurveys, Error> {
+        self.orbit().await?;
+        let surveys = self.client.request(|client| client.create_survey(&self.ship.symbol)).await?;
+        self.ship.apply(&surveys);
+        Ok(surveys)
+    }
+
+    /// Refine raw goods of the cargo.
+    pub async fn refine(&mut self, produce: FromRefinedTradeSymbol) -> Result<RefiningResult, Error> {
+        self.wait_until_ready().await;
+        let refining = self.client.request(|client| client.ship_refine(&self.ship.symbol, produce)).await?;
+        self.ship.apply(&refining);
+        Ok(refining)
+    }
+
+    /// Dock and sell goods of the cargo to the market.
+    pub async fn sell(&mut self, symbol: TradeSymbol, units: NonZeroU64) -> Result<CagoTransactionResult, Error> {
+        self.dock().await?;
+        let sale = self.client.request(|client| client.sell_cargo(&self.ship.symbol, symbol, units)).await?;
+        self.ship.apply(&sale);
+        Ok(sale)
+    }
+
+    /// Dock and purchase goods from the market.
+    pub async fn purchase(&mut self, symbol: TradeSymbol, units: NonZeroU64) -> Result<CagoTransactionResult, Error> {
+        self.dock().await?;
+        let purchase = self.client.request(|client| client.purchase_cargo(&self.ship.symbol, symbol, units)).await?;
+        self.ship.apply(&purchase);
+        Ok(purchase)
+    }
+
+    /// Dock and buy goods in transactions of at most the trade volume, see [`crate::client::SpaceTradersClient::buy`].
+    pub async fn buy_order(&mut self, order: &TradeOrder) -> Result<FillReport, Error> {
+        self.client.buy(&mut self.ship, order).await
+    }
+
+    /// Dock and sell goods in transactions of at most the trade volume, see [`crate::client::SpaceTradersClient::sell`].
+    pub async fn sell_order(&mut self, order: &TradeOrder) -> Result<FillReport, Error> {
+        self.client.sell(&mut self.ship, order).await
+    }
+
+    /// Sell the cargo at the current market, see [`crate::client::SpaceTradersClient::liquidate`].
+    pub async fn liquidate(&mut self, options: &LiquidationOptions) -> Result<LiquidationReport, LiquidationError> {
+        self.client.liquidate(&mut self.ship, options).await
+    }
+
+    /// Throw goods of the cargo away.
+    pub async fn jettison(&mut self, symbol: TradeSymbol, units: NonZeroU32) -> Result<(), Error> {
+        self.wait_until_ready().await;
+        let cargo = self.client.request(|client| client.jettison_cargo(&self.ship.symbol, symbol, units)).await?;
+        self.ship.apply(&cargo);
+        Ok(())
+    }
+
+    /// Transfer goods of the cargo to another ship at the same waypoint.
+    /// The local state of the other ship is not updated.
+    pub async fn transfer(&mut self, symbol: TradeSymbol, units: NonZeroU64, to: &str) -> Result<ShipCargo, Error> {
+        self.wait_until_ready().await;
+        let cargo = self.client.request(|client| client.transfer_cargo(&self.ship.symbol, symbol, units, to)).await?;
+        self.ship.apply(&cargo);
+        Ok(cargo)
+    }
+
+    /// Fetch the market at the current waypoint. Prices are only visible while the ship is there.
+    pub async fn market(&self) -> Result<Market, Error> {
+        let waypoint = &self.ship.nav.waypoint_symbol;
+        self.client.request(|client| client.get_market(system_symbol(waypoint), waypoint)).await
+    }
+
+    /// Chart the current waypoint.
+    pub async fn chart(&mut self) -> Result<ChartAndWaypoint, Error> {
+        self.wait_until_ready().await;
+        let chart = self.client.request(|client| client.create_chart(&self.ship.symbol)).await?;
+        self.ship.apply(&chart);
+        Ok(chart)
+    }
+
+    /// Dock and deliver goods of the cargo for a contract.
+    pub async fn deliver(&mut self, contract_id: &str, symbol: TradeSymbol, units: u64) -> Result<ContractAndCargo, Error> {
+        self.dock().await?;
+        let symbol = symbol.to_string();
+        let delivery = self.client
+            .request(|client| client.deliver_cargo_to_contract(contract_id, &self.ship.symbol, &symbol, units))
+            .await?;
+        self.ship.apply(&delivery);
+        Ok(delivery)
+    }
+}
@@ -0,0 +1,31 @@
+//* Runtime to automate a fleet: each ship is an actor running in its own task.
+
+pub mod actor;
+pub mod behavior;
+pub mod context;
+pub mod supervisor;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        client::{SpaceTradersClient, Authenticated, rate_limit::{RateLimitedClient, RateLimiter}},
+        conformance::fixture_data,
+        schemas::ship::Ship,
+    };
+    use super::context::ShipContext;
+
+    pub(crate) fn ship() -> Ship {
+        fixture_data("get-my-ship")
+    }
+
+    /// A client that is never used, as the tests only touch the local state.
+    pub(crate) fn client() -> Arc<RateLimitedClient<Authenticated>> {
+        Arc::new(RateLimitedClient::new(SpaceTradersClient::new_with_auth("token"), Arc::new(RateLimiter::default())))
+    }
+
+    pub(crate) fn context() -> ShipContext {
+        ShipContext::new(ship(), client())
+    }
+}
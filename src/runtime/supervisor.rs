@@ -0,0 +1,218 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, atomic::Ordering},
+};
+
+use tokio::task::JoinSet;
+use crate::{
+    client::{Authenticated, rate_limit::RateLimitedClient},
+    error::Error,
+    schemas::ship::Ship,
+};
+use super::{
+    actor::{ActorSlot, ShipHandle, channel, run_actor},
+    behavior::{BehaviorHandle, ShipBehavior},
+    context::ShipContext,
+};
+
+/// How the fleet supervises its actors.
+///
+/// Only panics count as failures of an actor. A task returning an `Err` hands the error to its caller,
+/// and the actor keeps running with the state the task left, without being restarted or refreshed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupervisorOptions {
+    /// Number of times an actor is restarted after a task panicked, before it is stopped for good.
+    pub max_restarts: usize,
+    /// Fetch the ship again when its actor is restarted, as its last known state may be outdated.
+    pub refresh_on_restart: bool,
+    /// Number of tasks that can wait in the mailbox of a ship.
+    pub mailbox_size: usize,
+}
+
+impl Default for SupervisorOptions {
+    fn default() -> Self {
+        SupervisorOptions {
+            max_restarts: 5,
+            refresh_on_restart: true,
+            mailbox_size: 32,
+        }
+    }
+}
+
+/// Run the actor of a ship, and restart it each time a task panics.
+async fn supervise(client: Arc<RateLimitedClient<Authenticated>>, slot: ActorSlot, options: SupervisorOptions) {
+    loop {
+        let restarted = slot.restarts.load(Ordering::SeqCst) > 0;
+        let mut context = ShipContext::new(slot.state.borrow().clone(), client.clone());
+        let mailbox = slot.mailbox.clone();
+        let state = slot.state.clone();
+        let refresh = restarted && options.refresh_on_restart;
+        let actor = tokio::spawn(async move {
+            if refresh {
+                // keep the last known state if the ship can't be fetched
+                let _ = context.refresh().await;
+            }
+            run_actor(context, mailbox, state).await
+        });
+        match actor.await {
+            Err(error) if error.is_panic() && slot.restarts.load(Ordering::SeqCst) < options.max_restarts => {
+                slot.restarts.fetch_add(1, Ordering::SeqCst);
+            },
+            _ => return,
+        }
+    }
+}
+
+/// The actors of every ship of the fleet, sharing one rate limited client.
+///
+/// Each ship has its own task handling its mailbox, so ships act concurrently while the actions of a ship stay in order.
+/// An actor whose task panics is restarted from the last known state of its ship,
+/// while errors returned by tasks are left to their callers, see [`SupervisorOptions`].
+pub struct Fleet {
+    client: Arc<RateLimitedClient<Authenticated>>,
+    options: SupervisorOptions,
+    ships: BTreeMap<String, ShipHandle>,
+    behaviors: BTreeMap<String, BehaviorHandle>,
+    supervisors: JoinSet<()>,
+}
+
+impl Fleet {
+    /// Create a fleet without ships.
+    pub fn new(client: Arc<RateLimitedClient<Authenticated>>, options: SupervisorOptions) -> Fleet {
+        Fleet {
+            client,
+            options,
+            ships: BTreeMap::new(),
+            behaviors: BTreeMap::new(),
+            supervisors: JoinSet::new(),
+        }
+    }
+
+    /// Fetch every ship of the agent, and start an actor for each of them.
+    pub async fn start(client: Arc<RateLimitedClient<Authenticated>>, options: SupervisorOptions) -> Result<Fleet, Error> {
+        let mut fleet = Fleet::new(client, options);
+        let mut page = 1;
+        loop {
+            let (ships, meta) = fleet.client.request(|client| client.list_ships(Some(20), Some(page))).await?;
+            for ship in ships {
+                fleet.spawn(ship);
+            }
+            if !meta.has_next_page() {
+                return Ok(fleet);
+            }
+            page += 1;
+        }
+    }
+
+    /// Start the actor of a ship, or return the handle of its actor if it already has one.
+    pub fn spawn(&mut self, ship: Ship) -> ShipHandle {
+        if let Some(handle) = self.ships.get(&ship.symbol) {
+            return handle.clone();
+        }
+        let (handle, slot) = channel(ship, self.options.mailbox_size);
+        self.supervisors.spawn(supervise(self.client.clone(), slot, self.options.clone()));
+        self.ships.insert(handle.symbol().to_string(), handle.clone());
+        handle
+    }
+
+    /// The shared client.
+    pub fn client(&self) -> &Arc<RateLimitedClient<Authenticated>> {
+        &self.client
+    }
+
+    /// The handle of a ship.
+    pub fn ship(&self, ship_symbol: &str) -> Option<&ShipHandle> {
+        self.ships.get(ship_symbol)
+    }
+
+    /// The handles of every ship.
+    pub fn ships(&self) -> impl Iterator<Item = &ShipHandle> + '_ {
+        self.ships.values()
+    }
+
+    /// Drive a ship with a behavior, replacing the behavior it had. Returns `None` if the fleet has no such ship.
+    pub fn assign<B: ShipBehavior + 'static>(&mut self, ship_symbol: &str, behavior: B) -> Option<&BehaviorHandle> {
+        let handle = self.ships.get(ship_symbol)?.assign(behavior);
+        if let Some(previous) = self.behaviors.insert(ship_symbol.to_string(), handle) {
+            previous.abort();
+        }
+        self.behaviors.get(ship_symbol)
+    }
+
+    /// The behavior assigned to a ship.
+    pub fn behavior(&self, ship_symbol: &str) -> Option<&BehaviorHandle> {
+        self.behaviors.get(ship_symbol)
+    }
+
+    /// Take back the behavior assigned to a ship, without stopping it, to abort or join it.
+    pub fn unassign(&mut self, ship_symbol: &str) -> Option<BehaviorHandle> {
+        self.behaviors.remove(ship_symbol)
+    }
+
+    /// Abort every behavior, stop every actor once the tasks already sent are done, and wait for them.
+    pub async fn shutdown(mut self) {
+        for behavior in self.behaviors.values() {
+            behavior.abort();
+        }
+        for handle in self.ships.values() {
+            handle.stop().await;
+        }
+        while self.supervisors.join_next().await.is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::tests::{client, ship};
+
+    /// A fleet whose client is never used, as the tasks only touch the local state.
+    fn fleet(max_restarts: usize) -> Fleet {
+        let options = SupervisorOptions { max_restarts, refresh_on_restart: false, ..Default::default() };
+        Fleet::new(client(), options)
+    }
+
+    #[tokio::test]
+    async fn serialized_tasks() {
+        let mut fleet = fleet(0);
+        let handle = fleet.spawn(ship());
+        assert_eq!(fleet.spawn(ship()).symbol(), handle.symbol());
+        let tasks = (0..10).map(|_| {
+            let handle = handle.clone();
+            tokio::spawn(async move {
+                handle.run(|context| Box::pin(async move {
+                    context.ship.fuel.current += 1;
+                    tokio::task::yield_now().await;
+                    context.ship.fuel.current
+                })).await.unwrap()
+            })
+        }).collect::<Vec<_>>();
+        let mut results = Vec::new();
+        for task in tasks {
+            results.push(task.await.unwrap());
+        }
+        results.sort();
+        let initial = ship().fuel.current;
+        assert_eq!(results, (initial + 1..=initial + 10).collect::<Vec<_>>());
+        assert_eq!(handle.ship().fuel.current, initial + 10);
+        fleet.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn restart() {
+        let mut fleet = fleet(1);
+        let handle = fleet.spawn(ship());
+        handle.run(|context| Box::pin(async move { context.ship.fuel.current = 7 })).await.unwrap();
+        let panicked = handle.run(|_| Box::pin(async move { panic!("task failed") })).await;
+        assert!(matches!(panicked, Err(Error::ShipActorStopped { .. })));
+        // the restarted actor keeps the last known state
+        assert_eq!(handle.run(|context| Box::pin(async move { context.ship.fuel.current })).await.unwrap(), 7);
+        assert_eq!(handle.restarts(), 1);
+
+        // too many restarts
+        let _ = handle.run(|_| Box::pin(async move { panic!("task failed again") })).await;
+        let stopped = handle.run(|context| Box::pin(async move { context.ship.fuel.current })).await;
+        assert!(matches!(stopped, Err(Error::ShipActorStopped { .. })));
+        fleet.shutdown().await;
+    }
+}
@@ -6,4 +6,5 @@ pub enum ActivityLevel {
     Weak,
     Growing,
     Strong,
+    Restricted,
 }
\ No newline at end of file
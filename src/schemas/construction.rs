@@ -7,6 +7,7 @@ use self::construction_material::ConstructionMaterial;
 
 /// The construction details of a waypoint.
 #[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct Construction {
     /// The symbol of the waypoint.
     pub symbol: String,
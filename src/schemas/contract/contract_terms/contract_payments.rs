@@ -7,4 +7,11 @@ pub struct ContractPayment {
     pub on_accepted: i64,
     /// The amount of credits received when the contract is fulfilled.
     pub on_fulfilled: i64,
-}
\ No newline at end of file
+}
+
+impl ContractPayment {
+    /// The credits received for the whole contract, once accepted and fulfilled.
+    pub fn total(&self) -> i64 {
+        self.on_accepted + self.on_fulfilled
+    }
+}
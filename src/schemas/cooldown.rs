@@ -1,6 +1,7 @@
-use serde::Deserialize;
-
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
 
 /// A cooldown is a period of time in which a ship cannot perform certain actions.
 #[derive(Deserialize, Debug, Clone)]
@@ -16,4 +17,44 @@ pub struct Cooldown {
     pub expiration: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+impl Cooldown {
+    /// Whether the cooldown is over at the given time.
+    pub fn is_ready(&self, now: DateTime<Utc>) -> bool {
+        self.remaining(now).is_zero()
+    }
+
+    /// Time left before the cooldown is over at the given time.
+    /// The server only sends the expiration while the cooldown is active, so no expiration means no time left.
+    pub fn remaining(&self, now: DateTime<Utc>) -> Duration {
+        self.expiration
+            .and_then(|expiration| (expiration - now).to_std().ok())
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Wait until the cooldown is over.
+    pub async fn wait_until_ready(&self) {
+        tokio::time::sleep(self.remaining(Utc::now())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn remaining() {
+        let now = Utc::now();
+        let mut cooldown = Cooldown {
+            ship_symbol: "SHIP-1".to_string(),
+            total_seconds: 60,
+            remaining_seconds: 60,
+            expiration: Some(now + chrono::Duration::seconds(60)),
+        };
+        assert_eq!(cooldown.remaining(now), Duration::from_secs(60));
+        assert!(!cooldown.is_ready(now));
+        assert!(cooldown.is_ready(now + chrono::Duration::seconds(61)));
+        assert_eq!(cooldown.remaining(now + chrono::Duration::seconds(61)), Duration::ZERO);
+        cooldown.expiration = None;
+        assert!(cooldown.is_ready(now));
+    }
+}
@@ -22,5 +22,5 @@ pub enum FactionSymbol {
     Cult,
     Ancients,
     Shadow,
-    Etheral,
+    Ethereal,
 }
\ No newline at end of file
@@ -12,8 +12,8 @@ pub enum FactionTraitIdentifier {
     Distrustful,
     Welcoming,
     Smugglers,
-    Scavangers,
-    Rebelious,
+    Scavengers,
+    Rebellious,
     Exiles,
     Pirates,
     Raiders,
@@ -42,14 +42,14 @@ pub enum FactionTraitIdentifier {
     Intelligent,
     ResearchFocused,
     Collaborative,
-    Progessive,
+    Progressive,
     Militaristic,
     TechnologicallyAdvanced,
-    Agressive,
+    Aggressive,
     Imperialistic,
     TreasureHunters,
-    Dexterious,
-    Unpredictible,
+    Dexterous,
+    Unpredictable,
     Brutal,
     Fleeting,
     Adaptable,
@@ -57,7 +57,7 @@ pub enum FactionTraitIdentifier {
     Defensive,
     Proud,
     Diverse,
-    Independant,
+    Independent,
     SelfInterested,
     Fragmented,
     Commercial,
@@ -3,6 +3,8 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct JumpGate {
+    /// The symbol of the waypoint.
+    pub symbol: String,
     /// All the gates that are connected to this waypoint.
     pub connections: Vec<String>,
 }
\ No newline at end of file
@@ -6,7 +6,7 @@ use self::{
     market_transaction::MarketTransaction,
     market_trade_good::MarketTradeGood
 };
-use super::trade_good::TradeGood;
+use super::{trade_good::TradeGood, trade_symbol::TradeSymbol};
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -23,4 +23,16 @@ pub struct Market {
     pub transactions: Option<Vec<MarketTransaction>>,
     /// The list of goods that are traded at this market. Visible only when a ship is present at the market.
     pub trade_goods: Option<Vec<MarketTradeGood>>,
-}
\ No newline at end of file
+}
+
+impl Market {
+    /// Whether the given good can be bought at this market, as it is exported or exchanged.
+    pub fn sells(&self, symbol: TradeSymbol) -> bool {
+        self.exports.iter().chain(self.exchange.iter()).any(|good| good.symbol == symbol)
+    }
+
+    /// Whether the given good can be sold at this market, as it is imported or exchanged.
+    pub fn buys(&self, symbol: TradeSymbol) -> bool {
+        self.imports.iter().chain(self.exchange.iter()).any(|good| good.symbol == symbol)
+    }
+}
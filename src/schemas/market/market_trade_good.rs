@@ -11,6 +11,18 @@ pub enum MarketTradeGoodType {
     Exchange,
 }
 
+impl MarketTradeGoodType {
+    /// Whether agents buy the good from the market: it is exported or exchanged.
+    pub fn is_sold_to_agents(self) -> bool {
+        matches!(self, MarketTradeGoodType::Export | MarketTradeGoodType::Exchange)
+    }
+
+    /// Whether agents sell the good to the market: it is imported or exchanged.
+    pub fn is_bought_from_agents(self) -> bool {
+        matches!(self, MarketTradeGoodType::Import | MarketTradeGoodType::Exchange)
+    }
+}
+
 /// The activity level of a trade good. If the good is an import, this represents how strong consumption is for the good. If the good is an export, this represents how strong the production is for the good.
 #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -18,6 +30,7 @@ pub enum MarketTradeGoodActivity {
     Weak,
     Growing,
     Strong,
+    Restricted,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -11,4 +11,11 @@ pub struct Meta {
     pub page: NonZeroU64,
     /// The amount of items in each page. Limits how many items can be fetched at once.
     pub limit: NonZeroU64,
-}
\ No newline at end of file
+}
+
+impl Meta {
+    /// Whether there are items after this page.
+    pub fn has_next_page(&self) -> bool {
+        self.page.get().saturating_mul(self.limit.get()) < self.total
+    }
+}
@@ -3,12 +3,44 @@ use serde::Deserialize;
 use crate::schemas::ship::{
     ship_registration::Registration,
     ship_nav::ShipNav,
-    ship_frame::ShipFrame,
-    ship_reactor::ShipReactor,
-    ship_engine::ShipEngine,
-    ship_mount::ShipMount
+    ship_frame::ShipFrameType,
+    ship_reactor::ShipReactorType,
+    ship_engine::ShipEngineType,
+    ship_mount::ShipMountType
 };
 
+/// The frame of a scanned ship.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedShipFrame {
+    /// The symbol of the frame.
+    pub symbol: ShipFrameType,
+}
+
+/// The reactor of a scanned ship.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedShipReactor {
+    /// The symbol of the reactor.
+    pub symbol: ShipReactorType,
+}
+
+/// The engine of a scanned ship.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedShipEngine {
+    /// The symbol of the engine.
+    pub symbol: ShipEngineType,
+}
+
+/// A mount installed on a scanned ship.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedShipMount {
+    /// The symbol of the mount.
+    pub symbol: ShipMountType,
+}
+
 /// The ship that was scanned. Details include information about the ship that could be detected by the scanner.
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -19,12 +51,12 @@ pub struct ScannedShip {
     pub registration: Registration,
     /// The navigation information of the ship.
     pub nav: ShipNav,
-    /// The frame of the ship. The frame determines the number of modules and mounting points of the ship, as well as base fuel capacity. As the condition of the frame takes more wear, the ship will become more sluggish and less maneuverable.
-    pub frame: Option<ShipFrame>,
-    /// The reactor of the ship. The reactor is responsible for powering the ship's systems and weapons.
-    pub reactor: Option<ShipReactor>,
-    /// The engine determines how quickly a ship travels between waypoints.
-    pub engine: ShipEngine,
+    /// The frame of the ship.
+    pub frame: Option<ScannedShipFrame>,
+    /// The reactor of the ship.
+    pub reactor: Option<ScannedShipReactor>,
+    /// The engine of the ship.
+    pub engine: ScannedShipEngine,
     /// Mounts installed in this ship.
-    pub mounts: Option<Vec<ShipMount>>,
-}
\ No newline at end of file
+    pub mounts: Option<Vec<ScannedShipMount>>,
+}
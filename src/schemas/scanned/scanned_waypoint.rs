@@ -4,9 +4,9 @@ use crate::schemas::{
     waypoint::{
         waypoint_type::WaypointType,
         waypoint_orbital::WaypointOrbital,
-        waypoint_trait::WaypointTrait
+        waypoint_trait::WaypointTrait,
+        waypoint_faction::WaypointFaction
     },
-    faction::faction_symbol::FactionSymbol,
     chart::Chart
 };
 
@@ -27,8 +27,8 @@ pub struct ScannedWaypoint {
     pub y: i64,
     /// Waypoints that orbit this waypoint.
     pub orbitals: Vec<WaypointOrbital>,
-    /// The symbol of the parent waypoint, if this waypoint is in orbit around another waypoint. Otherwise this value is undefined.
-    pub faction: FactionSymbol,
+    /// The faction that controls the waypoint.
+    pub faction: Option<WaypointFaction>,
     /// The traits of the waypoint.
     pub traits: Vec<WaypointTrait>,
     /// The chart of a system or waypoint, which makes the location visible to other agents.
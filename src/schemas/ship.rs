@@ -1,3 +1,4 @@
+pub mod ship_capabilities;
 pub mod ship_cargo;
 pub mod ship_condition;
 pub mod ship_crew;
@@ -14,6 +15,9 @@ pub mod ship_requirements;
 pub mod ship_role;
 pub mod ship_type;
 
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use super::cooldown::Cooldown;
 use self::{
@@ -57,4 +61,26 @@ pub struct Ship {
     pub cargo: ShipCargo,
     /// Details of the ship's fuel tanks including how much fuel was consumed during the last transit or action.
     pub fuel: ShipFuel,
-}
\ No newline at end of file
+}
+
+impl Ship {
+    /// Whether the ship is neither on cooldown nor in transit at the given time.
+    pub fn is_ready(&self, now: DateTime<Utc>) -> bool {
+        self.cooldown.is_ready(now) && self.nav.is_ready(now)
+    }
+
+    /// Time left before the ship is neither on cooldown nor in transit at the given time.
+    pub fn remaining(&self, now: DateTime<Utc>) -> Duration {
+        self.cooldown.remaining(now).max(self.nav.remaining(now))
+    }
+
+    /// Wait until the ship is neither on cooldown nor in transit.
+    pub async fn wait_until_ready(&self) {
+        tokio::time::sleep(self.remaining(Utc::now())).await
+    }
+
+    /// Wait until the ship arrives at its destination.
+    pub async fn wait_for_arrival(&self) {
+        self.nav.wait_for_arrival().await
+    }
+}
@@ -0,0 +1,355 @@
+use std::fmt::Display;
+
+use crate::schemas::{
+    trade_symbol::TradeSymbol,
+    shipyard::shipyard_ship::ShipyardShip,
+    scanned::scanned_ship::ScannedShip,
+};
+use super::{
+    Ship,
+    ship_module::{ShipModule, ShipModuleType},
+    ship_mount::{ShipMount, ShipMountType, ShipMountDeposits},
+};
+
+/// Summary of what a ship is able to do, derived from its modules and mounts.
+///
+/// Strengths are the sum of the strengths of the relevant mounts, and ranges the best range of the relevant modules.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShipCapabilities {
+    /// Number of mining laser mounts.
+    pub mining_lasers: usize,
+    /// Number of gas siphon mounts.
+    pub gas_siphons: usize,
+    /// Number of surveyor mounts.
+    pub surveyors: usize,
+    /// Number of sensor array mounts.
+    pub sensor_arrays: usize,
+    /// Combined strength of the mining lasers.
+    pub extraction_strength: u64,
+    /// Combined strength of the gas siphons.
+    pub siphon_strength: u64,
+    /// Combined strength of the surveyors.
+    pub survey_strength: u64,
+    /// Combined strength of the sensor arrays.
+    pub sensor_strength: u64,
+    /// Goods that the mounts of the ship can produce.
+    pub deposits: Vec<ShipMountDeposits>,
+    /// Whether a mineral processor module is installed.
+    pub mineral_processor: bool,
+    /// Whether a gas processor module is installed.
+    pub gas_processor: bool,
+    /// Goods that the refinery modules of the ship can produce.
+    pub refines: Vec<TradeSymbol>,
+    /// Range of the jump drive, if any. Only needed to jump from a waypoint without a jump gate.
+    pub jump_range: Option<u64>,
+    /// Range of the warp drive, if any.
+    pub warp_range: Option<u64>,
+    /// Cargo capacity of the ship.
+    pub cargo_capacity: u64,
+}
+
+/// A capability a ship is missing to perform an action.
+/// Each variant matches the error the server would answer with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MissingCapability {
+    /// The ship has no mining laser.
+    MiningLasers,
+    /// The ship has no gas siphon.
+    GasSiphons,
+    /// The ship has no gas processor, which siphoning needs along with the siphons.
+    GasProcessor,
+    /// The ship has no surveyor.
+    Surveyor,
+    /// The ship has no sensor array.
+    SensorArrays,
+    /// The ship has no refinery, or no refinery able to produce the given good.
+    Refinery(TradeSymbol),
+    /// The ship has no jump drive, and is not at a jump gate.
+    JumpDrive,
+    /// The ship has no warp drive.
+    WarpDrive,
+}
+
+impl Display for MissingCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MissingCapability::MiningLasers => write!(f, "ship has no mining laser"),
+            MissingCapability::GasSiphons => write!(f, "ship has no gas siphon"),
+            MissingCapability::GasProcessor => write!(f, "ship has no gas processor"),
+            MissingCapability::Surveyor => write!(f, "ship has no surveyor"),
+            MissingCapability::SensorArrays => write!(f, "ship has no sensor array"),
+            MissingCapability::Refinery(good) => write!(f, "ship has no refinery able to produce {good:?}"),
+            MissingCapability::JumpDrive => write!(f, "ship has no jump drive"),
+            MissingCapability::WarpDrive => write!(f, "ship has no warp drive"),
+        }
+    }
+}
+
+impl ShipCapabilities {
+    /// Build the capabilities from installed modules and mounts.
+    /// The cargo capacity is the sum of the cargo hold modules capacity.
+    pub fn from_parts<'a>(
+        modules: impl IntoIterator<Item = &'a ShipModule>,
+        mounts: impl IntoIterator<Item = &'a ShipMount>,
+    ) -> ShipCapabilities {
+        let mut result = ShipCapabilities::default();
+        for module in modules {
+            result.add_module(module);
+        }
+        for mount in mounts {
+            result.add_mount(mount.symbol, mount.strength, mount.deposits.as_deref().unwrap_or_default());
+        }
+        result
+    }
+
+    fn add_module(&mut self, module: &ShipModule) {
+        match module.symbol {
+            ShipModuleType::ModuleMineralProcessorI => self.mineral_processor = true,
+            ShipModuleType::ModuleGasProcessorI => self.gas_processor = true,
+            ShipModuleType::ModuleCargoHoldI
+            | ShipModuleType::ModuleCargoHoldIi
+            | ShipModuleType::ModuleCargoHoldIii => self.cargo_capacity += module.capacity.unwrap_or_default(),
+            ShipModuleType::ModuleMicroRefineryI
+            | ShipModuleType::ModuleOreRefineryI => {
+                let metals = TradeSymbol::ALL.into_iter().filter(|good| good.refined_from().is_some() && *good != TradeSymbol::Fuel);
+                self.add_refined_goods(metals);
+            },
+            ShipModuleType::ModuleFuelRefineryI => self.add_refined_goods([TradeSymbol::Fuel]),
+            ShipModuleType::ModuleJumpDriveI
+            | ShipModuleType::ModuleJumpDriveIi
+            | ShipModuleType::ModuleJumpDriveIii => self.jump_range = self.jump_range.max(Some(module.range.unwrap_or_default())),
+            ShipModuleType::ModuleWarpDriveI
+            | ShipModuleType::ModuleWarpDriveIi
+            | ShipModuleType::ModuleWarpDriveIii => self.warp_range = self.warp_range.max(Some(module.range.unwrap_or_default())),
+            _ => {},
+        }
+    }
+
+    fn add_refined_goods(&mut self, goods: impl IntoIterator<Item = TradeSymbol>) {
+        for good in goods {
+            if !self.refines.contains(&good) {
+                self.refines.push(good);
+            }
+        }
+    }
+
+    fn add_mount(&mut self, symbol: ShipMountType, strength: Option<u64>, deposits: &[ShipMountDeposits]) {
+        let strength = strength.unwrap_or_default();
+        match symbol {
+            ShipMountType::MountMiningLaserI
+            | ShipMountType::MountMiningLaserIi
+            | ShipMountType::MountMiningLaserIii => {
+                self.mining_lasers += 1;
+                self.extraction_strength += strength;
+            },
+            ShipMountType::MountGasSiphonI
+            | ShipMountType::MountGasSiphonIi
+            | ShipMountType::MountGasSiphonIii => {
+                self.gas_siphons += 1;
+                self.siphon_strength += strength;
+            },
+            ShipMountType::MountSurveyorI
+            | ShipMountType::MountSurveyorIi
+            | ShipMountType::MountSurveyorIii => {
+                self.surveyors += 1;
+                self.survey_strength += strength;
+            },
+            ShipMountType::MountSensorArrayI
+            | ShipMountType::MountSensorArrayIi
+            | ShipMountType::MountSensorArrayIii => {
+                self.sensor_arrays += 1;
+                self.sensor_strength += strength;
+            },
+            _ => {},
+        }
+        for deposit in deposits {
+            if !self.deposits.contains(deposit) {
+                self.deposits.push(*deposit);
+            }
+        }
+    }
+
+    /// Whether the ship can extract resources from asteroids.
+    pub fn can_extract(&self) -> bool {
+        self.mining_lasers > 0
+    }
+
+    /// Whether the ship can siphon gases from gas giants, which needs gas siphons and a gas processor.
+    pub fn can_siphon(&self) -> bool {
+        self.check_siphon().is_ok()
+    }
+
+    /// Whether the ship can survey waypoints.
+    pub fn can_survey(&self) -> bool {
+        self.surveyors > 0
+    }
+
+    /// Whether the ship can scan systems, waypoints and ships.
+    pub fn can_scan(&self) -> bool {
+        self.sensor_arrays > 0
+    }
+
+    /// Whether the ship can refine goods into the given good.
+    pub fn can_refine_into(&self, good: TradeSymbol) -> bool {
+        self.refines.contains(&good)
+    }
+
+    /// Whether the ship can jump from a waypoint without a jump gate, which needs a jump drive.
+    /// Jumping through a gate needs no module.
+    pub fn can_jump_without_gate(&self) -> bool {
+        self.jump_range.is_some()
+    }
+
+    /// Whether the ship can warp to other systems.
+    pub fn can_warp(&self) -> bool {
+        self.warp_range.is_some()
+    }
+
+    /// Check the ship can extract resources.
+    pub fn check_extract(&self) -> Result<(), MissingCapability> {
+        self.can_extract().then_some(()).ok_or(MissingCapability::MiningLasers)
+    }
+
+    /// Check the ship can siphon resources.
+    pub fn check_siphon(&self) -> Result<(), MissingCapability> {
+        match (self.gas_siphons > 0, self.gas_processor) {
+            (false, _) => Err(MissingCapability::GasSiphons),
+            (true, false) => Err(MissingCapability::GasProcessor),
+            (true, true) => Ok(()),
+        }
+    }
+
+    /// Check the ship can survey.
+    pub fn check_survey(&self) -> Result<(), MissingCapability> {
+        self.can_survey().then_some(()).ok_or(MissingCapability::Surveyor)
+    }
+
+    /// Check the ship can scan.
+    pub fn check_scan(&self) -> Result<(), MissingCapability> {
+        self.can_scan().then_some(()).ok_or(MissingCapability::SensorArrays)
+    }
+
+    /// Check the ship can refine goods into the given good.
+    pub fn check_refine_into(&self, good: TradeSymbol) -> Result<(), MissingCapability> {
+        self.can_refine_into(good).then_some(()).ok_or(MissingCapability::Refinery(good))
+    }
+
+    /// Check the ship can jump from a waypoint without a jump gate.
+    pub fn check_jump_without_gate(&self) -> Result<(), MissingCapability> {
+        self.can_jump_without_gate().then_some(()).ok_or(MissingCapability::JumpDrive)
+    }
+
+    /// Check the ship can warp.
+    pub fn check_warp(&self) -> Result<(), MissingCapability> {
+        self.can_warp().then_some(()).ok_or(MissingCapability::WarpDrive)
+    }
+}
+
+impl Ship {
+    /// Capabilities of this ship. The cargo capacity is the one reported by the ship's cargo.
+    pub fn capabilities(&self) -> ShipCapabilities {
+        ShipCapabilities {
+            cargo_capacity: self.cargo.capacity,
+            ..ShipCapabilities::from_parts(&self.modules, &self.mounts)
+        }
+    }
+}
+
+impl ShipyardShip {
+    /// Capabilities of this ship once purchased.
+    pub fn capabilities(&self) -> ShipCapabilities {
+        ShipCapabilities::from_parts(&self.modules, &self.mounts)
+    }
+}
+
+impl ScannedShip {
+    /// Capabilities that can be deduced from the scan.
+    ///
+    /// Scans only reveal the mount symbols: strengths and deposits are unknown,
+    /// and module derived capabilities (refining, drives, cargo) are never reported.
+    pub fn capabilities(&self) -> ShipCapabilities {
+        let mut result = ShipCapabilities::default();
+        for mount in self.mounts.iter().flatten() {
+            result.add_mount(mount.symbol, None, &[]);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance::fixture_data;
+    use crate::schemas::shipyard::Shipyard;
+
+    #[test]
+    fn ship_capabilities() {
+        let ship: Ship = fixture_data("get-my-ship");
+        let capabilities = ship.capabilities();
+        assert_eq!(capabilities.cargo_capacity, 40);
+        assert_eq!((capabilities.mining_lasers, capabilities.gas_siphons, capabilities.surveyors, capabilities.sensor_arrays), (1, 1, 1, 1));
+        assert_eq!(capabilities.extraction_strength, 5);
+        assert_eq!(capabilities.siphon_strength, 20);
+        assert_eq!(capabilities.survey_strength, 2);
+        assert_eq!(capabilities.sensor_strength, 4);
+        assert_eq!(capabilities.deposits.len(), 13);
+        assert!(capabilities.mineral_processor && capabilities.gas_processor);
+        assert!(capabilities.refines.is_empty());
+        assert_eq!(capabilities.warp_range, Some(2000));
+        assert_eq!(capabilities.jump_range, None);
+        assert!(capabilities.can_extract() && capabilities.can_siphon() && capabilities.can_survey() && capabilities.can_warp());
+    }
+
+    #[test]
+    fn shipyard_ship_capabilities() {
+        let shipyard: Shipyard = fixture_data("get-shipyard");
+        // a mining drone, with a single cargo hold of 15 units
+        let capabilities = shipyard.ships.unwrap()[0].capabilities();
+        assert_eq!(capabilities.cargo_capacity, 15);
+        assert_eq!(capabilities.mining_lasers, 1);
+        assert!(capabilities.mineral_processor);
+    }
+
+    #[test]
+    fn scanned_ship_capabilities() {
+        let scan: serde_json::Value = fixture_data("create-ship-ship-scan");
+        let ships: Vec<ScannedShip> = serde_json::from_value(scan["ships"].clone()).unwrap();
+        assert_eq!(ships.len(), 1);
+        // a single mining laser, of unknown strength, and no module
+        let capabilities = ships[0].capabilities();
+        assert_eq!((capabilities.mining_lasers, capabilities.gas_siphons, capabilities.surveyors, capabilities.sensor_arrays), (1, 0, 0, 0));
+        assert_eq!(capabilities.extraction_strength, 0);
+        assert!(capabilities.deposits.is_empty());
+        assert!(!capabilities.mineral_processor && !capabilities.gas_processor);
+        assert!(capabilities.can_extract() && !capabilities.can_siphon());
+    }
+
+    #[test]
+    fn siphoning() {
+        let ship: Ship = fixture_data("get-my-ship");
+        let mut capabilities = ship.capabilities();
+        assert_eq!(capabilities.check_siphon(), Ok(()));
+        capabilities.gas_processor = false;
+        assert_eq!(capabilities.check_siphon(), Err(MissingCapability::GasProcessor));
+        capabilities.gas_siphons = 0;
+        assert_eq!(capabilities.check_siphon(), Err(MissingCapability::GasSiphons));
+    }
+
+    #[test]
+    fn refineries() {
+        let module = |symbol| ShipModule {
+            symbol,
+            capacity: None,
+            range: None,
+            name: String::new(),
+            description: String::new(),
+            requirements: crate::schemas::ship::ship_requirements::ShipRequirements { power: None, crew: None, slots: None },
+        };
+        let ore = ShipCapabilities::from_parts(&[module(ShipModuleType::ModuleOreRefineryI)], &[]);
+        assert!(ore.can_refine_into(TradeSymbol::Iron));
+        assert_eq!(ore.check_refine_into(TradeSymbol::Fuel), Err(MissingCapability::Refinery(TradeSymbol::Fuel)));
+        let fuel = ShipCapabilities::from_parts(&[module(ShipModuleType::ModuleFuelRefineryI)], &[]);
+        assert_eq!(fuel.refines, vec![TradeSymbol::Fuel]);
+        assert_eq!(fuel.check_extract(), Err(MissingCapability::MiningLasers));
+    }
+}
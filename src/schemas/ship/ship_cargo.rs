@@ -1,6 +1,14 @@
 pub mod ship_cargo_item;
 
+use std::{fmt::Display, num::NonZeroU64};
+
 use serde::Deserialize;
+use crate::schemas::{
+    trade_symbol::TradeSymbol,
+    extraction::extraction_yield::ExtractionYield,
+    siphon::siphon_yield::SiphonYield,
+    market::market_transaction::{MarketTransaction, MarketTransactionType},
+};
 use self::ship_cargo_item::ShipCargoItem;
 
 /// Ship cargo details.
@@ -13,4 +21,210 @@ pub struct ShipCargo {
     pub units: u64,
     /// The items currently in the cargo hold.
     pub inventory: Vec<ShipCargoItem>,
-}
\ No newline at end of file
+}
+
+/// Error returned when a change can't be applied to a cargo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CargoError {
+    /// There is not enough free space in the cargo hold to add the units.
+    InsufficientSpace {
+        available: u64,
+        required: u64,
+    },
+    /// There are not enough units of the good in the cargo hold to remove them.
+    InsufficientUnits {
+        symbol: TradeSymbol,
+        available: u64,
+        required: u64,
+    },
+    /// The transaction is about a good that is not a known trade symbol.
+    UnknownTradeSymbol,
+}
+
+impl Display for CargoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CargoError::InsufficientSpace { available, required } => write!(f, "cargo has {available} free units, {required} required"),
+            CargoError::InsufficientUnits { symbol, available, required } => write!(f, "cargo has {available} units of {symbol}, {required} required"),
+            CargoError::UnknownTradeSymbol => write!(f, "unknown trade symbol"),
+        }
+    }
+}
+
+impl ShipCargo {
+    /// The number of units that can still be stored in the cargo hold.
+    pub fn free_space(&self) -> u64 {
+        self.capacity.saturating_sub(self.units)
+    }
+
+    /// Whether the cargo hold is full.
+    pub fn is_full(&self) -> bool {
+        self.free_space() == 0
+    }
+
+    /// Whether the cargo hold is empty.
+    pub fn is_empty(&self) -> bool {
+        self.units == 0
+    }
+
+    /// The number of units of the given good in the cargo hold.
+    pub fn quantity_of(&self, symbol: TradeSymbol) -> u64 {
+        self.get(symbol).map_or(0, |item| item.units.get())
+    }
+
+    /// Whether there are units of the given good in the cargo hold.
+    pub fn contains(&self, symbol: TradeSymbol) -> bool {
+        self.get(symbol).is_some()
+    }
+
+    /// The cargo item of the given good, if any.
+    pub fn get(&self, symbol: TradeSymbol) -> Option<&ShipCargoItem> {
+        self.inventory.iter().find(|item| item.symbol == symbol)
+    }
+
+    /// Iterate over the goods in the cargo hold and their number of units.
+    pub fn iter(&self) -> impl Iterator<Item = (TradeSymbol, NonZeroU64)> + '_ {
+        self.inventory.iter().map(|item| (item.symbol, item.units))
+    }
+
+    /// Add units of a good to the cargo hold.
+    ///
+    /// Goods that were not in the cargo yet are added with their symbol as name and an empty description,
+    /// as those are only known by the server.
+    pub fn add(&mut self, symbol: TradeSymbol, units: u64) -> Result<(), CargoError> {
+        let units = match NonZeroU64::new(units) {
+            Some(units) => units,
+            None => return Ok(()),
+        };
+        if units.get() > self.free_space() {
+            return Err(CargoError::InsufficientSpace { available: self.free_space(), required: units.get() });
+        }
+        match self.inventory.iter_mut().find(|item| item.symbol == symbol) {
+            Some(item) => item.units = item.units.saturating_add(units.get()),
+            None => self.inventory.push(ShipCargoItem {
+                symbol,
+                name: symbol.to_string(),
+                description: String::new(),
+                units,
+            }),
+        }
+        self.units += units.get();
+        Ok(())
+    }
+
+    /// Remove units of a good from the cargo hold.
+    /// This is what happens on the server when jettisoning cargo or delivering it to a contract.
+    pub fn remove(&mut self, symbol: TradeSymbol, units: u64) -> Result<(), CargoError> {
+        let available = self.quantity_of(symbol);
+        if units > available {
+            return Err(CargoError::InsufficientUnits { symbol, available, required: units });
+        }
+        if units == 0 {
+            return Ok(());
+        }
+        match NonZeroU64::new(available - units) {
+            Some(left) => self.inventory.iter_mut()
+                .filter(|item| item.symbol == symbol)
+                .for_each(|item| item.units = left),
+            None => self.inventory.retain(|item| item.symbol != symbol),
+        }
+        self.units -= units;
+        Ok(())
+    }
+
+    /// Move units of a good from this cargo hold to another one.
+    /// Neither cargo is modified if the transfer is not possible.
+    pub fn transfer(&mut self, to: &mut ShipCargo, symbol: TradeSymbol, units: u64) -> Result<(), CargoError> {
+        let available = self.quantity_of(symbol);
+        if units > available {
+            return Err(CargoError::InsufficientUnits { symbol, available, required: units });
+        }
+        to.add(symbol, units)?;
+        self.remove(symbol, units)
+    }
+
+    /// Add the yield of an extraction to the cargo hold.
+    pub fn apply_extraction(&mut self, extraction_yield: &ExtractionYield) -> Result<(), CargoError> {
+        self.add(extraction_yield.symbol, extraction_yield.units.max(0) as u64)
+    }
+
+    /// Add the yield of a siphon to the cargo hold.
+    pub fn apply_siphon(&mut self, siphon_yield: &SiphonYield) -> Result<(), CargoError> {
+        self.add(siphon_yield.symbol, siphon_yield.units.max(0) as u64)
+    }
+
+    /// Apply a market transaction: purchased goods are added, sold goods are removed.
+    pub fn apply_transaction(&mut self, transaction: &MarketTransaction) -> Result<(), CargoError> {
+        let symbol = transaction.trade_symbol.parse().map_err(|_| CargoError::UnknownTradeSymbol)?;
+        match transaction.transaction_type {
+            MarketTransactionType::Purchase => self.add(symbol, transaction.units),
+            MarketTransactionType::Sell => self.remove(symbol, transaction.units),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance::{fixture_data, load_fixture};
+
+    fn cargo() -> ShipCargo {
+        fixture_data("get-my-ship-cargo")
+    }
+
+    fn empty(capacity: u64) -> ShipCargo {
+        ShipCargo { capacity, units: 0, inventory: Vec::new() }
+    }
+
+    #[test]
+    fn queries() {
+        // 8 units of aluminum ore and 4 of ice water, out of 40
+        let cargo = cargo();
+        assert_eq!(cargo.free_space(), 28);
+        assert!(!cargo.is_full() && !cargo.is_empty());
+        assert_eq!(cargo.quantity_of(TradeSymbol::AluminumOre), 8);
+        assert_eq!(cargo.quantity_of(TradeSymbol::IceWater), 4);
+        assert_eq!(cargo.quantity_of(TradeSymbol::Fuel), 0);
+        assert!(cargo.contains(TradeSymbol::AluminumOre) && !cargo.contains(TradeSymbol::Fuel));
+        let goods = cargo.iter().map(|(symbol, units)| (symbol, units.get())).collect::<Vec<_>>();
+        assert_eq!(goods, [(TradeSymbol::AluminumOre, 8), (TradeSymbol::IceWater, 4)]);
+    }
+
+    #[test]
+    fn add_and_remove() {
+        let mut cargo = empty(40);
+        cargo.add(TradeSymbol::IronOre, 30).unwrap();
+        cargo.add(TradeSymbol::IronOre, 5).unwrap();
+        assert_eq!(cargo.quantity_of(TradeSymbol::IronOre), 35);
+        assert_eq!(cargo.add(TradeSymbol::Fuel, 6), Err(CargoError::InsufficientSpace { available: 5, required: 6 }));
+        assert_eq!(cargo.remove(TradeSymbol::IronOre, 36), Err(CargoError::InsufficientUnits { symbol: TradeSymbol::IronOre, available: 35, required: 36 }));
+        cargo.remove(TradeSymbol::IronOre, 35).unwrap();
+        assert!(cargo.is_empty());
+        assert!(!cargo.contains(TradeSymbol::IronOre));
+        assert!(cargo.inventory.is_empty());
+    }
+
+    #[test]
+    fn transfer() {
+        let mut from = empty(40);
+        let mut to = empty(10);
+        from.add(TradeSymbol::Copper, 20).unwrap();
+        assert!(from.transfer(&mut to, TradeSymbol::Copper, 15).is_err());
+        assert_eq!((from.units, to.units), (20, 0));
+        from.transfer(&mut to, TradeSymbol::Copper, 10).unwrap();
+        assert_eq!(from.quantity_of(TradeSymbol::Copper), 10);
+        assert_eq!(to.quantity_of(TradeSymbol::Copper), 10);
+    }
+
+    #[test]
+    fn transactions() {
+        let json = load_fixture("responses/sell-cargo.json");
+        let transaction: MarketTransaction = serde_json::from_value(json["data"]["transaction"].clone()).unwrap();
+        let symbol = transaction.trade_symbol.parse().unwrap();
+        let mut cargo = empty(transaction.units + 10);
+        assert!(cargo.apply_transaction(&transaction).is_err());
+        cargo.add(symbol, transaction.units + 1).unwrap();
+        cargo.apply_transaction(&transaction).unwrap();
+        assert_eq!(cargo.quantity_of(symbol), 1);
+    }
+}
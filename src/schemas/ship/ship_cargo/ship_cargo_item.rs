@@ -1,12 +1,13 @@
 use std::num::NonZeroU64;
 use serde::Deserialize;
+use crate::schemas::trade_symbol::TradeSymbol;
 
 /// The type of cargo item and the number of units.
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ShipCargoItem {
     /// The unique identifier of the cargo item type.
-    pub symbol: String,
+    pub symbol: TradeSymbol,
     /// The name of the cargo item type.
     pub name: String,
     /// The description of the cargo item type.
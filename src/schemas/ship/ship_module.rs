@@ -14,6 +14,8 @@ pub enum ShipModuleType {
     ModuleEnvoyQuartersI,
     ModulePassengerCabinI,
     ModuleMicroRefineryI,
+    ModuleOreRefineryI,
+    ModuleFuelRefineryI,
     ModuleScienceLabI,
     ModuleJumpDriveI,
     ModuleJumpDriveIi,
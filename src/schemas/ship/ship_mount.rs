@@ -1,5 +1,7 @@
 use serde::{Serialize, Deserialize};
 
+use crate::schemas::trade_symbol::TradeSymbol;
+
 use super::ship_requirements::ShipRequirements;
 
 /// Symbo of this mount.
@@ -18,7 +20,7 @@ pub enum ShipMountType {
     MountMiningLaserI,
     MountMiningLaserIi,
     MountMiningLaserIii,
-    MountLaserCanonI,
+    MountLaserCannonI,
     MountMissileLauncherI,
     MountTurretI,
 }
@@ -43,6 +45,53 @@ pub enum ShipMountDeposits {
     MeritiumOre,
 }
 
+impl From<ShipMountDeposits> for TradeSymbol {
+    fn from(value: ShipMountDeposits) -> Self {
+        match value {
+            ShipMountDeposits::QuartzSand => TradeSymbol::QuartzSand,
+            ShipMountDeposits::SiliconCrystals => TradeSymbol::SiliconCrystals,
+            ShipMountDeposits::PreciousStones => TradeSymbol::PreciousStones,
+            ShipMountDeposits::IceWater => TradeSymbol::IceWater,
+            ShipMountDeposits::AmmoniaIce => TradeSymbol::AmmoniaIce,
+            ShipMountDeposits::IronOre => TradeSymbol::IronOre,
+            ShipMountDeposits::CopperOre => TradeSymbol::CopperOre,
+            ShipMountDeposits::SilverOre => TradeSymbol::SilverOre,
+            ShipMountDeposits::AluminumOre => TradeSymbol::AluminumOre,
+            ShipMountDeposits::GoldOre => TradeSymbol::GoldOre,
+            ShipMountDeposits::PlatinumOre => TradeSymbol::PlatinumOre,
+            ShipMountDeposits::Diamonds => TradeSymbol::Diamonds,
+            ShipMountDeposits::UraniteOre => TradeSymbol::UraniteOre,
+            ShipMountDeposits::MeritiumOre => TradeSymbol::MeritiumOre,
+        }
+    }
+}
+
+/// Error returned when converting a trade symbol that can't be mined into a deposit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeSymbolIsNotADeposit;
+impl TryFrom<TradeSymbol> for ShipMountDeposits {
+    type Error = TradeSymbolIsNotADeposit;
+    fn try_from(value: TradeSymbol) -> Result<Self, Self::Error> {
+        match value {
+            TradeSymbol::QuartzSand => Ok(ShipMountDeposits::QuartzSand),
+            TradeSymbol::SiliconCrystals => Ok(ShipMountDeposits::SiliconCrystals),
+            TradeSymbol::PreciousStones => Ok(ShipMountDeposits::PreciousStones),
+            TradeSymbol::IceWater => Ok(ShipMountDeposits::IceWater),
+            TradeSymbol::AmmoniaIce => Ok(ShipMountDeposits::AmmoniaIce),
+            TradeSymbol::IronOre => Ok(ShipMountDeposits::IronOre),
+            TradeSymbol::CopperOre => Ok(ShipMountDeposits::CopperOre),
+            TradeSymbol::SilverOre => Ok(ShipMountDeposits::SilverOre),
+            TradeSymbol::AluminumOre => Ok(ShipMountDeposits::AluminumOre),
+            TradeSymbol::GoldOre => Ok(ShipMountDeposits::GoldOre),
+            TradeSymbol::PlatinumOre => Ok(ShipMountDeposits::PlatinumOre),
+            TradeSymbol::Diamonds => Ok(ShipMountDeposits::Diamonds),
+            TradeSymbol::UraniteOre => Ok(ShipMountDeposits::UraniteOre),
+            TradeSymbol::MeritiumOre => Ok(ShipMountDeposits::MeritiumOre),
+            _ => Err(TradeSymbolIsNotADeposit),
+        }
+    }
+}
+
 /// A mount is installed on the exterier of a ship.
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
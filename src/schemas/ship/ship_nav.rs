@@ -2,6 +2,9 @@ pub mod ship_nav_flight_mode;
 pub mod ship_nav_route;
 pub mod ship_nav_status;
 
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use self::{
     ship_nav_status::ShipNavStatus,
@@ -23,4 +26,56 @@ pub struct ShipNav {
     pub status: ShipNavStatus,
     /// The ship's set speed when traveling between waypoints or systems.
     pub flight_mode: ShipNavFlightMode,
-}
\ No newline at end of file
+}
+
+impl ShipNav {
+    /// Whether the ship is done travelling at the given time.
+    pub fn is_ready(&self, now: DateTime<Utc>) -> bool {
+        self.remaining(now).is_zero()
+    }
+
+    /// Time left before the ship arrives at its destination at the given time.
+    /// Ships that are not in transit have no time left.
+    pub fn remaining(&self, now: DateTime<Utc>) -> Duration {
+        match self.status {
+            ShipNavStatus::InTransit => (self.route.arrival - now).to_std().unwrap_or(Duration::ZERO),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Mark the ship as orbiting its destination if its transit is over at the given time,
+    /// as the server would report it.
+    pub fn update_status(&mut self, now: DateTime<Utc>) {
+        if self.status == ShipNavStatus::InTransit && self.is_ready(now) {
+            self.status = ShipNavStatus::InOrbit;
+        }
+    }
+
+    /// Wait until the ship arrives at its destination.
+    pub async fn wait_for_arrival(&self) {
+        tokio::time::sleep(self.remaining(Utc::now())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance::load_fixture;
+
+    #[test]
+    fn transit() {
+        let json = load_fixture("responses/navigate-ship.json");
+        let mut nav: ShipNav = serde_json::from_value(json["data"]["nav"].clone()).unwrap();
+        let departure = nav.route.departure_time;
+        let arrival = nav.route.arrival;
+        assert_eq!(nav.status, ShipNavStatus::InTransit);
+        assert_eq!(nav.remaining(departure), (arrival - departure).to_std().unwrap());
+        assert!(!nav.is_ready(departure));
+        assert!(nav.is_ready(arrival));
+        nav.update_status(departure);
+        assert_eq!(nav.status, ShipNavStatus::InTransit);
+        nav.update_status(arrival);
+        assert_eq!(nav.status, ShipNavStatus::InOrbit);
+        assert!(nav.is_ready(departure));
+    }
+}
@@ -30,7 +30,7 @@ pub struct ShipyardShip {
     pub supply: SupplyLevel,
     /// The activity level of a trade good. If the good is an import, this represents how strong consumption is for the good. If the good is an export, this represents how strong the production is for the good.
     pub activity: Option<MarketTradeGoodActivity>,
-    /// 
+    /// The purchase price of the ship.
     pub purchase_price: i64,
     /// The frame of the ship. The frame determines the number of modules and mounting points of the ship, as well as base fuel capacity. As the condition of the frame takes more wear, the ship will become more sluggish and less maneuverable.
     pub frame: ShipFrame,
@@ -42,6 +42,6 @@ pub struct ShipyardShip {
     pub modules: Vec<ShipModule>,
     /// A mount is installed on the exterier of a ship.
     pub mounts: Vec<ShipMount>,
-    /// 
+    /// The crew requirements and capacity of the ship.
     pub crew: ShipyardShipCrew,
 }
\ No newline at end of file
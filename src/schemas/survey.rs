@@ -5,7 +5,7 @@ use serde::{Serialize, Deserialize};
 use self::survey_deposit::SurveyDeposit;
 
 /// The size of the deposit. This value indicates how much can be extracted from the survey before it is exhausted.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SurveySize {
     Small,
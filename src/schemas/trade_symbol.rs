@@ -1,5 +1,37 @@
+use std::{fmt::Display, str::FromStr};
+
 use serde::{Serialize, Deserialize};
 
+/// Number of raw goods consumed by a single refining operation.
+pub const REFINING_INPUT_UNITS: u64 = 30;
+/// Number of refined goods produced by a single refining operation.
+pub const REFINING_OUTPUT_UNITS: u64 = 10;
+/// Number of ship fuel units replenished by one unit of market fuel.
+pub const SHIP_FUEL_PER_FUEL_UNIT: u64 = 100;
+
+/// Broad category of a trade good.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TradeSymbolCategory {
+    /// Metal ores, that can be refined into metals.
+    Ore,
+    /// Raw minerals and ices that are mined but not refined.
+    Mineral,
+    /// Gases that are siphoned from gas giants.
+    Gas,
+    /// Metals refined from ores.
+    RefinedMetal,
+    /// Fuel and antimatter, consumed by ships when travelling.
+    Fuel,
+    /// Rare goods that are found rather than produced.
+    Exotic,
+    /// Goods produced by markets from other goods.
+    Manufactured,
+    /// Reactors, engines, modules and mounts that can be installed on ships.
+    ShipComponent,
+}
+
+/// The good's symbol.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TradeSymbol {
@@ -32,6 +64,7 @@ pub enum TradeSymbol {
     MeritiumOre,
     Hydrocarbon,
     Antimatter,
+    FabMats,
     Fertilizers,
     Fabrics,
     Food,
@@ -48,14 +81,14 @@ pub enum TradeSymbol {
     ShipParts,
     Equipment,
     Fuel,
-    Medecine,
+    Medicine,
     Drugs,
     Clothing,
     Microprocessors,
-    Plastic,
-    QuantumStabilizers,
+    Plastics,
     Polynucleotides,
     Biocomposites,
+    QuantumStabilizers,
     Nanobots,
     AiMainframes,
     QuantumDrives,
@@ -118,4 +151,342 @@ pub enum TradeSymbol {
     MountLaserCannonI,
     MountMissileLauncherI,
     MountTurretI,
-}
\ No newline at end of file
+}
+
+impl TradeSymbol {
+    /// Every trade symbol, in declaration order.
+    pub const ALL: [TradeSymbol; 116] = [
+        TradeSymbol::PreciousStones,
+        TradeSymbol::QuartzSand,
+        TradeSymbol::SiliconCrystals,
+        TradeSymbol::AmmoniaIce,
+        TradeSymbol::LiquidHydrogen,
+        TradeSymbol::LiquidNitrogen,
+        TradeSymbol::IceWater,
+        TradeSymbol::ExoticMatter,
+        TradeSymbol::AdvancedCircuitry,
+        TradeSymbol::GravitonEmitters,
+        TradeSymbol::Iron,
+        TradeSymbol::IronOre,
+        TradeSymbol::Copper,
+        TradeSymbol::CopperOre,
+        TradeSymbol::Aluminum,
+        TradeSymbol::AluminumOre,
+        TradeSymbol::Silver,
+        TradeSymbol::SilverOre,
+        TradeSymbol::Gold,
+        TradeSymbol::GoldOre,
+        TradeSymbol::Platinum,
+        TradeSymbol::PlatinumOre,
+        TradeSymbol::Diamonds,
+        TradeSymbol::Uranite,
+        TradeSymbol::UraniteOre,
+        TradeSymbol::Meritium,
+        TradeSymbol::MeritiumOre,
+        TradeSymbol::Hydrocarbon,
+        TradeSymbol::Antimatter,
+        TradeSymbol::FabMats,
+        TradeSymbol::Fertilizers,
+        TradeSymbol::Fabrics,
+        TradeSymbol::Food,
+        TradeSymbol::Jewelry,
+        TradeSymbol::Machinery,
+        TradeSymbol::Firearms,
+        TradeSymbol::AssaultRifles,
+        TradeSymbol::MilitaryEquipment,
+        TradeSymbol::Explosives,
+        TradeSymbol::LabInstruments,
+        TradeSymbol::Ammunition,
+        TradeSymbol::Electronics,
+        TradeSymbol::ShipPlating,
+        TradeSymbol::ShipParts,
+        TradeSymbol::Equipment,
+        TradeSymbol::Fuel,
+        TradeSymbol::Medicine,
+        TradeSymbol::Drugs,
+        TradeSymbol::Clothing,
+        TradeSymbol::Microprocessors,
+        TradeSymbol::Plastics,
+        TradeSymbol::Polynucleotides,
+        TradeSymbol::Biocomposites,
+        TradeSymbol::QuantumStabilizers,
+        TradeSymbol::Nanobots,
+        TradeSymbol::AiMainframes,
+        TradeSymbol::QuantumDrives,
+        TradeSymbol::RoboticDrones,
+        TradeSymbol::CyberImplants,
+        TradeSymbol::GeneTherapeutics,
+        TradeSymbol::NeuralChips,
+        TradeSymbol::MoodRegulators,
+        TradeSymbol::ViralAgents,
+        TradeSymbol::MicroFusionGenerators,
+        TradeSymbol::Supergrains,
+        TradeSymbol::LaserRifles,
+        TradeSymbol::Holographics,
+        TradeSymbol::ShipSalvage,
+        TradeSymbol::RelicTech,
+        TradeSymbol::NovelLifeforms,
+        TradeSymbol::BotanicalSpecimens,
+        TradeSymbol::CulturalArtifacts,
+        TradeSymbol::ReactorSolarI,
+        TradeSymbol::ReactorFusionI,
+        TradeSymbol::ReactorFissionI,
+        TradeSymbol::ReactorChemicalI,
+        TradeSymbol::ReactorAntimatterI,
+        TradeSymbol::EngineImpulseDriveI,
+        TradeSymbol::EngineIonDriveI,
+        TradeSymbol::EngineIonDriveIi,
+        TradeSymbol::EngineHyperDriveI,
+        TradeSymbol::ModuleMineralProcessorI,
+        TradeSymbol::ModuleGasProcessorI,
+        TradeSymbol::ModuleCargoHoldI,
+        TradeSymbol::ModuleCargoHoldIi,
+        TradeSymbol::ModuleCargoHoldIii,
+        TradeSymbol::ModuleCrewQuartersI,
+        TradeSymbol::ModuleEnvoyQuartersI,
+        TradeSymbol::ModulePassengerCabinI,
+        TradeSymbol::ModuleMicroRefineryI,
+        TradeSymbol::ModuleOreRefineryI,
+        TradeSymbol::ModuleFuelRefineryI,
+        TradeSymbol::ModuleScienceLabI,
+        TradeSymbol::ModuleJumpDriveI,
+        TradeSymbol::ModuleJumpDriveIi,
+        TradeSymbol::ModuleJumpDriveIii,
+        TradeSymbol::ModuleWarpDriveI,
+        TradeSymbol::ModuleWarpDriveIi,
+        TradeSymbol::ModuleWarpDriveIii,
+        TradeSymbol::ModuleShieldGeneratorI,
+        TradeSymbol::ModuleShieldGeneratorIi,
+        TradeSymbol::MountGasSiphonI,
+        TradeSymbol::MountGasSiphonIi,
+        TradeSymbol::MountGasSiphonIii,
+        TradeSymbol::MountSurveyorI,
+        TradeSymbol::MountSurveyorIi,
+        TradeSymbol::MountSurveyorIii,
+        TradeSymbol::MountSensorArrayI,
+        TradeSymbol::MountSensorArrayIi,
+        TradeSymbol::MountSensorArrayIii,
+        TradeSymbol::MountMiningLaserI,
+        TradeSymbol::MountMiningLaserIi,
+        TradeSymbol::MountMiningLaserIii,
+        TradeSymbol::MountLaserCannonI,
+        TradeSymbol::MountMissileLauncherI,
+        TradeSymbol::MountTurretI,
+    ];
+
+    /// The category of this good.
+    pub fn category(self) -> TradeSymbolCategory {
+        match self {
+            TradeSymbol::IronOre
+            | TradeSymbol::CopperOre
+            | TradeSymbol::AluminumOre
+            | TradeSymbol::SilverOre
+            | TradeSymbol::GoldOre
+            | TradeSymbol::PlatinumOre
+            | TradeSymbol::UraniteOre
+            | TradeSymbol::MeritiumOre => TradeSymbolCategory::Ore,
+            TradeSymbol::PreciousStones
+            | TradeSymbol::QuartzSand
+            | TradeSymbol::SiliconCrystals
+            | TradeSymbol::AmmoniaIce
+            | TradeSymbol::IceWater
+            | TradeSymbol::Diamonds => TradeSymbolCategory::Mineral,
+            TradeSymbol::LiquidHydrogen
+            | TradeSymbol::LiquidNitrogen
+            | TradeSymbol::Hydrocarbon => TradeSymbolCategory::Gas,
+            TradeSymbol::Iron
+            | TradeSymbol::Copper
+            | TradeSymbol::Aluminum
+            | TradeSymbol::Silver
+            | TradeSymbol::Gold
+            | TradeSymbol::Platinum
+            | TradeSymbol::Uranite
+            | TradeSymbol::Meritium => TradeSymbolCategory::RefinedMetal,
+            TradeSymbol::Fuel
+            | TradeSymbol::Antimatter => TradeSymbolCategory::Fuel,
+            TradeSymbol::ExoticMatter
+            | TradeSymbol::GravitonEmitters
+            | TradeSymbol::ShipSalvage
+            | TradeSymbol::RelicTech
+            | TradeSymbol::NovelLifeforms
+            | TradeSymbol::BotanicalSpecimens
+            | TradeSymbol::CulturalArtifacts => TradeSymbolCategory::Exotic,
+            TradeSymbol::ReactorSolarI
+            | TradeSymbol::ReactorFusionI
+            | TradeSymbol::ReactorFissionI
+            | TradeSymbol::ReactorChemicalI
+            | TradeSymbol::ReactorAntimatterI
+            | TradeSymbol::EngineImpulseDriveI
+            | TradeSymbol::EngineIonDriveI
+            | TradeSymbol::EngineIonDriveIi
+            | TradeSymbol::EngineHyperDriveI
+            | TradeSymbol::ModuleMineralProcessorI
+            | TradeSymbol::ModuleGasProcessorI
+            | TradeSymbol::ModuleCargoHoldI
+            | TradeSymbol::ModuleCargoHoldIi
+            | TradeSymbol::ModuleCargoHoldIii
+            | TradeSymbol::ModuleCrewQuartersI
+            | TradeSymbol::ModuleEnvoyQuartersI
+            | TradeSymbol::ModulePassengerCabinI
+            | TradeSymbol::ModuleMicroRefineryI
+            | TradeSymbol::ModuleOreRefineryI
+            | TradeSymbol::ModuleFuelRefineryI
+            | TradeSymbol::ModuleScienceLabI
+            | TradeSymbol::ModuleJumpDriveI
+            | TradeSymbol::ModuleJumpDriveIi
+            | TradeSymbol::ModuleJumpDriveIii
+            | TradeSymbol::ModuleWarpDriveI
+            | TradeSymbol::ModuleWarpDriveIi
+            | TradeSymbol::ModuleWarpDriveIii
+            | TradeSymbol::ModuleShieldGeneratorI
+            | TradeSymbol::ModuleShieldGeneratorIi
+            | TradeSymbol::MountGasSiphonI
+            | TradeSymbol::MountGasSiphonIi
+            | TradeSymbol::MountGasSiphonIii
+            | TradeSymbol::MountSurveyorI
+            | TradeSymbol::MountSurveyorIi
+            | TradeSymbol::MountSurveyorIii
+            | TradeSymbol::MountSensorArrayI
+            | TradeSymbol::MountSensorArrayIi
+            | TradeSymbol::MountSensorArrayIii
+            | TradeSymbol::MountMiningLaserI
+            | TradeSymbol::MountMiningLaserIi
+            | TradeSymbol::MountMiningLaserIii
+            | TradeSymbol::MountLaserCannonI
+            | TradeSymbol::MountMissileLauncherI
+            | TradeSymbol::MountTurretI => TradeSymbolCategory::ShipComponent,
+            _ => TradeSymbolCategory::Manufactured,
+        }
+    }
+
+    /// The raw good this good is refined from, if it can be produced by a refinery.
+    ///
+    /// Refining consumes [`REFINING_INPUT_UNITS`] of the raw good to produce [`REFINING_OUTPUT_UNITS`] of this good.
+    pub fn refined_from(self) -> Option<TradeSymbol> {
+        match self {
+            TradeSymbol::Iron => Some(TradeSymbol::IronOre),
+            TradeSymbol::Copper => Some(TradeSymbol::CopperOre),
+            TradeSymbol::Silver => Some(TradeSymbol::SilverOre),
+            TradeSymbol::Gold => Some(TradeSymbol::GoldOre),
+            TradeSymbol::Aluminum => Some(TradeSymbol::AluminumOre),
+            TradeSymbol::Platinum => Some(TradeSymbol::PlatinumOre),
+            TradeSymbol::Uranite => Some(TradeSymbol::UraniteOre),
+            TradeSymbol::Meritium => Some(TradeSymbol::MeritiumOre),
+            TradeSymbol::Fuel => Some(TradeSymbol::Hydrocarbon),
+            _ => None,
+        }
+    }
+
+    /// The good produced when refining this good, if it can be refined.
+    ///
+    /// Refining consumes [`REFINING_INPUT_UNITS`] of this good to produce [`REFINING_OUTPUT_UNITS`] of the refined good.
+    pub fn refines_into(self) -> Option<TradeSymbol> {
+        match self {
+            TradeSymbol::IronOre => Some(TradeSymbol::Iron),
+            TradeSymbol::CopperOre => Some(TradeSymbol::Copper),
+            TradeSymbol::SilverOre => Some(TradeSymbol::Silver),
+            TradeSymbol::GoldOre => Some(TradeSymbol::Gold),
+            TradeSymbol::AluminumOre => Some(TradeSymbol::Aluminum),
+            TradeSymbol::PlatinumOre => Some(TradeSymbol::Platinum),
+            TradeSymbol::UraniteOre => Some(TradeSymbol::Uranite),
+            TradeSymbol::MeritiumOre => Some(TradeSymbol::Meritium),
+            TradeSymbol::Hydrocarbon => Some(TradeSymbol::Fuel),
+            _ => None,
+        }
+    }
+
+    /// Whether this good can be extracted with mining lasers.
+    pub fn is_mineable(self) -> bool {
+        matches!(self.category(), TradeSymbolCategory::Ore | TradeSymbolCategory::Mineral)
+    }
+
+    /// Whether this good can be extracted with gas siphons.
+    pub fn is_siphonable(self) -> bool {
+        self.category() == TradeSymbolCategory::Gas
+    }
+
+    /// The amount of ship fuel one unit of this good provides when refueling, if it is fuel.
+    pub fn ship_fuel_units(self) -> Option<u64> {
+        match self {
+            TradeSymbol::Fuel => Some(SHIP_FUEL_PER_FUEL_UNIT),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TradeSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // serializing a unit variant can't fail, and always yields the symbol as sent by the server
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(symbol)) => write!(f, "{symbol}"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+
+/// Error returned when parsing a string that is not a known trade symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownTradeSymbol;
+impl FromStr for TradeSymbol {
+    type Err = UnknownTradeSymbol;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TradeSymbol::deserialize(serde_json::Value::String(s.to_string())).map_err(|_| UnknownTradeSymbol)
+    }
+}
+
+/// Number of market fuel units to buy to get at least the given amount of ship fuel.
+pub fn fuel_units_for_ship_fuel(ship_fuel: u64) -> u64 {
+    ship_fuel.div_ceil(SHIP_FUEL_PER_FUEL_UNIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::ship::ship_mount::ShipMountDeposits;
+
+    #[test]
+    fn refining_is_symmetric() {
+        for symbol in TradeSymbol::ALL {
+            if let Some(refined) = symbol.refines_into() {
+                assert_eq!(refined.refined_from(), Some(symbol));
+            }
+            if let Some(raw) = symbol.refined_from() {
+                assert_eq!(raw.refines_into(), Some(symbol));
+            }
+        }
+        assert_eq!(TradeSymbol::IronOre.refines_into(), Some(TradeSymbol::Iron));
+        assert_eq!(TradeSymbol::Fuel.refined_from(), Some(TradeSymbol::Hydrocarbon));
+        assert_eq!(TradeSymbol::Iron.refines_into(), None);
+    }
+
+    #[test]
+    fn mineable_goods_are_deposits() {
+        for symbol in TradeSymbol::ALL {
+            let deposit = ShipMountDeposits::try_from(symbol);
+            assert_eq!(symbol.is_mineable(), deposit.is_ok(), "{symbol:?}");
+            if let Ok(deposit) = deposit {
+                assert_eq!(TradeSymbol::from(deposit), symbol);
+            }
+            assert!(!(symbol.is_mineable() && symbol.is_siphonable()));
+        }
+    }
+
+    #[test]
+    fn fuel_units() {
+        assert_eq!(TradeSymbol::Fuel.ship_fuel_units(), Some(100));
+        assert_eq!(TradeSymbol::Antimatter.ship_fuel_units(), None);
+        assert_eq!(fuel_units_for_ship_fuel(0), 0);
+        assert_eq!(fuel_units_for_ship_fuel(1), 1);
+        assert_eq!(fuel_units_for_ship_fuel(100), 1);
+        assert_eq!(fuel_units_for_ship_fuel(238), 3);
+    }
+
+    #[test]
+    fn parse_and_display() {
+        for symbol in TradeSymbol::ALL {
+            assert_eq!(symbol.to_string().parse(), Ok(symbol));
+        }
+        assert_eq!(TradeSymbol::IronOre.to_string(), "IRON_ORE");
+        assert_eq!("NOT_A_GOOD".parse::<TradeSymbol>(), Err(UnknownTradeSymbol));
+    }
+}
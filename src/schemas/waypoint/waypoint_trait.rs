@@ -11,7 +11,8 @@ pub enum WaypointTraitType {
     ScatteredSettlements,
     SprawlingCities,
     MegaStructures,
-    Overcroweded,
+    PirateBase,
+    Overcrowded,
     HighTech,
     Corrupt,
     Bureaucratic,
@@ -24,6 +25,8 @@ pub enum WaypointTraitType {
     ExplorationOutpost,
     MineralDeposits,
     CommonMetalDeposits,
+    PreciousMetalDeposits,
+    RareMetalDeposits,
     MethanePools,
     IceCrystals,
     ExplosiveGases,
@@ -0,0 +1,230 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use crate::schemas::{
+    market::{
+        Market,
+        market_trade_good::{MarketTradeGood, MarketTradeGoodActivity, MarketTradeGoodType},
+        market_transaction::{MarketTransaction, MarketTransactionType},
+    },
+    supply_level::SupplyLevel,
+    trade_symbol::TradeSymbol,
+};
+
+/// Prices of a good at a market, at a given time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceObservation {
+    /// When the prices were seen.
+    pub timestamp: DateTime<Utc>,
+    /// Whether the good is exported, imported or exchanged by the market.
+    pub trade_type: MarketTradeGoodType,
+    /// Maximum number of units traded in a single transaction.
+    pub trade_volume: u64,
+    /// The supply level of the good.
+    pub supply: SupplyLevel,
+    /// The activity level of the good.
+    pub activity: MarketTradeGoodActivity,
+    /// The price at which the good could be purchased from the market.
+    pub purchase_price: u64,
+    /// The price at which the good could be sold to the market.
+    pub sell_price: u64,
+}
+
+impl PriceObservation {
+    /// Observation of a trade good of a market at the given time.
+    pub fn new(good: &MarketTradeGood, timestamp: DateTime<Utc>) -> PriceObservation {
+        PriceObservation {
+            timestamp,
+            trade_type: good.trade_type,
+            trade_volume: good.trade_volume.get(),
+            supply: good.supply,
+            activity: good.activity,
+            purchase_price: good.purchase_price,
+            sell_price: good.sell_price,
+        }
+    }
+}
+
+/// What identifies a transaction, as the server doesn't give them an id.
+type TransactionKey = (String, String, MarketTransactionType, u64, u64, DateTime<Utc>);
+
+fn transaction_key(transaction: &MarketTransaction) -> TransactionKey {
+    (
+        transaction.ship_symbol.clone(),
+        transaction.trade_symbol.clone(),
+        transaction.transaction_type,
+        transaction.units,
+        transaction.price_per_unit,
+        transaction.timestamp,
+    )
+}
+
+/// Prices and transactions seen at markets over time.
+///
+/// Trade goods and transactions are only visible while a ship is at the market,
+/// so the history keeps every observation to take decisions about markets that are not visited right now.
+#[derive(Debug, Clone, Default)]
+pub struct MarketHistory {
+    /// Observations of each good at each market, oldest first.
+    prices: HashMap<(String, TradeSymbol), Vec<PriceObservation>>,
+    /// Transactions at each market, oldest first.
+    transactions: HashMap<String, Vec<MarketTransaction>>,
+    known_transactions: HashSet<TransactionKey>,
+    /// When the prices of each market were last seen.
+    last_seen: HashMap<String, DateTime<Utc>>,
+}
+
+impl MarketHistory {
+    /// Create an empty history.
+    pub fn new() -> MarketHistory {
+        MarketHistory::default()
+    }
+
+    /// Record the trade goods and transactions of a market seen at the given time.
+    ///
+    /// Transactions already recorded are ignored. Returns the number of new transactions.
+    pub fn record(&mut self, market: &Market, timestamp: DateTime<Utc>) -> usize {
+        if let Some(goods) = &market.trade_goods {
+            for good in goods {
+                if let Ok(symbol) = good.symbol.parse::<TradeSymbol>() {
+                    self.record_price(&market.symbol, symbol, PriceObservation::new(good, timestamp));
+                }
+            }
+            let last_seen = self.last_seen.entry(market.symbol.clone()).or_insert(timestamp);
+            *last_seen = (*last_seen).max(timestamp);
+        }
+        market.transactions.iter()
+            .flatten()
+            .filter(|transaction| self.record_transaction(transaction))
+            .count()
+    }
+
+    /// Record an observation of a good at a market. Observations with the same timestamp replace each other.
+    pub fn record_price(&mut self, waypoint_symbol: &str, symbol: TradeSymbol, observation: PriceObservation) {
+        let series = self.prices.entry((waypoint_symbol.to_string(), symbol)).or_default();
+        match series.binary_search_by_key(&observation.timestamp, |o| o.timestamp) {
+            Ok(index) => series[index] = observation,
+            Err(index) => series.insert(index, observation),
+        }
+    }
+
+    /// Record a transaction, such as the result of a purchase or a sale.
+    /// Returns `false` if the transaction was already recorded.
+    pub fn record_transaction(&mut self, transaction: &MarketTransaction) -> bool {
+        if !self.known_transactions.insert(transaction_key(transaction)) {
+            return false;
+        }
+        let transactions = self.transactions.entry(transaction.waypoint_symbol.clone()).or_default();
+        let index = transactions.partition_point(|t| t.timestamp <= transaction.timestamp);
+        transactions.insert(index, transaction.clone());
+        true
+    }
+
+    /// The most recent observation of a good at a market.
+    pub fn latest(&self, waypoint_symbol: &str, symbol: TradeSymbol) -> Option<&PriceObservation> {
+        self.series(waypoint_symbol, symbol).last()
+    }
+
+    /// Every observation of a good at a market, oldest first.
+    pub fn series(&self, waypoint_symbol: &str, symbol: TradeSymbol) -> &[PriceObservation] {
+        self.prices.get(&(waypoint_symbol.to_string(), symbol))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Observations of a good at a market made at or after the given time, oldest first.
+    pub fn series_since(&self, waypoint_symbol: &str, symbol: TradeSymbol, since: DateTime<Utc>) -> &[PriceObservation] {
+        let series = self.series(waypoint_symbol, symbol);
+        &series[series.partition_point(|o| o.timestamp < since)..]
+    }
+
+    /// The most recent observation of a good at every market it was seen at.
+    pub fn latest_prices(&self, symbol: TradeSymbol) -> impl Iterator<Item = (&str, &PriceObservation)> + '_ {
+        self.prices.iter()
+            .filter(move |((_, s), _)| *s == symbol)
+            .filter_map(|((waypoint, _), series)| Some((waypoint.as_str(), series.last()?)))
+    }
+
+    /// The most recent observation of every good at every market.
+    pub fn all_latest_prices(&self) -> impl Iterator<Item = (&str, TradeSymbol, &PriceObservation)> + '_ {
+        self.prices.iter()
+            .filter_map(|((waypoint, symbol), series)| Some((waypoint.as_str(), *symbol, series.last()?)))
+    }
+
+    /// Goods seen at a market.
+    pub fn goods(&self, waypoint_symbol: &str) -> impl Iterator<Item = TradeSymbol> + '_ {
+        let waypoint_symbol = waypoint_symbol.to_string();
+        self.prices.keys()
+            .filter(move |(waypoint, _)| *waypoint == waypoint_symbol)
+            .map(|(_, symbol)| *symbol)
+    }
+
+    /// Markets whose prices were seen.
+    pub fn markets(&self) -> impl Iterator<Item = &str> + '_ {
+        self.last_seen.keys().map(String::as_str)
+    }
+
+    /// Transactions recorded at a market, oldest first.
+    pub fn transactions(&self, waypoint_symbol: &str) -> &[MarketTransaction] {
+        self.transactions.get(waypoint_symbol).map_or(&[], Vec::as_slice)
+    }
+
+    /// When the prices of a market were last seen.
+    pub fn last_seen(&self, waypoint_symbol: &str) -> Option<DateTime<Utc>> {
+        self.last_seen.get(waypoint_symbol).copied()
+    }
+
+    /// How long ago the prices of a market were last seen.
+    pub fn last_seen_age(&self, waypoint_symbol: &str, now: DateTime<Utc>) -> Option<Duration> {
+        let last_seen = self.last_seen(waypoint_symbol)?;
+        Some((now - last_seen).to_std().unwrap_or(Duration::ZERO))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance::fixture_data;
+
+    fn market() -> Market {
+        fixture_data("get-market")
+    }
+
+    #[test]
+    fn record() {
+        let mut market = market();
+        let mut history = MarketHistory::new();
+        let first = Utc::now();
+        let second = first + chrono::Duration::minutes(3);
+        assert_eq!(history.record(&market, first), 1);
+        // the transaction is still listed the next time, and only recorded once
+        market.trade_goods.as_mut().unwrap()[0].purchase_price = 80;
+        assert_eq!(history.record(&market, second), 0);
+        assert_eq!(history.transactions(&market.symbol).len(), 1);
+
+        let series = history.series(&market.symbol, TradeSymbol::Fuel);
+        assert_eq!(series.iter().map(|o| o.purchase_price).collect::<Vec<_>>(), [72, 80]);
+        assert_eq!(history.latest(&market.symbol, TradeSymbol::Fuel).unwrap().purchase_price, 80);
+        assert_eq!(history.series_since(&market.symbol, TradeSymbol::Fuel, second).len(), 1);
+        assert_eq!(history.latest_prices(TradeSymbol::IceWater).count(), 1);
+        assert_eq!(history.goods(&market.symbol).count(), 4);
+        assert!(history.latest(&market.symbol, TradeSymbol::Gold).is_none());
+
+        let later = second + chrono::Duration::seconds(90);
+        assert_eq!(history.last_seen_age(&market.symbol, later), Some(Duration::from_secs(90)));
+        assert_eq!(history.last_seen_age("X1-NOPE-A1", later), None);
+    }
+
+    #[test]
+    fn without_ship() {
+        let mut market = market();
+        market.trade_goods = None;
+        market.transactions = None;
+        let mut history = MarketHistory::new();
+        assert_eq!(history.record(&market, Utc::now()), 0);
+        assert!(history.last_seen(&market.symbol).is_none());
+        assert_eq!(history.markets().count(), 0);
+    }
+}
@@ -0,0 +1,4 @@
+//* Tools to take trading decisions from the markets seen so far.
+
+pub mod history;
+pub mod routes;
@@ -0,0 +1,264 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use crate::{
+    navigation::route::{RouteOptions, SystemMap},
+    schemas::{
+        market::market_trade_good::MarketTradeGoodActivity,
+        supply_level::SupplyLevel,
+        trade_symbol::{TradeSymbol, fuel_units_for_ship_fuel},
+    },
+};
+use super::history::{MarketHistory, PriceObservation};
+
+/// Fuel and time to travel from a market to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Travel {
+    /// Ship fuel consumed.
+    pub fuel: u64,
+    pub duration: Duration,
+}
+
+impl Travel {
+    /// Plan the travel between two waypoints of a system, for a ship leaving with a full tank.
+    pub fn plan(map: &SystemMap, engine_speed: u64, fuel_capacity: u64, from: &str, to: &str, options: &RouteOptions) -> Option<Travel> {
+        map.plan_route_with(engine_speed, fuel_capacity, fuel_capacity, from, to, options)
+            .map(|route| Travel { fuel: route.fuel(), duration: route.duration() })
+    }
+}
+
+/// What the route finder considers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradeOptions {
+    /// Units the ship can carry.
+    pub cargo_capacity: u64,
+    /// Transactions made at each market per trip. Prices change after each transaction of the trade volume,
+    /// so the more transactions, the less accurate the estimate.
+    pub transactions_per_trip: u64,
+    /// Price of a unit of market fuel, to count the fuel of the trips as a cost.
+    pub fuel_price: u64,
+    /// Ignore prices seen longer ago than this.
+    pub max_age: Option<Duration>,
+    /// Ignore markets where the supply of the good to buy is lower than this.
+    pub min_supply: SupplyLevel,
+    /// Ignore markets where the activity of the good to buy is one of these: the production of an export
+    /// with a restricted activity is held back by a missing import, so its supply doesn't recover.
+    pub excluded_activities: Vec<MarketTradeGoodActivity>,
+}
+
+impl Default for TradeOptions {
+    fn default() -> Self {
+        TradeOptions {
+            cargo_capacity: 40,
+            transactions_per_trip: 1,
+            fuel_price: 0,
+            max_age: None,
+            min_supply: SupplyLevel::Scarce,
+            excluded_activities: vec![MarketTradeGoodActivity::Restricted],
+        }
+    }
+}
+
+/// An opportunity to buy a good at a market and sell it at another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeRoute {
+    pub symbol: TradeSymbol,
+    /// Where the good is bought.
+    pub buy_market: String,
+    /// Where the good is sold.
+    pub sell_market: String,
+    /// Last seen prices, volume, supply and activity where the good is bought.
+    pub buy: PriceObservation,
+    /// Last seen prices, volume, supply and activity where the good is sold.
+    pub sell: PriceObservation,
+    /// Units carried each trip, limited by the cargo capacity and the trade volumes.
+    pub units_per_trip: u64,
+    /// Travel from the buy market to the sell market and back.
+    pub round_trip: Travel,
+}
+
+impl TradeRoute {
+    /// Sell price minus purchase price.
+    pub fn profit_per_unit(&self) -> i64 {
+        self.sell.sell_price as i64 - self.buy.purchase_price as i64
+    }
+
+    /// Profit of the units carried in a trip, minus the fuel of the round trip at the given price.
+    pub fn profit_per_trip(&self, fuel_price: u64) -> i64 {
+        let fuel_cost = fuel_units_for_ship_fuel(self.round_trip.fuel) * fuel_price;
+        self.profit_per_unit() * self.units_per_trip as i64 - fuel_cost as i64
+    }
+
+    /// Profit per hour of going back and forth between the markets.
+    /// A round trip taking no time, such as between orbitals, is counted as taking a second.
+    pub fn profit_per_hour(&self, fuel_price: u64) -> f64 {
+        self.profit_per_trip(fuel_price) as f64 * 3600. / self.round_trip.duration.as_secs_f64().max(1.)
+    }
+}
+
+/// Find the profitable trade routes between the markets of the history, best profit per hour first.
+///
+/// A good is only bought where the market exports or exchanges it, with enough supply and an accepted activity,
+/// and sold where it imports or exchanges it. `travel` gives the travel between two markets, or `None` if the ship can't travel between them.
+pub fn find_trade_routes(
+    history: &MarketHistory,
+    options: &TradeOptions,
+    now: DateTime<Utc>,
+    travel: impl Fn(&str, &str) -> Option<Travel>,
+) -> Vec<TradeRoute> {
+    let is_recent = |observation: &PriceObservation| options.max_age
+        .is_none_or(|max_age| (now - observation.timestamp).to_std().map_or(true, |age| age <= max_age));
+
+    let mut goods = HashMap::<TradeSymbol, (Vec<(&str, &PriceObservation)>, Vec<(&str, &PriceObservation)>)>::new();
+    for (market, symbol, observation) in history.all_latest_prices().filter(|(_, _, observation)| is_recent(observation)) {
+        let (buy, sell) = goods.entry(symbol).or_default();
+        if observation.trade_type.is_sold_to_agents()
+            && observation.supply >= options.min_supply
+            && !options.excluded_activities.contains(&observation.activity)
+        {
+            buy.push((market, observation));
+        }
+        if observation.trade_type.is_bought_from_agents() {
+            sell.push((market, observation));
+        }
+    }
+
+    let mut routes = Vec::new();
+    for (symbol, (buy, sell)) in goods {
+        for &(buy_market, buy_observation) in &buy {
+            for &(sell_market, sell_observation) in &sell {
+                if buy_market == sell_market || sell_observation.sell_price <= buy_observation.purchase_price {
+                    continue;
+                }
+                let (Some(there), Some(back)) = (travel(buy_market, sell_market), travel(sell_market, buy_market)) else {
+                    continue;
+                };
+                let units_per_trip = options.cargo_capacity
+                    .min(buy_observation.trade_volume * options.transactions_per_trip)
+                    .min(sell_observation.trade_volume * options.transactions_per_trip);
+                let route = TradeRoute {
+                    symbol,
+                    buy_market: buy_market.to_string(),
+                    sell_market: sell_market.to_string(),
+                    buy: buy_observation.clone(),
+                    sell: sell_observation.clone(),
+                    units_per_trip,
+                    round_trip: Travel { fuel: there.fuel + back.fuel, duration: there.duration + back.duration },
+                };
+                if route.profit_per_trip(options.fuel_price) > 0 {
+                    routes.push(route);
+                }
+            }
+        }
+    }
+    routes.sort_by(|a, b| b.profit_per_hour(options.fuel_price).total_cmp(&a.profit_per_hour(options.fuel_price)));
+    routes
+}
+
+/// Group routes by good, keeping their order.
+pub fn routes_by_symbol(routes: Vec<TradeRoute>) -> HashMap<TradeSymbol, Vec<TradeRoute>> {
+    let mut grouped = HashMap::<TradeSymbol, Vec<TradeRoute>>::new();
+    for route in routes {
+        grouped.entry(route.symbol).or_default().push(route);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::market::market_trade_good::MarketTradeGoodType;
+
+    fn observation(trade_type: MarketTradeGoodType, trade_volume: u64, purchase_price: u64, sell_price: u64, timestamp: DateTime<Utc>) -> PriceObservation {
+        PriceObservation {
+            timestamp,
+            trade_type,
+            trade_volume,
+            supply: SupplyLevel::Moderate,
+            activity: MarketTradeGoodActivity::Growing,
+            purchase_price,
+            sell_price,
+        }
+    }
+
+    /// Iron is exported at A and imported at B and C, C being further away but paying more.
+    fn history(now: DateTime<Utc>) -> MarketHistory {
+        let mut history = MarketHistory::new();
+        history.record_price("A", TradeSymbol::Iron, observation(MarketTradeGoodType::Export, 20, 100, 80, now));
+        history.record_price("B", TradeSymbol::Iron, observation(MarketTradeGoodType::Import, 60, 150, 130, now));
+        history.record_price("C", TradeSymbol::Iron, observation(MarketTradeGoodType::Import, 60, 250, 200, now));
+        // exported at both, so it can't be sold anywhere
+        history.record_price("A", TradeSymbol::Copper, observation(MarketTradeGoodType::Export, 20, 10, 5, now));
+        history.record_price("B", TradeSymbol::Copper, observation(MarketTradeGoodType::Export, 20, 100, 90, now));
+        // exchanged at both
+        history.record_price("B", TradeSymbol::Fuel, observation(MarketTradeGoodType::Exchange, 100, 50, 40, now - chrono::Duration::hours(2)));
+        history.record_price("C", TradeSymbol::Fuel, observation(MarketTradeGoodType::Exchange, 100, 80, 70, now));
+        history
+    }
+
+    fn travel(from: &str, to: &str) -> Option<Travel> {
+        let seconds = match (from, to) {
+            ("A", "B") | ("B", "A") => 600,
+            ("A", "C") | ("C", "A") => 1800,
+            ("B", "C") | ("C", "B") => 1200,
+            _ => return None,
+        };
+        Some(Travel { fuel: seconds / 10, duration: Duration::from_secs(seconds) })
+    }
+
+    #[test]
+    fn ranking() {
+        let now = Utc::now();
+        let options = TradeOptions { fuel_price: 100, ..Default::default() };
+        let routes = find_trade_routes(&history(now), &options, now, travel);
+        let summary = routes.iter()
+            .map(|route| (route.symbol, route.buy_market.as_str(), route.sell_market.as_str()))
+            .collect::<Vec<_>>();
+        // C is further away, but pays enough more to be worth it
+        assert_eq!(summary, [
+            (TradeSymbol::Iron, "A", "C"),
+            (TradeSymbol::Iron, "A", "B"),
+            (TradeSymbol::Fuel, "B", "C"),
+        ]);
+
+        // limited by the trade volume at A
+        let route = &routes[1];
+        assert_eq!(route.units_per_trip, 20);
+        assert_eq!(route.profit_per_unit(), 30);
+        assert_eq!(route.profit_per_trip(100), 20 * 30 - 2 * 100);
+        assert_eq!(route.profit_per_hour(100), 400. * 3600. / 1200.);
+
+        let grouped = routes_by_symbol(routes);
+        assert_eq!(grouped[&TradeSymbol::Iron].len(), 2);
+        assert_eq!(grouped[&TradeSymbol::Iron][0].sell_market, "C");
+    }
+
+    #[test]
+    fn filters() {
+        let now = Utc::now();
+        // the fuel prices at B are too old
+        let options = TradeOptions { max_age: Some(Duration::from_secs(3600)), ..Default::default() };
+        let routes = find_trade_routes(&history(now), &options, now, travel);
+        assert!(routes.iter().all(|route| route.symbol == TradeSymbol::Iron));
+
+        // more transactions carry more units, up to the cargo capacity
+        let options = TradeOptions { transactions_per_trip: 3, ..Default::default() };
+        let routes = find_trade_routes(&history(now), &options, now, travel);
+        assert!(routes.iter().all(|route| route.units_per_trip == 40));
+
+        let options = TradeOptions { min_supply: SupplyLevel::High, ..Default::default() };
+        assert!(find_trade_routes(&history(now), &options, now, travel).is_empty());
+        let options = TradeOptions { excluded_activities: vec![MarketTradeGoodActivity::Growing], ..Default::default() };
+        assert!(find_trade_routes(&history(now), &options, now, travel).is_empty());
+
+        // the production of iron at A is restricted
+        let mut restricted_at_a = history(now);
+        let restricted = PriceObservation { activity: MarketTradeGoodActivity::Restricted, ..observation(MarketTradeGoodType::Export, 20, 100, 80, now) };
+        restricted_at_a.record_price("A", TradeSymbol::Iron, restricted);
+        let routes = find_trade_routes(&restricted_at_a, &TradeOptions::default(), now, travel);
+        assert!(routes.iter().all(|route| route.symbol == TradeSymbol::Fuel));
+        let options = TradeOptions { excluded_activities: Vec::new(), ..Default::default() };
+        assert!(find_trade_routes(&restricted_at_a, &options, now, travel).iter().any(|route| route.symbol == TradeSymbol::Iron));
+        assert!(find_trade_routes(&history(now), &TradeOptions::default(), now, |_, _| None).is_empty());
+    }
+}
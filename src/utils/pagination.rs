@@ -4,12 +4,24 @@
 pub(crate) fn page_limit_and_index(page_limit: Option<u64>, page_index: Option<u64>) -> (u64, u64) {
     (
         match page_limit {
-            Some(limit) => limit.min(20).max(1),
+            Some(limit) => limit.clamp(1, 20),
             None => 10,
         },
         match page_index {
-            Some(index) => index.min(1),
+            Some(index) => index.max(1),
             None => 1,
         },
     )
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::page_limit_and_index;
+
+    #[test]
+    fn clamp() {
+        assert_eq!(page_limit_and_index(None, None), (10, 1));
+        assert_eq!(page_limit_and_index(Some(50), Some(0)), (20, 1));
+        assert_eq!(page_limit_and_index(Some(0), Some(7)), (1, 7));
+    }
+}